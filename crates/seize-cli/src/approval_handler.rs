@@ -0,0 +1,144 @@
+//! 承認（Human-in-the-loop）のハンドラー
+
+use colored::*;
+use seize_core::approval::{
+    ApprovalDecision, ApprovalKind, ApprovalPolicy, ApprovalRequest, Approver, FileQueueApprover,
+    PolicyApprover, AUTO_DENY_REASON,
+};
+use std::io::{BufRead, IsTerminal, Write};
+use std::sync::{Arc, Mutex};
+
+/// 既定の承認キューのディレクトリ
+pub const DEFAULT_QUEUE_DIR: &str = ".seize/approvals";
+
+/// 端末で対話的に承認を求める承認者
+pub struct TtyApprover {
+    approver: String,
+
+    /// 複数のタスクから同時に問い合わせが来ても1件ずつ尋ねる
+    lock: Mutex<()>,
+}
+
+impl TtyApprover {
+    pub fn new(approver: String) -> Self {
+        Self {
+            approver,
+            lock: Mutex::new(()),
+        }
+    }
+}
+
+impl Approver for TtyApprover {
+    fn review(&self, request: &ApprovalRequest) -> anyhow::Result<ApprovalDecision> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+
+        println!();
+        print_request(request);
+        let answer = prompt(&t!("approval.prompt"))?;
+
+        if matches!(answer.to_lowercase().as_str(), "y" | "yes") {
+            return Ok(ApprovalDecision::Approved {
+                approver: self.approver.clone(),
+            });
+        }
+
+        let reason = prompt(&t!("approval.reason_prompt"))?;
+        Ok(ApprovalDecision::Rejected {
+            approver: self.approver.clone(),
+            reason: if reason.is_empty() {
+                t!("approval.no_reason")
+            } else {
+                reason
+            },
+        })
+    }
+}
+
+fn prompt(message: &str) -> anyhow::Result<String> {
+    print!("{}", message.bright_yellow());
+    std::io::stdout().flush()?;
+
+    let mut line = String::new();
+    std::io::stdin().lock().read_line(&mut line)?;
+    Ok(line.trim().to_string())
+}
+
+fn print_request(request: &ApprovalRequest) {
+    let kind = match request.kind {
+        ApprovalKind::Plan => t!("approval.kind_plan"),
+        ApprovalKind::Task => t!("approval.kind_task"),
+        ApprovalKind::Decision => t!("approval.kind_decision"),
+    };
+    println!("{}", t!("approval.request", kind = kind, id = request.id).bright_cyan().bold());
+    println!("  {}", request.summary);
+    for line in request.details.lines() {
+        println!("    {}", line);
+    }
+}
+
+/// 却下の理由を表示言語の文言にする（判断待ちなら `None`、自動却下ポリシーのコードはカタログから）
+pub fn rejection_text(reason: Option<&str>) -> String {
+    match reason {
+        None => t!("approval.awaiting"),
+        Some(AUTO_DENY_REASON) => t!("approval.auto_denied"),
+        Some(reason) => reason.to_string(),
+    }
+}
+
+/// 承認者の名前（環境変数 USER / USERNAME）
+fn current_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "human".to_string())
+}
+
+/// `--approval` の指定から承認者を構築
+pub fn build_approver(mode: &str, queue_dir: &str) -> anyhow::Result<Arc<dyn Approver>> {
+    let approver: Arc<dyn Approver> = match mode {
+        "tty" if std::io::stdin().is_terminal() => Arc::new(TtyApprover::new(current_user())),
+        "tty" => {
+            tracing::warn!("{}", t!("approval.tty_fallback"));
+            Arc::new(PolicyApprover::new(ApprovalPolicy::AutoDeny))
+        }
+        "auto-approve" => Arc::new(PolicyApprover::new(ApprovalPolicy::AutoApprove)),
+        "auto-deny" => Arc::new(PolicyApprover::new(ApprovalPolicy::AutoDeny)),
+        "queue" => Arc::new(FileQueueApprover::open(queue_dir)?),
+        _ => {
+            anyhow::bail!(t!("approval.unknown_mode", mode = mode));
+        }
+    };
+
+    Ok(approver)
+}
+
+/// 承認キューコマンドを処理
+pub async fn handle_approvals_command(cmd: super::ApprovalCommands) -> anyhow::Result<()> {
+    match cmd {
+        super::ApprovalCommands::List { queue } => {
+            let queue_approver = FileQueueApprover::open(&queue)?;
+            let pending = queue_approver.pending()?;
+
+            println!("{}", t!("approval.pending_title", queue = queue).bright_cyan().bold());
+            println!();
+            if pending.is_empty() {
+                println!("{}", t!("approval.pending_empty"));
+            }
+            for request in &pending {
+                print_request(request);
+                println!();
+            }
+        }
+        super::ApprovalCommands::Approve { id, queue, approver } => {
+            let approver = approver.unwrap_or_else(current_user);
+            FileQueueApprover::open(&queue)?.respond(&id, &ApprovalDecision::Approved { approver })?;
+            println!("{}", t!("approval.approved", id = id).bright_green());
+        }
+        super::ApprovalCommands::Reject { id, reason, queue, approver } => {
+            let approver = approver.unwrap_or_else(current_user);
+            FileQueueApprover::open(&queue)?.respond(&id, &ApprovalDecision::Rejected { approver, reason })?;
+            println!("{}", t!("approval.rejected", id = id).bright_red());
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,127 @@
+//! CLI のメッセージカタログ
+//!
+//! メッセージは `locales/<言語>.toml` に置かれ、ビルド時に組み込まれる。
+//! 表示言語は `--lang`、なければ環境変数 `LC_ALL` / `LC_MESSAGES` / `LANG` で決まる。
+//! 英語のカタログにないキーは日本語のカタログで補う。
+
+use seize_core::Language;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+const JA: &str = include_str!("../locales/ja.toml");
+const EN: &str = include_str!("../locales/en.toml");
+
+/// メッセージカタログ
+pub struct Catalog {
+    messages: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+
+static CATALOG: OnceLock<Catalog> = OnceLock::new();
+
+impl Catalog {
+    /// 指定した言語のカタログ
+    pub fn new(language: Language) -> Self {
+        let messages = match language {
+            Language::Ja => parse(JA),
+            Language::En => parse(EN),
+        };
+        Self {
+            messages,
+            fallback: parse(JA),
+        }
+    }
+
+    /// キーに対応するメッセージ（`{name}` を `args` の値に置き換える）
+    pub fn message(&self, key: &str, args: &[(&str, String)]) -> String {
+        let template = self
+            .messages
+            .get(key)
+            .or_else(|| self.fallback.get(key))
+            .map_or(key, String::as_str);
+
+        args.iter().fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), value)
+        })
+    }
+}
+
+/// TOML の表を `<表>.<名前>` のキーに平らにする
+fn parse(source: &str) -> HashMap<String, String> {
+    let table: toml::Table = toml::from_str(source).expect("組み込みのメッセージカタログの形式が不正です");
+    let mut messages = HashMap::new();
+    for (section, entries) in table {
+        if let toml::Value::Table(entries) = entries {
+            for (name, value) in entries {
+                if let toml::Value::String(text) = value {
+                    messages.insert(format!("{}.{}", section, name), text);
+                }
+            }
+        }
+    }
+    messages
+}
+
+/// 表示言語を決める（`--lang` → `LC_ALL` → `LC_MESSAGES` → `LANG` → 日本語）
+pub fn select_language(lang: Option<&str>) -> anyhow::Result<Language> {
+    if let Some(code) = lang {
+        return Language::from_code(code).ok_or_else(|| anyhow::anyhow!("Unknown language: {}", code));
+    }
+
+    Ok(["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| Language::from_code(&value))
+        .unwrap_or_default())
+}
+
+/// 表示言語を設定（最初の1回だけ有効）
+pub fn init(language: Language) {
+    let _ = CATALOG.set(Catalog::new(language));
+}
+
+/// 現在のカタログ（未設定なら日本語）
+pub fn catalog() -> &'static Catalog {
+    CATALOG.get_or_init(|| Catalog::new(Language::Ja))
+}
+
+/// カタログのメッセージを得る
+///
+/// ```ignore
+/// t!("run.iterations", count = result.iterations)
+/// ```
+macro_rules! t {
+    ($key:expr) => {
+        $crate::i18n::catalog().message($key, &[])
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::catalog().message($key, &[$((stringify!($name), $value.to_string())),+])
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalogs_have_the_same_keys() {
+        let mut ja: Vec<_> = parse(JA).into_keys().collect();
+        let mut en: Vec<_> = parse(EN).into_keys().collect();
+        ja.sort();
+        en.sort();
+        assert_eq!(ja, en);
+    }
+
+    #[test]
+    fn test_message_arguments() {
+        let catalog = Catalog::new(Language::En);
+        assert_eq!(
+            catalog.message("run.iterations", &[("count", "3".to_string())]),
+            "  Iterations: 3"
+        );
+        assert_eq!(catalog.message("no.such.key", &[]), "no.such.key");
+        assert_eq!(select_language(Some("en_US.UTF-8")).unwrap(), Language::En);
+        assert!(select_language(Some("fr")).is_err());
+    }
+}
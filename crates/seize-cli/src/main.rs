@@ -1,12 +1,28 @@
 //! # Seize CLI - 統一エージェントのコマンドラインインターフェース
 
+#[macro_use]
+mod i18n;
+
+mod approval_handler;
 mod requirements_handler;
+mod world_handler;
 
-use clap::{Parser, Subcommand};
+use approval_handler::{build_approver, handle_approvals_command, rejection_text, DEFAULT_QUEUE_DIR};
+use clap::{Args, Parser, Subcommand};
 use colored::*;
-use seize_core::{UnifiedAgent, World};
-use tracing_subscriber;
+use seize_core::approval::RejectionPolicy;
+use seize_core::command::ExecutionStrategy;
+use seize_core::convergence::{self, AnyOf, Budget};
+use seize_core::filesystem::FileChange;
+use seize_core::model::{HeuristicModel, OpenAiCompatibleModel, ReplayModel};
+use seize_core::store::WorldStore;
+use seize_core::world::DecisionStatus;
+use seize_core::{Charter, CharterEngine, HaltReason, LanguageModel, RecipeBook, ResourceBudget, UnifiedAgent, World};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 use requirements_handler::handle_requirements_command;
+use world_handler::handle_world_command;
 
 #[derive(Parser)]
 #[command(name = "seize")]
@@ -20,24 +36,116 @@ struct Cli {
     /// ログレベル設定 (trace, debug, info, warn, error)
     #[arg(short, long, default_value = "info")]
     log_level: String,
+
+    /// 組織憲章ファイル（TOML / YAML / JSON、省略時は組み込みの憲章）
+    #[arg(long, global = true)]
+    charter: Option<String>,
+
+    /// 分解レシピファイル（省略時は .seize/recipes.toml があれば使う。組み込みのレシピに重ねる）
+    #[arg(long, global = true)]
+    recipes: Option<String>,
+
+    /// 表示言語 (ja, en、省略時は LANG から判断)
+    #[arg(long, global = true)]
+    lang: Option<String>,
+}
+
+/// `seize run` の引数
+#[derive(Args)]
+struct RunArgs {
+    /// 入力テキスト
+    #[arg(short, long)]
+    input: String,
+
+    /// 最大反復回数
+    #[arg(short, long, default_value = "10")]
+    max_iterations: usize,
+
+    /// タスクをサブタスクに細分化する深さ（0 なら細分化しない）
+    #[arg(long, default_value = "0")]
+    decomposition_depth: usize,
+
+    /// θ₁ でタスクに添える関連知識（過去の学習・意思決定）の件数（0 なら添えない）
+    #[arg(long, default_value = "5")]
+    recall: usize,
+
+    /// 実行戦略 (sequential, parallel, adaptive、省略時は目標の優先度から決める)
+    #[arg(long)]
+    strategy: Option<String>,
+
+    /// 収束閾値
+    #[arg(short, long, default_value = "0.8")]
+    threshold: f64,
+
+    /// 推論バックエンド (heuristic, openai, replay)
+    #[arg(long, default_value = "heuristic")]
+    backend: String,
+
+    /// リプレイファイル（--backend replay のとき使用）
+    #[arg(long)]
+    replay_file: Option<String>,
+
+    /// 世界に取り込む作業ディレクトリ（.gitignore を尊重）
+    #[arg(long)]
+    workspace: Option<String>,
+
+    /// 実行後にファイルの変更をまとめて作業ディレクトリに書き戻す（省略時はドライランで、ディスクには書き込まない）
+    #[arg(long, requires = "workspace")]
+    apply: bool,
+
+    /// 世界ストアのディレクトリ（HEAD の世界から開始する）
+    #[arg(long)]
+    world: Option<String>,
+
+    /// 最終的な世界を世界ストアに保存する
+    #[arg(long)]
+    save: bool,
+
+    /// トークン使用量の上限（到達したら反復を止める）
+    #[arg(long)]
+    max_tokens: Option<u64>,
+
+    /// 実行時間の上限（秒、到達したら反復を止める）
+    #[arg(long)]
+    max_seconds: Option<u64>,
+
+    /// 1回の反復で使える実行時間（秒、θ₃ でタスクに配分する）
+    #[arg(long)]
+    plan_seconds: Option<u64>,
+
+    /// 1回の反復でタスクが使えるトークン数
+    #[arg(long)]
+    plan_tokens: Option<u64>,
+
+    /// 1回の反復で許すファイル書き込みの回数
+    #[arg(long)]
+    plan_file_writes: Option<u32>,
+
+    /// 1回の反復で許すプロセス起動の回数
+    #[arg(long)]
+    plan_processes: Option<u32>,
+
+    /// 意思決定・破壊的なタスクの承認方法 (tty, auto-approve, auto-deny, queue)
+    #[arg(long, default_value = "tty")]
+    approval: String,
+
+    /// 承認キューのディレクトリ（--approval queue のとき使用）
+    #[arg(long, default_value = DEFAULT_QUEUE_DIR)]
+    approval_queue: String,
+
+    /// 却下・判断待ちのとき反復を止めずに再計画する
+    #[arg(long)]
+    replan_on_rejection: bool,
+
+    /// 反復ごとの実行トレースを書き出すJSONファイル
+    #[arg(long)]
+    trace: Option<String>,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// エージェントを起動して入力を処理
-    Run {
-        /// 入力テキスト
-        #[arg(short, long)]
-        input: String,
-
-        /// 最大反復回数
-        #[arg(short, long, default_value = "10")]
-        max_iterations: usize,
-
-        /// 収束閾値
-        #[arg(short, long, default_value = "0.8")]
-        threshold: f64,
-    },
+    Run(Box<RunArgs>),
 
     /// 世界の初期状態を表示
     Init {
@@ -55,6 +163,76 @@ enum Commands {
     /// 要求工学（Requirements Engineering）
     #[command(subcommand)]
     Requirements(RequirementsCommands),
+
+    /// 世界ストア（保存された世界の履歴）
+    #[command(subcommand)]
+    World(WorldCommands),
+
+    /// 承認キュー（判断待ちのリクエスト）
+    #[command(subcommand)]
+    Approvals(ApprovalCommands),
+}
+
+#[derive(Subcommand)]
+enum ApprovalCommands {
+    /// 判断待ちのリクエストを表示
+    List {
+        /// 承認キューのディレクトリ
+        #[arg(long, default_value = DEFAULT_QUEUE_DIR)]
+        queue: String,
+    },
+
+    /// リクエストを承認
+    Approve {
+        /// リクエストID
+        id: String,
+
+        /// 承認キューのディレクトリ
+        #[arg(long, default_value = DEFAULT_QUEUE_DIR)]
+        queue: String,
+
+        /// 承認者名（省略時は USER）
+        #[arg(long)]
+        approver: Option<String>,
+    },
+
+    /// リクエストを却下
+    Reject {
+        /// リクエストID
+        id: String,
+
+        /// 却下の理由
+        #[arg(short, long)]
+        reason: String,
+
+        /// 承認キューのディレクトリ
+        #[arg(long, default_value = DEFAULT_QUEUE_DIR)]
+        queue: String,
+
+        /// 承認者名（省略時は USER）
+        #[arg(long)]
+        approver: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum WorldCommands {
+    /// 保存された世界の一覧を表示
+    Log {
+        /// 世界ストアのディレクトリ
+        #[arg(long, default_value = WorldStore::DEFAULT_DIR)]
+        world: String,
+    },
+
+    /// HEAD を指定したバージョンに移動
+    Checkout {
+        /// バージョン番号
+        version: usize,
+
+        /// 世界ストアのディレクトリ
+        #[arg(long, default_value = WorldStore::DEFAULT_DIR)]
+        world: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -135,16 +313,20 @@ async fn main() -> anyhow::Result<()> {
         .with_target(false)
         .init();
 
+    i18n::init(i18n::select_language(cli.lang.as_deref())?);
+
+    let charter = Arc::new(CharterEngine::new(match &cli.charter {
+        Some(path) => Charter::load(path)?,
+        None => Charter::builtin(),
+    }));
+
     match cli.command {
-        Commands::Run {
-            input,
-            max_iterations,
-            threshold,
-        } => {
-            run_agent(&input, max_iterations, threshold).await?;
+        Commands::Run(args) => {
+            let recipes = load_recipes(cli.recipes.as_deref())?;
+            run_agent(&args, charter, recipes).await?;
         }
         Commands::Init { format } => {
-            init_world(&format)?;
+            init_world(&format, &charter)?;
         }
         Commands::Status => {
             show_status()?;
@@ -153,59 +335,285 @@ async fn main() -> anyhow::Result<()> {
             show_formula()?;
         }
         Commands::Requirements(req_cmd) => {
-            handle_requirements_command(req_cmd).await?;
+            handle_requirements_command(req_cmd, charter).await?;
+        }
+        Commands::World(world_cmd) => {
+            handle_world_command(world_cmd).await?;
+        }
+        Commands::Approvals(approval_cmd) => {
+            handle_approvals_command(approval_cmd).await?;
         }
     }
 
     Ok(())
 }
 
+/// 分解レシピを読み込む（指定がなければ .seize/recipes.toml、それもなければ組み込みのみ）
+fn load_recipes(path: Option<&str>) -> anyhow::Result<Arc<RecipeBook>> {
+    let path = match path {
+        Some(path) => Some(PathBuf::from(path)),
+        None => Some(PathBuf::from(".seize/recipes.toml")).filter(|p| p.exists()),
+    };
+
+    let builtin = RecipeBook::builtin();
+    Ok(Arc::new(match path {
+        Some(path) => builtin.with_overrides(RecipeBook::load(path)?),
+        None => builtin,
+    }))
+}
+
+/// 推論バックエンドを構築
+fn build_model(backend: &str, replay_file: Option<&str>) -> anyhow::Result<Arc<dyn LanguageModel>> {
+    let model: Arc<dyn LanguageModel> = match backend {
+        "heuristic" => Arc::new(HeuristicModel),
+        "openai" => Arc::new(OpenAiCompatibleModel::from_env()?),
+        "replay" => {
+            let path = replay_file
+                .ok_or_else(|| anyhow::anyhow!(t!("run.replay_file_required")))?;
+            Arc::new(ReplayModel::load(path)?)
+        }
+        _ => {
+            anyhow::bail!(t!("run.unknown_backend", backend = backend));
+        }
+    };
+
+    Ok(model)
+}
+
+fn parse_strategy(strategy: &str) -> anyhow::Result<ExecutionStrategy> {
+    match strategy {
+        "sequential" => Ok(ExecutionStrategy::Sequential),
+        "parallel" => Ok(ExecutionStrategy::Parallel),
+        "adaptive" => Ok(ExecutionStrategy::Adaptive),
+        _ => anyhow::bail!(t!("run.unknown_strategy", strategy = strategy)),
+    }
+}
+
 /// エージェントを実行
-async fn run_agent(input: &str, max_iterations: usize, threshold: f64) -> anyhow::Result<()> {
-    println!("{}", "🎯 統一エージェント起動".bright_cyan().bold());
+async fn run_agent(args: &RunArgs, charter: Arc<CharterEngine>, recipes: Arc<RecipeBook>) -> anyhow::Result<()> {
+    println!("{}", t!("run.title").bright_cyan().bold());
     println!();
 
-    let agent = UnifiedAgent::new(max_iterations, threshold);
-    let world = World::new();
+    let input = args.input.as_str();
+    let model = build_model(&args.backend, args.replay_file.as_deref())?;
+    let approver = build_approver(&args.approval, &args.approval_queue)?;
+    let rejection_policy = if args.replan_on_rejection {
+        RejectionPolicy::Replan
+    } else {
+        RejectionPolicy::Halt
+    };
+    let mut agent = UnifiedAgent::new(args.max_iterations, args.threshold)
+        .with_model(model)
+        .with_charter(charter)
+        .with_recipes(recipes)
+        .with_decomposition_depth(args.decomposition_depth)
+        .with_recall(args.recall)
+        .with_approver(approver)
+        .with_rejection_policy(rejection_policy);
+    let plan_budget = ResourceBudget {
+        wall_time_ms: args.plan_seconds.map(|seconds| seconds * 1000),
+        tokens: args.plan_tokens,
+        file_writes: args.plan_file_writes,
+        processes: args.plan_processes,
+    };
+    if let Some(strategy) = &args.strategy {
+        agent = agent.with_strategy(parse_strategy(strategy)?);
+    }
+    if !plan_budget.is_unlimited() {
+        agent = agent.with_plan_budget(plan_budget);
+    }
+    if args.max_tokens.is_some() || args.max_seconds.is_some() {
+        let budget = Budget {
+            max_tokens: args.max_tokens,
+            max_duration: args.max_seconds.map(Duration::from_secs),
+        };
+        agent = agent.with_convergence(Box::new(AnyOf(vec![
+            Box::new(convergence::default_criterion(args.threshold)),
+            Box::new(budget),
+        ])));
+    }
+
+    // 世界ストアを使う場合は HEAD の世界から開始する
+    let store = if args.world.is_some() || args.save {
+        Some(WorldStore::open(args.world.as_deref().unwrap_or(WorldStore::DEFAULT_DIR))?)
+    } else {
+        None
+    };
+    let mut world = match &store {
+        Some(store) => store.load_head()?.unwrap_or_default(),
+        None => World::new(),
+    };
+    if let Some(store) = &store {
+        println!("{}", t!("run.world_store").bright_green());
+        println!("  {} (World v{})", store.root().display(), world.version);
+        println!();
+    }
+
+    let workspace = args.workspace.as_deref();
+    if let Some(dir) = workspace {
+        world.load_directory(dir)?;
+        println!("{}", t!("run.workspace").bright_green());
+        println!("{}", t!("run.workspace_files", dir = dir, count = world.filesystem.len()));
+        println!();
+    }
 
-    println!("{}", "📝 入力:".bright_green());
+    println!("{}", t!("run.input").bright_green());
     println!("  {}", input);
     println!();
 
     let result = agent.run(input, world)?;
 
     println!();
-    println!("{}", "✅ 実行完了".bright_green().bold());
-    println!("  反復回数: {}", result.iterations);
-    println!("  収束: {}", if result.converged { "✓" } else { "✗" });
-    println!("  初期世界: v{}", result.initial_world.version);
-    println!("  最終世界: v{}", result.final_world.version);
+    println!("{}", t!("run.completed").bright_green().bold());
+    println!("{}", t!("run.iterations", count = result.iterations));
+    println!("{}", t!("run.converged", mark = if result.converged { "✓" } else { "✗" }));
+    if let Some(criterion) = &result.converged_by {
+        println!("{}", t!("run.converged_by", criterion = criterion));
+    }
+    if let Some(reason) = &result.halted {
+        println!("  {}", t!("run.halted", reason = halt_text(reason)).bright_red());
+    }
+    let cost = &result.cost;
+    println!(
+        "{}",
+        t!(
+            "run.cost",
+            wall_time_ms = cost.usage.wall_time_ms,
+            tokens = cost.usage.tokens,
+            file_writes = cost.usage.file_writes,
+            processes = cost.usage.processes,
+            model_tokens = cost.model_tokens
+        )
+    );
+    if let Some(path) = &args.trace {
+        let trace = serde_json::json!({
+            "input": input,
+            "iterations": result.iterations,
+            "converged": result.converged,
+            "converged_by": result.converged_by,
+            "halted": result.halted,
+            "trace": result.trace,
+            "cost": result.cost,
+        });
+        std::fs::write(path, serde_json::to_string_pretty(&trace)?)
+            .map_err(|e| anyhow::anyhow!(t!("run.trace_write_failed", path = path, error = e)))?;
+        println!("{}", t!("run.trace_written", path = path));
+    }
+    let scores: Vec<String> = result.scores.iter().map(|s| format!("{:.2}", s)).collect();
+    println!("{}", t!("run.scores", scores = scores.join(" → ")));
+    println!("{}", t!("run.initial_world", version = result.initial_world.version));
+    println!("{}", t!("run.final_world", version = result.final_world.version));
     println!();
 
-    println!("{}", "📊 実行履歴:".bright_yellow());
+    println!("{}", t!("run.history").bright_yellow());
     for (i, history) in result.final_world.context.history.iter().enumerate() {
         println!("  {}. {}", i + 1, history);
     }
     println!();
 
-    println!("{}", "🎓 学習内容:".bright_magenta());
+    println!("{}", t!("run.learnings").bright_magenta());
     for (i, learning) in result.final_world.knowledge.learnings.iter().enumerate() {
-        println!("  {}. {} (信頼度: {:.2})", i + 1, learning.content, learning.confidence);
+        println!(
+            "{}",
+            t!(
+                "run.learning",
+                index = i + 1,
+                content = learning.content,
+                confidence = format!("{:.2}", learning.confidence)
+            )
+        );
     }
     println!();
 
-    println!("{}", "📋 意思決定記録:".bright_blue());
+    println!("{}", t!("run.decisions").bright_blue());
     for (i, decision) in result.final_world.knowledge.decisions.iter().enumerate() {
         println!("  {}. {}", i + 1, decision.purpose);
-        println!("     承認者: {}", decision.approver);
+        match decision.status {
+            DecisionStatus::Approved => {
+                println!("{}", t!("run.decision_approved", approver = decision.approver))
+            }
+            DecisionStatus::Rejected => println!(
+                "{}",
+                t!(
+                    "run.decision_rejected",
+                    approver = decision.approver,
+                    reason = rejection_text(decision.rejection_reason.as_deref())
+                )
+            ),
+            DecisionStatus::Pending => println!("{}", t!("run.decision_pending")),
+        }
+    }
+
+    if let Some(dir) = workspace {
+        println!();
+        let diff = result.initial_world.diff(&result.final_world);
+        let report = diff.apply(dir, !args.apply)?;
+
+        let title = if report.dry_run {
+            t!("run.changes_dry_run")
+        } else {
+            t!("run.changes")
+        };
+        println!("{}", title.bright_cyan());
+        for change in &diff.changes {
+            let path = change.path();
+            let mark = match change {
+                FileChange::Added { .. } => "+",
+                FileChange::Modified { .. } => "~",
+                FileChange::Removed { .. } => "-",
+            };
+            let state = if report.skipped.iter().any(|p| p == path) {
+                t!("run.change_skipped")
+            } else {
+                String::new()
+            };
+            println!("  {} {}{}", mark, path, state);
+        }
+        if diff.is_empty() {
+            println!("{}", t!("run.no_changes"));
+        }
+    }
+
+    if let (Some(store), true) = (&store, args.save) {
+        let mut final_world = result.final_world;
+        let version = store.save(&mut final_world)?;
+        println!();
+        println!(
+            "{}",
+            t!("run.saved", version = version, path = store.root().display()).bright_green()
+        );
     }
 
     Ok(())
 }
 
+/// 反復を止めた理由を表示言語の文言にする
+fn halt_text(reason: &HaltReason) -> String {
+    match reason {
+        HaltReason::RolledBack { phase, world_version, error } => t!(
+            "run.halted_rolled_back",
+            phase = phase.label(),
+            version = world_version,
+            error = error
+        ),
+        HaltReason::NotApproved(refusals) => refusals
+            .iter()
+            .map(|refusal| {
+                t!(
+                    "run.refusal",
+                    purpose = refusal.purpose,
+                    reason = rejection_text(refusal.reason.as_deref())
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
 /// 世界の初期状態を表示
-fn init_world(format: &str) -> anyhow::Result<()> {
-    let world = World::new();
+fn init_world(format: &str, charter: &CharterEngine) -> anyhow::Result<()> {
+    let mut world = World::new();
+    world.knowledge.principles = charter.charter().principles.clone();
 
     match format {
         "json" => {
@@ -213,18 +621,18 @@ fn init_world(format: &str) -> anyhow::Result<()> {
             println!("{}", json);
         }
         "pretty" => {
-            println!("{}", "🌍 世界の初期状態".bright_cyan().bold());
+            println!("{}", t!("init.title").bright_cyan().bold());
             println!();
-            println!("バージョン: v{}", world.version);
-            println!("作業ディレクトリ: {}", world.context.working_directory);
+            println!("{}", t!("init.version", version = world.version));
+            println!("{}", t!("init.working_directory", dir = world.context.working_directory));
             println!();
-            println!("{}", "📚 組織の原則:".bright_green());
+            println!("{}", t!("init.principles", name = charter.charter().name).bright_green());
             for principle in &world.knowledge.principles {
                 println!("  • {} - {}", principle.name, principle.description);
             }
         }
         _ => {
-            anyhow::bail!("Unknown format: {}", format);
+            anyhow::bail!(t!("init.unknown_format", format = format));
         }
     }
 
@@ -235,7 +643,7 @@ fn init_world(format: &str) -> anyhow::Result<()> {
 fn show_status() -> anyhow::Result<()> {
     let agent = UnifiedAgent::default();
 
-    println!("{}", "📊 エージェントステータス".bright_cyan().bold());
+    println!("{}", t!("status.title").bright_cyan().bold());
     println!();
     println!("{}", agent.status());
 
@@ -244,29 +652,26 @@ fn show_status() -> anyhow::Result<()> {
 
 /// 統一エージェント方程式を表示
 fn show_formula() -> anyhow::Result<()> {
-    println!("{}", "🎯 統一エージェント方程式".bright_cyan().bold());
+    println!("{}", t!("formula.title").bright_cyan().bold());
     println!();
     println!("{}", "𝔸(Input, World₀) = lim_{n→∞} [∫₀ⁿ (Θ ◦ 𝒞 ◦ ℐ)(t) dt] = World_∞".bright_yellow());
     println!();
-    println!("{}", "Where:".bright_green());
-    println!("  ℐ : Intent Resolution (意図解決)");
+    println!("{}", t!("formula.where").bright_green());
+    println!("{}", t!("formula.intent"));
     println!("      Input → Fixed Goal");
     println!();
-    println!("  𝒞 : Command Stack (コマンドスタック)");
+    println!("{}", t!("formula.command"));
     println!("      Goal → Execution Plan");
     println!("      𝒞 = C₃ ◦ C₂ ◦ C₁");
     println!();
-    println!("  Θ : World Transformation (世界変換)");
+    println!("{}", t!("formula.transformation"));
     println!("      World_t → World_{{t+1}}");
     println!("      Θ = θ₆ ◦ θ₅ ◦ θ₄ ◦ θ₃ ◦ θ₂ ◦ θ₁");
     println!();
-    println!("{}", "変換フェーズ:".bright_magenta());
-    println!("  θ₁: Understand  - 理解");
-    println!("  θ₂: Generate    - 生成");
-    println!("  θ₃: Allocate    - 配分");
-    println!("  θ₄: Execute     - 実行");
-    println!("  θ₅: Integrate   - 統合");
-    println!("  θ₆: Learn       - 学習");
+    println!("{}", t!("formula.phases").bright_magenta());
+    for phase in ["understand", "generate", "allocate", "execute", "integrate", "learn"] {
+        println!("{}", t!(&format!("formula.{}", phase)));
+    }
 
     Ok(())
 }
@@ -1,11 +1,17 @@
 //! 要求工学コマンドのハンドラー
 
 use colored::*;
-use seize_core::{RequirementsEngineer, RequirementsSpecification};
+use seize_core::charter::{CharterViolation, Severity};
+use seize_core::requirements::Finding;
+use seize_core::{Charter, CharterEngine, RequirementsEngineer};
 use std::fs;
+use std::sync::Arc;
 
 /// 要求工学コマンドを処理
-pub async fn handle_requirements_command(cmd: super::RequirementsCommands) -> anyhow::Result<()> {
+pub async fn handle_requirements_command(
+    cmd: super::RequirementsCommands,
+    charter: Arc<CharterEngine>,
+) -> anyhow::Result<()> {
     match cmd {
         super::RequirementsCommands::Elicit {
             input,
@@ -13,10 +19,10 @@ pub async fn handle_requirements_command(cmd: super::RequirementsCommands) -> an
             project,
             output,
         } => {
-            elicit_requirements(&input, &stakeholder, &project, output.as_deref()).await?;
+            elicit_requirements(&input, &stakeholder, &project, output.as_deref(), charter).await?;
         }
         super::RequirementsCommands::Analyze { spec, report } => {
-            analyze_requirements(&spec, report.as_deref()).await?;
+            analyze_requirements(&spec, report.as_deref(), charter).await?;
         }
         super::RequirementsCommands::Trace {
             spec,
@@ -42,30 +48,31 @@ async fn elicit_requirements(
     stakeholder: &str,
     project: &str,
     output_file: Option<&str>,
+    charter: Arc<CharterEngine>,
 ) -> anyhow::Result<()> {
-    println!("{}", "📝 要求抽出 (Requirements Elicitation)".bright_cyan().bold());
+    println!("{}", t!("requirements.elicit_title").bright_cyan().bold());
     println!();
 
     // 入力ファイルを読み込み
     let input_text = fs::read_to_string(input_file)?;
-    println!("入力ファイル: {}", input_file);
-    println!("ステークホルダー: {}", stakeholder.bright_yellow());
-    println!("プロジェクト: {}", project.bright_yellow());
+    println!("{}", t!("requirements.input_file", path = input_file));
+    println!("{}", t!("requirements.stakeholder", stakeholder = stakeholder.bright_yellow()));
+    println!("{}", t!("common.project", project = project.bright_yellow()));
     println!();
 
     // 要求エンジニアを作成
-    let mut engineer = RequirementsEngineer::new(project.to_string(), true);
+    let mut engineer = RequirementsEngineer::new(project.to_string(), true).with_charter(charter);
 
     // 要求を抽出
-    println!("{}", "要求を抽出中...".bright_green());
+    println!("{}", t!("requirements.eliciting").bright_green());
     let requirements = engineer.elicit_requirements(&input_text, stakeholder)?;
 
-    println!("{}", format!("✅ {}個の要求を抽出しました", requirements.len()).bright_green());
+    println!("{}", t!("requirements.elicited", count = requirements.len()).bright_green());
     println!();
 
     // 抽出した要求を表示
     for (i, req) in requirements.iter().enumerate() {
-        println!("{}", format!("{}. {} [{}]", i + 1, req.id, format!("{:?}", req.priority)).bright_white().bold());
+        println!("{}", format!("{}. {} [{:?}]", i + 1, req.id, req.priority).bright_white().bold());
         println!("   {}", req.description);
         println!();
     }
@@ -76,76 +83,114 @@ async fn elicit_requirements(
     }
 
     // 出力
-    let spec = engineer.export_specification();
-    let json = serde_json::to_string_pretty(spec)?;
-
     if let Some(output) = output_file {
-        fs::write(output, &json)?;
-        println!("{}", format!("📄 要求仕様書を出力: {}", output).bright_green());
+        engineer.save(output)?;
+        println!("{}", t!("requirements.spec_written", path = output).bright_green());
     } else {
-        println!("{}", "📄 要求仕様書 (JSON):".bright_blue());
+        let json = serde_json::to_string_pretty(engineer.export_specification())?;
+        println!("{}", t!("requirements.spec_json").bright_blue());
         println!("{}", json);
     }
 
     Ok(())
 }
 
+/// 分析で見つかった問題・推奨事項を表示言語の文言にする
+fn finding_text(finding: &Finding) -> String {
+    let key = match finding {
+        Finding::DescriptionTooShort => "findings.description_too_short",
+        Finding::MissingAcceptanceCriteria => "findings.missing_acceptance_criteria",
+        Finding::AddAcceptanceCriteria => "findings.add_acceptance_criteria",
+        Finding::MissingTraceability => "findings.missing_traceability",
+        Finding::MissingStakeholders => "findings.missing_stakeholders",
+        Finding::Charter(violation) => {
+            let key = match violation.severity {
+                Severity::Error => "findings.charter_error",
+                Severity::Warn => "findings.charter_warn",
+                Severity::Info => "findings.charter_info",
+            };
+            return t!(key, rule = violation.rule_id, message = rule_message(violation));
+        }
+    };
+    t!(key)
+}
+
+/// 憲章のルールのメッセージ（組み込みの憲章のルールのままなら表示言語のカタログから）
+fn rule_message(violation: &CharterViolation) -> String {
+    let builtin = Charter::default()
+        .rules
+        .iter()
+        .any(|rule| rule.id == violation.rule_id && rule.message == violation.message);
+    let key = format!("charter_rules.{}", violation.rule_id);
+    let message = t!(&key);
+    if builtin && message != key {
+        message
+    } else {
+        violation.message.clone()
+    }
+}
+
 /// 要求分析
-async fn analyze_requirements(spec_file: &str, report_file: Option<&str>) -> anyhow::Result<()> {
-    println!("{}", "🔍 要求分析 (Requirements Analysis)".bright_cyan().bold());
+async fn analyze_requirements(
+    spec_file: &str,
+    report_file: Option<&str>,
+    charter: Arc<CharterEngine>,
+) -> anyhow::Result<()> {
+    println!("{}", t!("requirements.analyze_title").bright_cyan().bold());
     println!();
 
     // 要求仕様書を読み込み
-    let spec_json = fs::read_to_string(spec_file)?;
-    let spec: RequirementsSpecification = serde_json::from_str(&spec_json)?;
+    let engineer = RequirementsEngineer::load(spec_file, true)?.with_charter(charter);
+    let spec = engineer.export_specification();
 
-    println!("プロジェクト: {}", spec.project_name.bright_yellow());
-    println!("要求数: {}", spec.requirements.len());
+    println!("{}", t!("common.project", project = spec.project_name.bright_yellow()));
+    println!("{}", t!("requirements.count", count = spec.requirements.len()));
     println!();
 
-    // 要求エンジニアを作成
-    let engineer = RequirementsEngineer::new(spec.project_name.clone(), true);
-
     // 各要求を分析
-    println!("{}", "要求を分析中...".bright_green());
+    println!("{}", t!("requirements.analyzing").bright_green());
     let mut report_lines = Vec::new();
-    report_lines.push(format!("# 要求分析レポート - {}\n", spec.project_name));
-    report_lines.push(format!("バージョン: {}\n", spec.version));
-    report_lines.push(format!("要求数: {}\n", spec.requirements.len()));
+    report_lines.push(format!("{}\n", t!("report.title", project = spec.project_name)));
+    report_lines.push(format!("{}\n", t!("report.version", version = spec.version)));
+    report_lines.push(format!("{}\n", t!("report.count", count = spec.requirements.len())));
     report_lines.push("\n---\n\n".to_string());
 
     let mut valid_count = 0;
     let mut total_issues = 0;
 
-    for req in &spec.requirements {
-        let analysis = engineer.analyze_requirement(req)?;
+    for (req, analysis) in spec.requirements.iter().zip(engineer.analyze_all()?) {
 
         if analysis.is_valid {
             valid_count += 1;
-            println!("{}", format!("✅ {} - 有効", req.id).bright_green());
+            println!("{}", t!("requirements.valid", id = req.id).bright_green());
         } else {
-            println!("{}", format!("⚠️  {} - 問題あり", req.id).bright_yellow());
+            println!("{}", t!("requirements.invalid", id = req.id).bright_yellow());
             for issue in &analysis.issues {
-                println!("    • {}", issue.bright_red());
+                println!("    • {}", finding_text(issue).bright_red());
                 total_issues += 1;
             }
         }
 
         // レポートに追加
         report_lines.push(format!("## {}: {}\n", req.id, req.description));
-        report_lines.push(format!("**ステータス**: {}\n", if analysis.is_valid { "✅ 有効" } else { "⚠️ 問題あり" }));
+        let status = if analysis.is_valid {
+            t!("report.status_valid")
+        } else {
+            t!("report.status_invalid")
+        };
+        report_lines.push(format!("{}\n", t!("report.status", status = status)));
 
         if !analysis.issues.is_empty() {
-            report_lines.push("\n**問題点**:\n".to_string());
+            report_lines.push(format!("\n{}\n", t!("report.issues")));
             for issue in &analysis.issues {
-                report_lines.push(format!("- {}\n", issue));
+                report_lines.push(format!("- {}\n", finding_text(issue)));
             }
         }
 
         if !analysis.recommendations.is_empty() {
-            report_lines.push("\n**推奨事項**:\n".to_string());
+            report_lines.push(format!("\n{}\n", t!("report.recommendations")));
             for rec in &analysis.recommendations {
-                report_lines.push(format!("- {}\n", rec));
+                report_lines.push(format!("- {}\n", finding_text(rec)));
             }
         }
 
@@ -153,9 +198,9 @@ async fn analyze_requirements(spec_file: &str, report_file: Option<&str>) -> any
     }
 
     println!();
-    println!("{}", "📊 分析結果".bright_cyan().bold());
-    println!("有効な要求: {}/{}", valid_count, spec.requirements.len());
-    println!("問題の総数: {}", total_issues);
+    println!("{}", t!("requirements.summary_title").bright_cyan().bold());
+    println!("{}", t!("requirements.summary_valid", valid = valid_count, total = spec.requirements.len()));
+    println!("{}", t!("requirements.summary_issues", count = total_issues));
     println!();
 
     // レポート出力
@@ -163,9 +208,9 @@ async fn analyze_requirements(spec_file: &str, report_file: Option<&str>) -> any
 
     if let Some(output) = report_file {
         fs::write(output, &report_text)?;
-        println!("{}", format!("📄 レポートを出力: {}", output).bright_green());
+        println!("{}", t!("requirements.report_written", path = output).bright_green());
     } else {
-        println!("{}", "📄 分析レポート:".bright_blue());
+        println!("{}", t!("requirements.report_title").bright_blue());
         println!("{}", report_text);
     }
 
@@ -178,18 +223,16 @@ async fn generate_traceability(
     format: &str,
     output_file: Option<&str>,
 ) -> anyhow::Result<()> {
-    println!("{}", "🔗 トレーサビリティマトリクス生成".bright_cyan().bold());
+    println!("{}", t!("requirements.trace_title").bright_cyan().bold());
     println!();
 
     // 要求仕様書を読み込み
-    let spec_json = fs::read_to_string(spec_file)?;
-    let spec: RequirementsSpecification = serde_json::from_str(&spec_json)?;
-
-    let engineer = RequirementsEngineer::new(spec.project_name.clone(), true);
+    let engineer = RequirementsEngineer::load(spec_file, true)?;
+    let spec = engineer.export_specification();
     let matrix = engineer.analyze_traceability();
 
-    println!("プロジェクト: {}", spec.project_name.bright_yellow());
-    println!("トレース可能な要求: {}", matrix.requirements.len());
+    println!("{}", t!("common.project", project = spec.project_name.bright_yellow()));
+    println!("{}", t!("requirements.traceable", count = matrix.requirements.len()));
     println!();
 
     let output = match format {
@@ -198,8 +241,8 @@ async fn generate_traceability(
         }
         "markdown" => {
             let mut lines = Vec::new();
-            lines.push(format!("# トレーサビリティマトリクス - {}\n", spec.project_name));
-            lines.push("\n| 要求ID | 親要求 | 子要求 | テストケース |\n".to_string());
+            lines.push(format!("{}\n", t!("matrix.title", project = spec.project_name)));
+            lines.push(format!("\n{}\n", t!("matrix.header")));
             lines.push("|--------|--------|--------|-------------|\n".to_string());
 
             for (req_id, entry) in &matrix.requirements {
@@ -227,15 +270,15 @@ async fn generate_traceability(
             lines.join("")
         }
         _ => {
-            anyhow::bail!("Unsupported format: {}", format);
+            anyhow::bail!(t!("requirements.unsupported_format", format = format));
         }
     };
 
     if let Some(file) = output_file {
         fs::write(file, &output)?;
-        println!("{}", format!("📄 トレーサビリティマトリクスを出力: {}", file).bright_green());
+        println!("{}", t!("requirements.matrix_written", path = file).bright_green());
     } else {
-        println!("{}", "📄 トレーサビリティマトリクス:".bright_blue());
+        println!("{}", t!("requirements.matrix_title").bright_blue());
         println!("{}", output);
     }
 
@@ -244,11 +287,11 @@ async fn generate_traceability(
 
 /// 要求プロジェクトの初期化
 async fn init_requirements_project(project: &str, output_dir: &str) -> anyhow::Result<()> {
-    println!("{}", "🚀 要求プロジェクト初期化".bright_cyan().bold());
+    println!("{}", t!("requirements.init_title").bright_cyan().bold());
     println!();
 
-    println!("プロジェクト名: {}", project.bright_yellow());
-    println!("出力ディレクトリ: {}", output_dir.bright_yellow());
+    println!("{}", t!("requirements.project_name", project = project.bright_yellow()));
+    println!("{}", t!("requirements.output_dir", dir = output_dir.bright_yellow()));
     println!();
 
     // ディレクトリ構造を作成
@@ -259,37 +302,18 @@ async fn init_requirements_project(project: &str, output_dir: &str) -> anyhow::R
     fs::create_dir_all(format!("{}/analysis", project_dir))?;
 
     // テンプレートファイルを作成
-    let readme = format!(
-        "# {} - 要求仕様書\n\n\
-        ## プロジェクト概要\n\n\
-        [プロジェクトの概要を記述]\n\n\
-        ## ステークホルダー\n\n\
-        - [ステークホルダー1]\n\
-        - [ステークホルダー2]\n\n\
-        ## ディレクトリ構造\n\n\
-        - `stakeholders/` - ステークホルダーインタビュー記録\n\
-        - `requirements/` - 要求仕様書\n\
-        - `analysis/` - 分析レポート\n",
-        project
-    );
+    let readme = t!("templates.readme", project = project);
 
     fs::write(format!("{}/README.md", project_dir), readme)?;
 
-    let interview_template = "# ステークホルダーインタビュー\n\n\
-        **日時**: [日時]\n\
-        **インタビュー対象**: [名前、役割]\n\n\
-        ## 主要な要求\n\n\
-        1. [要求1]\n\
-        2. [要求2]\n\n\
-        ## その他のコメント\n\n\
-        [コメント]\n";
+    let interview_template = t!("templates.interview");
 
     fs::write(
         format!("{}/stakeholders/interview_template.md", project_dir),
         interview_template,
     )?;
 
-    println!("{}", "✅ プロジェクト構造を作成しました:".bright_green());
+    println!("{}", t!("requirements.init_created").bright_green());
     println!("  {}/", project_dir);
     println!("  ├── README.md");
     println!("  ├── stakeholders/");
@@ -297,10 +321,10 @@ async fn init_requirements_project(project: &str, output_dir: &str) -> anyhow::R
     println!("  ├── requirements/");
     println!("  └── analysis/");
     println!();
-    println!("{}", "次のステップ:".bright_yellow());
-    println!("  1. stakeholders/ にインタビュー記録を追加");
-    println!("  2. seize requirements elicit でinタビューから要求を抽出");
-    println!("  3. seize requirements analyze で要求を分析");
+    println!("{}", t!("requirements.next_steps").bright_yellow());
+    println!("{}", t!("requirements.next_step_1"));
+    println!("{}", t!("requirements.next_step_2"));
+    println!("{}", t!("requirements.next_step_3"));
 
     Ok(())
 }
@@ -0,0 +1,66 @@
+//! 世界ストアコマンドのハンドラー
+
+use colored::*;
+use seize_core::store::WorldStore;
+
+/// 世界ストアコマンドを処理
+pub async fn handle_world_command(cmd: super::WorldCommands) -> anyhow::Result<()> {
+    match cmd {
+        super::WorldCommands::Log { world } => {
+            show_log(&world)?;
+        }
+        super::WorldCommands::Checkout { version, world } => {
+            checkout(&world, version)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 保存されている世界の一覧
+fn show_log(store_dir: &str) -> anyhow::Result<()> {
+    let store = WorldStore::open(store_dir)?;
+    let entries = store.log()?;
+
+    println!("{}", t!("world.log_title", dir = store_dir).bright_cyan().bold());
+    println!();
+
+    if entries.is_empty() {
+        println!("{}", t!("world.log_empty"));
+        return Ok(());
+    }
+
+    for entry in entries {
+        let marker = if entry.is_head { "* " } else { "  " };
+        let parent = entry
+            .parent
+            .map(|p| format!(" ← v{}", p))
+            .unwrap_or_default();
+        println!(
+            "{}{}{}{}",
+            marker,
+            format!("v{}", entry.version).bright_yellow(),
+            parent,
+            t!(
+                "world.log_entry",
+                history = entry.history,
+                learnings = entry.learnings,
+                decisions = entry.decisions
+            )
+        );
+    }
+
+    Ok(())
+}
+
+/// HEAD を指定したバージョンに移動
+fn checkout(store_dir: &str, version: usize) -> anyhow::Result<()> {
+    let store = WorldStore::open(store_dir)?;
+    let world = store.checkout(version)?;
+
+    println!("{}", t!("world.checked_out", version = world.version).bright_green());
+    println!("{}", t!("world.learnings", count = world.knowledge.learnings.len()));
+    println!("{}", t!("world.decisions", count = world.knowledge.decisions.len()));
+
+    Ok(())
+}
@@ -0,0 +1,80 @@
+//! `seize run` のドライランが作業ディレクトリを変更せず、
+//! 検証は留めた書き込みを反映した内容に対して実行されることを確かめる
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("seize-cli-{}-{}", name, std::process::id()));
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write_replay(path: &Path) {
+    let replay = serde_json::json!({
+        "entries": [
+            {
+                "task": "ResolveIntent",
+                "response": r#"{"essential_question": "何を残したいのか?", "category": "CodeGeneration", "priority": "Low"}"#
+            },
+            {
+                "task": "DecomposeGoal",
+                "response": r##"{"tasks": [
+                    {"id": "edit", "description": "README を更新", "task_type": "FileWrite",
+                     "parameters": {"path": "README.md", "content": "# updated\n"}},
+                    {"id": "note", "description": "メモを追加", "task_type": "FileWrite",
+                     "parameters": {"path": "docs/note.md", "content": "memo\n"}},
+                    {"id": "check", "description": "書き込みを確認", "task_type": "Validation",
+                     "dependencies": ["edit", "note"],
+                     "parameters": {"command": "grep -q updated README.md && test -f docs/note.md"}}
+                ]}"##
+            }
+        ]
+    });
+    fs::write(path, serde_json::to_string_pretty(&replay).unwrap()).unwrap();
+}
+
+fn run(dir: &Path, apply: bool) -> String {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_seize"));
+    command
+        .current_dir(dir)
+        .args(["--lang", "en", "run", "--input", "README を更新してください"])
+        .args(["--backend", "replay", "--replay-file", "replay.json"])
+        .args(["--workspace", "workspace", "--approval", "auto-approve", "--max-iterations", "1"]);
+    if apply {
+        command.arg("--apply");
+    }
+
+    let output = command.output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    assert!(output.status.success(), "{}\n{}", stdout, String::from_utf8_lossy(&output.stderr));
+    stdout
+}
+
+#[test]
+fn test_run_without_apply_leaves_workspace_unchanged() {
+    let dir = temp_dir("dry-run");
+    let workspace = dir.join("workspace");
+    fs::create_dir_all(&workspace).unwrap();
+    fs::write(workspace.join("README.md"), "# original\n").unwrap();
+    write_replay(&dir.join("replay.json"));
+
+    let stdout = run(&dir, false);
+    assert!(stdout.contains("dry run"), "{}", stdout);
+    assert!(stdout.contains("Executed: check"), "{}", stdout);
+    assert!(stdout.contains("~ README.md"), "{}", stdout);
+    assert!(stdout.contains("+ docs/note.md"), "{}", stdout);
+    assert_eq!(fs::read_to_string(workspace.join("README.md")).unwrap(), "# original\n");
+    assert!(!workspace.join("docs").exists());
+
+    let stdout = run(&dir, true);
+    assert!(stdout.contains("Executed: check"), "{}", stdout);
+    let readme = fs::read_to_string(workspace.join("README.md")).unwrap();
+    let note = fs::read_to_string(workspace.join("docs/note.md")).unwrap();
+    fs::remove_dir_all(&dir).ok();
+
+    assert_eq!(readme, "# updated\n");
+    assert_eq!(note, "memo\n");
+}
@@ -0,0 +1,35 @@
+//! `seize run` が止まった理由と却下の理由が表示言語でそろうことを確かめる
+
+use std::fs;
+use std::process::Command;
+
+#[test]
+fn test_english_run_reports_halt_without_japanese() {
+    let dir = std::env::temp_dir().join(format!("seize-cli-halted-{}", std::process::id()));
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_seize"))
+        .current_dir(&dir)
+        .args(["--lang", "en", "--log-level", "error", "run", "--input", "Investigate the login bug"])
+        .args(["--approval", "auto-deny", "--max-iterations", "1"])
+        .output()
+        .unwrap();
+    fs::remove_dir_all(&dir).ok();
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    assert!(output.status.success(), "{}\n{}", stdout, String::from_utf8_lossy(&output.stderr));
+
+    assert!(
+        stdout.contains("Halted: Investigate the login bug (Rejected by the auto-deny policy)"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("Rejected by: policy:auto-deny (Rejected by the auto-deny policy)"),
+        "{}",
+        stdout
+    );
+    for japanese in ["自動却下", "計画の実行", "判断待ち", "戻しました"] {
+        assert!(!stdout.contains(japanese), "{}", stdout);
+    }
+}
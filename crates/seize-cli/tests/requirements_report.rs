@@ -0,0 +1,44 @@
+//! `seize requirements analyze` のレポートが表示言語でそろうことを確かめる
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("seize-cli-{}-{}", name, std::process::id()));
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn seize(dir: &Path, args: &[&str]) {
+    let output = Command::new(env!("CARGO_BIN_EXE_seize"))
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn test_english_report_has_no_japanese_findings() {
+    let dir = temp_dir("report");
+    fs::write(dir.join("interview.md"), "システムは自動判断でアカウントを凍結する必要がある。").unwrap();
+
+    seize(&dir, &["requirements", "elicit", "--input", "interview.md", "--stakeholder", "Security", "--output", "spec.json"]);
+    seize(&dir, &["--lang", "en", "requirements", "analyze", "--spec", "spec.json", "--report", "report.md"]);
+    let report = fs::read_to_string(dir.join("report.md")).unwrap();
+    fs::remove_dir_all(&dir).ok();
+
+    assert!(report.contains("- Charter violation [HUMAN-001] Final decisions must be made by a human"), "{}", report);
+    assert!(report.contains("- No acceptance criteria are defined (verifiability)"), "{}", report);
+    assert!(report.contains("- Add acceptance criteria in Given-When-Then form"), "{}", report);
+    for japanese in ["憲章", "受入基準", "トレーサビリティ"] {
+        assert!(!report.contains(japanese), "{}", report);
+    }
+}
@@ -5,9 +5,25 @@
 //! ```
 
 use crate::{
-    command::CommandStack, intent::IntentResolver, transformation::WorldTransformer,
-    AgentResult, World,
+    approval::{Approver, RejectionPolicy},
+    budget::{CostReport, ResourceBudget},
+    charter::CharterEngine,
+    clock::Clock,
+    command::{CommandStack, ExecutionStrategy},
+    convergence::{self, ConvergenceCriterion, ConvergenceState},
+    error::SeizeResult,
+    intent::{Goal, IntentResolver},
+    learning::LearningModel,
+    model::{self, LanguageModel, MeteredModel, TokenMeter},
+    observer::AgentObserver,
+    phase::PhasePipeline,
+    recipe::RecipeBook,
+    transformation::WorldTransformer,
+    world::DecisionStatus,
+    AgentResult, HaltReason, IterationTrace, Refusal, World,
 };
+use std::sync::Arc;
+use std::time::Instant;
 
 /// 統一エージェント
 pub struct UnifiedAgent {
@@ -25,32 +41,165 @@ pub struct UnifiedAgent {
 
     /// 収束判定の閾値
     convergence_threshold: f64,
+
+    /// 収束判定基準
+    convergence: Box<dyn ConvergenceCriterion>,
+
+    /// トークン使用量の計測器
+    meter: Arc<TokenMeter>,
+
+    /// 却下・判断待ちの際の振る舞い
+    rejection_policy: RejectionPolicy,
+
+    /// 各段階の通知先
+    observers: Vec<Arc<dyn AgentObserver>>,
+
+    /// 1回の反復（実行計画）あたりの予算
+    plan_budget: ResourceBudget,
 }
 
 impl UnifiedAgent {
     /// 新しい統一エージェントを作成
+    ///
+    /// 既定の収束判定は「目標充足（閾値 `convergence_threshold`）または世界の不動点」
     pub fn new(max_iterations: usize, convergence_threshold: f64) -> Self {
-        Self {
+        let agent = Self {
             intent_resolver: IntentResolver::default(),
             command_stack: CommandStack::default(),
             world_transformer: WorldTransformer::default(),
             max_iterations,
             convergence_threshold,
+            convergence: Box::new(convergence::default_criterion(convergence_threshold)),
+            meter: Arc::new(TokenMeter::default()),
+            rejection_policy: RejectionPolicy::default(),
+            observers: Vec::new(),
+            plan_budget: ResourceBudget::unlimited(),
+        };
+        agent.with_model(model::default_model())
+    }
+
+    /// ℐ・𝒞・Θ のすべてで使う言語モデルを設定
+    pub fn with_model(mut self, model: Arc<dyn LanguageModel>) -> Self {
+        let model: Arc<dyn LanguageModel> = Arc::new(MeteredModel::new(model, self.meter.clone()));
+        self.intent_resolver = self.intent_resolver.with_model(model.clone());
+        self.command_stack = self.command_stack.with_model(model.clone());
+        self.world_transformer = self.world_transformer.with_model(model);
+        self
+    }
+
+    /// 収束判定基準を設定
+    pub fn with_convergence(mut self, criterion: Box<dyn ConvergenceCriterion>) -> Self {
+        self.convergence = criterion;
+        self
+    }
+
+    /// 意思決定・破壊的なタスクの承認者を設定（既定はすべて自動却下）
+    pub fn with_approver(mut self, approver: Arc<dyn Approver>) -> Self {
+        self.world_transformer = self.world_transformer.with_approver(approver);
+        self
+    }
+
+    /// ℐ・Θ で使う憲章を設定
+    pub fn with_charter(mut self, charter: Arc<CharterEngine>) -> Self {
+        self.intent_resolver = self.intent_resolver.with_charter(charter.clone());
+        self.world_transformer = self.world_transformer.with_charter(charter);
+        self
+    }
+
+    /// 𝒞 で使う分解レシピを設定
+    pub fn with_recipes(mut self, recipes: Arc<RecipeBook>) -> Self {
+        self.command_stack = self.command_stack.with_recipes(recipes);
+        self
+    }
+
+    /// 𝒞 でタスクをサブタスクに細分化する深さを設定
+    pub fn with_decomposition_depth(mut self, depth: usize) -> Self {
+        self.command_stack = self.command_stack.with_max_depth(depth);
+        self
+    }
+
+    /// 𝒞 が実行計画に付ける実行戦略を設定（既定は目標の優先度から決める）
+    pub fn with_strategy(mut self, strategy: ExecutionStrategy) -> Self {
+        self.command_stack = self.command_stack.with_strategy(strategy);
+        self
+    }
+
+    /// 1回の反復（実行計画）あたりの予算を設定（既定は無制限）
+    ///
+    /// θ₃ でタスクごとに配分し、θ₄ で上限を守らせる。計画全体の予算を超えた反復は取り消して止める
+    pub fn with_plan_budget(mut self, budget: ResourceBudget) -> Self {
+        self.command_stack = self.command_stack.with_budget(budget.clone());
+        self.plan_budget = budget;
+        self
+    }
+
+    /// Θ の変換フェーズの並びを設定
+    pub fn with_pipeline(mut self, pipeline: PhasePipeline) -> Self {
+        self.world_transformer = self.world_transformer.with_pipeline(pipeline);
+        self
+    }
+
+    /// θ₁ でタスクに添える関連知識（過去の学習・意思決定）の件数を設定
+    pub fn with_recall(mut self, recall: usize) -> Self {
+        self.world_transformer = self.world_transformer.with_recall(recall);
+        self
+    }
+
+    /// θ₆ で学習を更新する信頼度モデルを設定
+    pub fn with_learning_model(mut self, learning: LearningModel) -> Self {
+        self.world_transformer = self.world_transformer.with_learning_model(learning);
+        self
+    }
+
+    /// 学習・意思決定の記録に使う時計を設定
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.world_transformer = self.world_transformer.with_clock(clock);
+        self
+    }
+
+    /// 却下・判断待ちの際の振る舞いを設定（既定は反復を止める）
+    pub fn with_rejection_policy(mut self, policy: RejectionPolicy) -> Self {
+        self.rejection_policy = policy;
+        self
+    }
+
+    /// 各段階の通知を受け取る観測者を追加
+    pub fn with_observer(mut self, observer: Arc<dyn AgentObserver>) -> Self {
+        self.observers.push(observer);
+        self
+    }
+
+    fn notify(&self, event: impl Fn(&dyn AgentObserver)) {
+        for observer in &self.observers {
+            event(observer.as_ref());
         }
     }
 
     /// エージェントを実行
     ///
     /// ## プロセス
-    /// 1. ℐ: 入力から目標を解決
-    /// 2. 𝒞: 目標を実行計画に分解
+    /// 1. ℐ: 入力から目標を解決（2回目以降は前回の目標を世界の結果に基づいて見直す）
+    /// 2. 𝒞: 目標を実行計画に分解（複数の依頼を含む入力は目標ごとの計画を1つにまとめる）
     /// 3. Θ: 実行計画を適用して世界を変換
     /// 4. 収束判定: 目標が達成されたか確認
     /// 5. 未収束なら反復
-    pub fn run(&self, input: &str, initial_world: World) -> anyhow::Result<AgentResult> {
+    ///
+    /// 承認者が却下・保留した場合は `RejectionPolicy` に従い、
+    /// 反復を止めるか次の反復で再計画する。
+    /// Θ のフェーズが失敗した場合は世界を適用前に戻し、失敗を学習として記録して反復を止める
+    pub fn run(&self, input: &str, initial_world: World) -> SeizeResult<AgentResult> {
         let mut world = initial_world.clone();
         let mut iterations = 0;
         let mut converged = false;
+        let mut converged_by = None;
+        let mut scores = Vec::new();
+        let mut halted = None;
+        let mut trace = Vec::new();
+        let mut previous_goal = None;
+        let mut executed = Vec::new();
+
+        let started = Instant::now();
+        let tokens_at_start = self.meter.usage().total();
 
         tracing::info!("🎯 統一エージェント起動");
         tracing::info!("入力: {}", input);
@@ -58,70 +207,153 @@ impl UnifiedAgent {
         while iterations < self.max_iterations && !converged {
             iterations += 1;
             tracing::info!("\n📍 反復 {}/{}", iterations, self.max_iterations);
+            self.notify(|o| o.on_iteration_start(iterations, self.max_iterations));
+            let iteration_started = Instant::now();
 
             // ℐ: Intent Resolution
             tracing::info!("ℐ: 意図解決中...");
-            let goal = self.intent_resolver.resolve(input)?;
+            let goals = self.intent_resolver.resolve_all_in(input, &world, previous_goal.as_ref())?;
+            let goal = Goal::combine(&goals)?;
+            if goals.len() > 1 {
+                tracing::info!("✓ {}個の目標に分解", goals.len());
+            }
             tracing::info!("✓ 目標: {}", goal.description);
             tracing::info!("✓ 本質的な問い: {}", goal.essential_question);
+            self.notify(|o| o.on_intent_resolved(iterations, &goal));
 
             // 𝒞: Command Stack
             tracing::info!("𝒞: コマンドスタック分解中...");
-            let plan = self.command_stack.decompose(goal)?;
+            let plan = self.command_stack.decompose_all(goals)?;
             tracing::info!("✓ {}個のタスクに分解", plan.tasks.len());
+            self.notify(|o| o.on_plan_created(iterations, &plan));
 
             // Θ: World Transformation
             tracing::info!("Θ: 世界変換適用中...");
-            world = self.world_transformer.apply(plan, world)?;
+            let previous = world.clone();
+            let mut step = IterationTrace {
+                iteration: iterations,
+                goal: goal.clone(),
+                plan: plan.clone(),
+                phases: Vec::new(),
+                score: None,
+                wall_time_ms: 0,
+            };
+            let applied = self.world_transformer.apply_transaction(plan, world, &mut |result| {
+                self.notify(|o| o.on_phase(iterations, result));
+                step.phases.push(result.clone());
+            });
+            world = match applied {
+                Ok(world) => world,
+                Err(rolled_back) => {
+                    // 失敗を記録した適用前の世界を残して反復を止める
+                    let reason = HaltReason::RolledBack {
+                        phase: rolled_back.phase.clone(),
+                        world_version: rolled_back.world.version,
+                        error: rolled_back.error.to_string(),
+                    };
+                    tracing::warn!("⛔ {}", reason);
+                    self.notify(|o| o.on_halted(iterations, &reason));
+                    halted = Some(reason);
+                    executed.extend(rolled_back.executed);
+                    world = rolled_back.world;
+                    step.wall_time_ms = iteration_started.elapsed().as_millis() as u64;
+                    trace.push(step);
+                    break;
+                }
+            };
             tracing::info!("✓ World v{} に更新", world.version);
+            executed.extend(
+                world.context.task_records[previous.context.task_records.len().min(world.context.task_records.len())..]
+                    .iter()
+                    .cloned(),
+            );
+
+            let refusals: Vec<Refusal> = world.knowledge.decisions[previous.knowledge.decisions.len()..]
+                .iter()
+                .filter(|d| d.status != DecisionStatus::Approved)
+                .map(|d| Refusal {
+                    purpose: d.purpose.clone(),
+                    reason: match d.status {
+                        DecisionStatus::Rejected => d.rejection_reason.clone(),
+                        _ => None,
+                    },
+                })
+                .collect();
+            if !refusals.is_empty() {
+                let reason = HaltReason::NotApproved(refusals);
+                if self.rejection_policy == RejectionPolicy::Halt {
+                    tracing::warn!("⛔ 承認されなかったため反復を止めます: {}", reason);
+                    self.notify(|o| o.on_halted(iterations, &reason));
+                    halted = Some(reason);
+                    step.wall_time_ms = iteration_started.elapsed().as_millis() as u64;
+                    trace.push(step);
+                    break;
+                }
+                tracing::warn!("↻ 承認されなかったため次の反復で再計画します: {}", reason);
+            }
 
             // 収束判定
-            converged = self.check_convergence(&world, iterations);
+            let evaluation = self.convergence.evaluate(&ConvergenceState {
+                iteration: iterations,
+                goal: &goal,
+                previous: &previous,
+                current: &world,
+                elapsed: started.elapsed(),
+                tokens_used: self.meter.usage().total() - tokens_at_start,
+            });
+            tracing::info!("✓ 収束度: {:.2}", evaluation.score);
+            scores.push(evaluation.score);
+            step.score = Some(evaluation.score);
+            step.wall_time_ms = iteration_started.elapsed().as_millis() as u64;
+            trace.push(step);
+            self.notify(|o| o.on_iteration_end(iterations, &world, &evaluation));
+
+            converged = evaluation.converged;
             if converged {
-                tracing::info!("✅ 収束条件を満たしました");
+                tracing::info!(
+                    "✅ 収束条件を満たしました ({})",
+                    evaluation.fired_by.as_deref().unwrap_or("-")
+                );
+                let criterion = evaluation.fired_by.as_deref().unwrap_or("-");
+                self.notify(|o| o.on_converged(iterations, criterion));
+                converged_by = evaluation.fired_by;
             }
+
+            // 次の反復では今回の目標を世界の結果に基づいて見直す
+            previous_goal = Some(goal);
         }
 
-        if !converged {
+        if !converged && halted.is_none() {
             tracing::warn!("⚠️ 最大反復回数に到達（収束せず）");
         }
 
+        // 取り消した反復で実行したタスクもコストに含める
+        let cost = CostReport::from_records(
+            self.plan_budget.clone(),
+            &executed,
+            self.meter.usage().total() - tokens_at_start,
+        );
+
         Ok(AgentResult {
             initial_world,
             final_world: world,
             iterations,
             converged,
+            converged_by,
+            scores,
+            halted,
+            trace,
+            cost,
         })
     }
 
-    /// 収束判定
-    ///
-    /// ## 判定基準
-    /// - タスク実行の履歴が一定数に達した
-    /// - 学習内容が十分蓄積された
-    /// - エラーが発生していない
-    fn check_convergence(&self, world: &World, iterations: usize) -> bool {
-        // 簡易実装: 反復回数と履歴の長さで判定
-        let history_length = world.context.history.len();
-        let learning_count = world.knowledge.learnings.len();
-
-        // 十分な実行履歴と学習があれば収束とみなす
-        if history_length >= iterations * 2 && learning_count >= iterations {
-            return true;
-        }
-
-        // 閾値判定（将来的にはより高度な判定を実装）
-        let convergence_score =
-            (history_length as f64 / (iterations * 3) as f64).min(1.0);
-
-        convergence_score >= self.convergence_threshold
-    }
-
     /// エージェントの状態を表示
     pub fn status(&self) -> String {
         format!(
-            "UnifiedAgent {{ max_iterations: {}, convergence_threshold: {} }}",
-            self.max_iterations, self.convergence_threshold
+            "UnifiedAgent {{ max_iterations: {}, convergence_threshold: {}, convergence: {} }}",
+            self.max_iterations,
+            self.convergence_threshold,
+            self.convergence.name()
         )
     }
 }
@@ -135,10 +367,21 @@ impl Default for UnifiedAgent {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::convergence::Budget;
+    use crate::error::SeizeError;
+    use crate::command::TaskStatus;
+    use crate::model::{ModelTask, ReplayModel};
+    use crate::approval::{ApprovalPolicy, PolicyApprover, AUTO_DENY_REASON};
+    use std::time::Duration;
+
+    /// 承認をすべて自動で通すエージェント
+    fn approving_agent(max_iterations: usize) -> UnifiedAgent {
+        UnifiedAgent::new(max_iterations, 0.8).with_approver(Arc::new(PolicyApprover::new(ApprovalPolicy::AutoApprove)))
+    }
 
     #[test]
     fn test_unified_agent() {
-        let agent = UnifiedAgent::default();
+        let agent = approving_agent(10);
         let world = World::new();
 
         let result = agent
@@ -147,5 +390,158 @@ mod tests {
 
         assert!(result.iterations > 0);
         assert!(result.final_world.version > 0);
+
+        assert_eq!(result.trace.len(), result.iterations);
+        let first = &result.trace[0];
+        assert_eq!(first.iteration, 1);
+        assert_eq!(first.phases.len(), 6);
+        assert_eq!(first.score, Some(result.scores[0]));
+        assert!(!first.plan.tasks.is_empty());
+        let json = serde_json::to_value(&result.trace).unwrap();
+        assert_eq!(json[0]["goal"]["description"], first.goal.description.as_str());
+    }
+
+    #[test]
+    fn test_unified_agent_with_replay_model() {
+        let model = ReplayModel::new()
+            .with_response(
+                ModelTask::ResolveIntent,
+                None,
+                r#"{"essential_question": "何を知りたいのか?", "category": "Understanding", "priority": "Low"}"#,
+            )
+            .with_response(
+                ModelTask::DecomposeGoal,
+                None,
+                r#"{"tasks": [{"id": "survey", "description": "調査", "task_type": "Analysis"}]}"#,
+            )
+            .with_response(ModelTask::ExecuteTask, None, "調査結果: 問題なし");
+        let agent = approving_agent(1).with_model(Arc::new(model));
+
+        let result = agent.run("現状を調査してください", World::new()).unwrap();
+
+        assert_eq!(
+            result.final_world.context.history,
+            vec!["Executed: survey - 調査".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_convergence_depends_on_goal() {
+        let agent = approving_agent(5);
+
+        // すべてのタスクが完了する目標は1回で目標充足
+        let result = agent.run("現状を調査してください", World::new()).unwrap();
+        assert_eq!(result.iterations, 1);
+        assert_eq!(result.converged_by.as_deref(), Some("goal_satisfaction"));
+        assert_eq!(result.scores, vec![1.0]);
+
+        // 組み込みのレシピの検証タスクはコマンドがなければ省略され、失敗として再計画されない
+        let result = agent.run("コードを修正してください", World::new()).unwrap();
+        assert_eq!(result.iterations, 1);
+        assert_eq!(result.converged_by.as_deref(), Some("goal_satisfaction"));
+        assert!(result
+            .final_world
+            .context
+            .task_records
+            .iter()
+            .all(|r| r.status == TaskStatus::Completed));
+    }
+
+    #[test]
+    fn test_budget_stops_iterations() {
+        let agent = approving_agent(3).with_convergence(Box::new(Budget {
+            max_tokens: None,
+            max_duration: Some(Duration::ZERO),
+        }));
+
+        let result = agent.run("コードを修正してください", World::new()).unwrap();
+        assert_eq!(result.iterations, 1);
+        assert_eq!(result.converged_by.as_deref(), Some("budget"));
+    }
+
+    #[test]
+    fn test_rejection_halts_or_replans() {
+        let deny = Arc::new(PolicyApprover::new(ApprovalPolicy::AutoDeny));
+
+        // 既定の承認者はすべて自動で却下する
+        let agent = UnifiedAgent::new(3, 0.8);
+        let result = agent.run("現状を調査してください", World::new()).unwrap();
+        assert_eq!(result.iterations, 1);
+        assert!(!result.converged);
+        let halted = result.halted.unwrap();
+        assert_eq!(
+            halted,
+            HaltReason::NotApproved(vec![Refusal {
+                purpose: "現状を調査してください".to_string(),
+                reason: Some(AUTO_DENY_REASON.to_string()),
+            }])
+        );
+        assert!(halted.to_string().contains("自動却下"));
+        let decision = &result.final_world.knowledge.decisions[0];
+        assert_eq!(decision.status, DecisionStatus::Rejected);
+        assert_eq!(decision.approver, "policy:auto-deny");
+        assert!(result.final_world.context.task_records.is_empty());
+        assert_eq!(result.trace.len(), 1);
+        assert_eq!(result.trace[0].score, None);
+
+        let agent = UnifiedAgent::new(3, 0.8)
+            .with_approver(deny)
+            .with_rejection_policy(RejectionPolicy::Replan);
+        let result = agent.run("現状を調査してください", World::new()).unwrap();
+        assert_eq!(result.iterations, 3);
+        assert!(result.halted.is_none());
+        assert!(result.trace[0].goal.lineage.is_empty());
+        assert_eq!(result.trace[2].goal.lineage.len(), 2);
+    }
+
+    #[test]
+    fn test_charter_violation_is_a_distinct_error() {
+        let agent = UnifiedAgent::default();
+        let result = agent.run("自動判断で採用候補を決める", World::new());
+        assert!(matches!(result, Err(SeizeError::CharterViolation(_))));
+    }
+
+    #[test]
+    fn test_observer_receives_each_stage() {
+        use crate::transformation::TransformationResult;
+        use std::sync::Mutex;
+
+        #[derive(Default)]
+        struct Recorder(Mutex<Vec<String>>);
+
+        impl AgentObserver for Recorder {
+            fn on_iteration_start(&self, iteration: usize, _: usize) {
+                self.0.lock().unwrap().push(format!("start {}", iteration));
+            }
+            fn on_intent_resolved(&self, _: usize, _: &crate::Goal) {
+                self.0.lock().unwrap().push("intent".to_string());
+            }
+            fn on_plan_created(&self, _: usize, _: &crate::ExecutionPlan) {
+                self.0.lock().unwrap().push("plan".to_string());
+            }
+            fn on_phase(&self, _: usize, result: &TransformationResult) {
+                self.0.lock().unwrap().push(format!("{:?}", result.phase));
+            }
+            fn on_iteration_end(&self, iteration: usize, _: &World, _: &convergence::Evaluation) {
+                self.0.lock().unwrap().push(format!("end {}", iteration));
+            }
+            fn on_converged(&self, _: usize, criterion: &str) {
+                self.0.lock().unwrap().push(format!("converged {}", criterion));
+            }
+        }
+
+        let recorder = Arc::new(Recorder::default());
+        let agent = approving_agent(1).with_observer(recorder.clone());
+        agent.run("現状を調査してください", World::new()).unwrap();
+
+        let events = recorder.0.lock().unwrap();
+        assert_eq!(
+            events[..9],
+            [
+                "start 1", "intent", "plan", "Understand", "Generate", "Allocate", "Execute",
+                "Integrate", "Learn",
+            ]
+        );
+        assert_eq!(events[9], "end 1");
     }
 }
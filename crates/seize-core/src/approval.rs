@@ -0,0 +1,317 @@
+//! # 承認 (Approval)
+//!
+//! 憲章「人間は判断する」に基づき、意思決定や破壊的なタスク
+//! （ファイル書き込み・シェルコマンドの実行・意思決定）の前に承認者の判断を求める。
+//!
+//! - `PolicyApprover`: CI向けの自動承認・自動却下
+//! - `FileQueueApprover`: ファイルキューによる非同期の承認
+//!
+//! 対話的な承認は CLI 側で `Approver` を実装する。
+
+use crate::command::{Task, TaskType};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 承認の対象
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ApprovalKind {
+    /// 計画の実行（要約は目標の記述）
+    Plan,
+    /// 破壊的なタスクの実行
+    Task,
+    /// 意思決定の記録
+    Decision,
+}
+
+/// 承認リクエスト
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalRequest {
+    /// 内容から決まるID（同じ内容なら同じID）
+    pub id: String,
+
+    /// 承認の対象
+    pub kind: ApprovalKind,
+
+    /// 要約
+    pub summary: String,
+
+    /// 詳細（プロンプト・パラメータ・選択肢など）
+    pub details: String,
+}
+
+impl ApprovalRequest {
+    /// 新しいリクエストを作成
+    pub fn new(kind: ApprovalKind, summary: impl Into<String>, details: impl Into<String>) -> Self {
+        let summary = summary.into();
+        let details = details.into();
+        let id = format!(
+            "{:016x}",
+            fnv1a(format!("{:?}\n{}\n{}", kind, summary, details).as_bytes())
+        );
+
+        Self {
+            id,
+            kind,
+            summary,
+            details,
+        }
+    }
+
+    /// タスク実行の承認リクエストを作成
+    ///
+    /// ID はパラメータとプロンプトを含む内容全体から決める（実行する内容が変われば改めて承認を求める）。
+    /// θ₁ が想起した知見はプロンプトとは別に持つため ID を変えない
+    pub fn for_task(task: &Task) -> Self {
+        let mut parameters: Vec<String> = task
+            .parameters
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect();
+        parameters.sort();
+
+        // 実行されるコマンドはそのままの形で先頭に示す
+        let command = task
+            .parameters
+            .get("command")
+            .map(|command| format!("$ {}\n", command))
+            .unwrap_or_default();
+
+        Self::new(
+            ApprovalKind::Task,
+            format!("{:?}: {} - {}", task.task_type, task.id, task.description),
+            format!("{}{}\n{}", command, parameters.join("\n"), task.prompt).trim().to_string(),
+        )
+    }
+}
+
+/// 実行前に承認が必要なタスクか（ファイル書き込み・検証・意思決定と、`command` パラメータを持つタスク）
+///
+/// 検証のコマンドはモデルが分解・再計画で書いたものをそのままシェルに渡すため、必ず承認を求める
+pub fn requires_approval(task: &Task) -> bool {
+    matches!(task.task_type, TaskType::FileWrite | TaskType::Validation | TaskType::Decision)
+        || task.parameters.contains_key("command")
+}
+
+/// 実行をまたいで安定したハッシュ（FNV-1a 64bit）
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// 承認者の判断
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ApprovalDecision {
+    /// 承認
+    Approved { approver: String },
+    /// 却下
+    Rejected { approver: String, reason: String },
+    /// 判断待ち
+    Pending,
+}
+
+impl ApprovalDecision {
+    /// 承認されたか
+    pub fn is_approved(&self) -> bool {
+        matches!(self, Self::Approved { .. })
+    }
+}
+
+/// 承認者
+pub trait Approver: Send + Sync {
+    /// リクエストを審査
+    fn review(&self, request: &ApprovalRequest) -> anyhow::Result<ApprovalDecision>;
+}
+
+/// 自動却下ポリシーによる却下の理由
+///
+/// 表示する文言は利用側で選べるよう、文言ではなくこのコードを理由に記録する
+pub const AUTO_DENY_REASON: &str = "auto-deny";
+
+/// 却下の理由の日本語の文言（自動却下ポリシーのコードは文言に置き換える）
+pub fn describe_reason(reason: &str) -> &str {
+    if reason == AUTO_DENY_REASON {
+        "自動却下ポリシーにより却下されました"
+    } else {
+        reason
+    }
+}
+
+/// 自動判断のポリシー
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApprovalPolicy {
+    /// すべて承認
+    AutoApprove,
+    /// すべて却下
+    AutoDeny,
+}
+
+/// ポリシーによる自動承認者（CI向け）
+#[derive(Debug, Clone, Copy)]
+pub struct PolicyApprover {
+    policy: ApprovalPolicy,
+}
+
+impl PolicyApprover {
+    /// 新しい承認者を作成
+    pub fn new(policy: ApprovalPolicy) -> Self {
+        Self { policy }
+    }
+}
+
+impl Approver for PolicyApprover {
+    fn review(&self, _request: &ApprovalRequest) -> anyhow::Result<ApprovalDecision> {
+        Ok(match self.policy {
+            ApprovalPolicy::AutoApprove => ApprovalDecision::Approved {
+                approver: "policy:auto-approve".to_string(),
+            },
+            ApprovalPolicy::AutoDeny => ApprovalDecision::Rejected {
+                approver: "policy:auto-deny".to_string(),
+                reason: AUTO_DENY_REASON.to_string(),
+            },
+        })
+    }
+}
+
+/// ファイルキューによる承認者
+///
+/// ```text
+/// <dir>/
+/// ├── pending/<id>.json     # 判断待ちのリクエスト
+/// └── responses/<id>.json   # 承認者の判断
+/// ```
+///
+/// 判断がまだなければリクエストをキューに置いて `Pending` を返す。
+/// 次回の実行時に同じリクエストが来ると、記録された判断を返す。
+pub struct FileQueueApprover {
+    dir: PathBuf,
+}
+
+impl FileQueueApprover {
+    /// キューを開く（存在しなければ作成する）
+    pub fn open(dir: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(dir.join("pending"))?;
+        fs::create_dir_all(dir.join("responses"))?;
+        Ok(Self { dir })
+    }
+
+    fn pending_path(&self, id: &str) -> PathBuf {
+        self.dir.join("pending").join(format!("{}.json", id))
+    }
+
+    fn response_path(&self, id: &str) -> PathBuf {
+        self.dir.join("responses").join(format!("{}.json", id))
+    }
+
+    /// 判断待ちのリクエスト
+    pub fn pending(&self) -> anyhow::Result<Vec<ApprovalRequest>> {
+        let mut requests = Vec::new();
+        for entry in fs::read_dir(self.dir.join("pending"))? {
+            let json = fs::read_to_string(entry?.path())?;
+            requests.push(serde_json::from_str(&json)?);
+        }
+        requests.sort_by(|a: &ApprovalRequest, b| a.id.cmp(&b.id));
+        Ok(requests)
+    }
+
+    /// リクエストに判断を記録する
+    pub fn respond(&self, id: &str, decision: &ApprovalDecision) -> anyhow::Result<()> {
+        if !self.pending_path(id).exists() {
+            anyhow::bail!("判断待ちのリクエストがありません: {}", id);
+        }
+        fs::write(self.response_path(id), serde_json::to_string_pretty(decision)?)?;
+        fs::remove_file(self.pending_path(id))?;
+        Ok(())
+    }
+}
+
+impl Approver for FileQueueApprover {
+    fn review(&self, request: &ApprovalRequest) -> anyhow::Result<ApprovalDecision> {
+        if let Ok(json) = fs::read_to_string(self.response_path(&request.id)) {
+            return Ok(serde_json::from_str(&json)?);
+        }
+
+        fs::write(self.pending_path(&request.id), serde_json::to_string_pretty(request)?)?;
+        Ok(ApprovalDecision::Pending)
+    }
+}
+
+/// 却下・判断待ちの際の振る舞い
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RejectionPolicy {
+    /// 反復を止める
+    #[default]
+    Halt,
+    /// 却下を記録して次の反復で再計画する
+    Replan,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_id_is_stable() {
+        let a = ApprovalRequest::new(ApprovalKind::Task, "書き込み", "path=a.txt");
+        let b = ApprovalRequest::new(ApprovalKind::Task, "書き込み", "path=a.txt");
+        let c = ApprovalRequest::new(ApprovalKind::Task, "書き込み", "path=b.txt");
+
+        assert_eq!(a.id, b.id);
+        assert_ne!(a.id, c.id);
+    }
+
+    #[test]
+    fn test_shell_commands_require_approval() {
+        let mut task = Task::new("task_0", TaskType::Validation)
+            .with_parameters(&[("command", "cargo test && rm -rf target")]);
+        assert!(requires_approval(&task));
+        assert!(ApprovalRequest::for_task(&task)
+            .details
+            .starts_with("$ cargo test && rm -rf target\n"));
+
+        // コマンドを持つタスクはタスクタイプによらず承認を求める
+        task.task_type = TaskType::Analysis;
+        assert!(requires_approval(&task));
+        task.parameters.clear();
+        assert!(!requires_approval(&task));
+    }
+
+    #[test]
+    fn test_task_request_id_follows_prompt() {
+        let mut task = Task {
+            prompt: "方針を決めてください".to_string(),
+            ..Task::new("task_0", TaskType::Decision)
+        };
+        let first = ApprovalRequest::for_task(&task);
+
+        // 想起した知見が変わっても同じリクエスト、プロンプトが変われば別のリクエスト
+        task.recalled.push("学習: A を選んだ (信頼度: 0.67)".to_string());
+        assert_eq!(ApprovalRequest::for_task(&task).id, first.id);
+        task.prompt.push_str("。B を優先してください");
+        assert_ne!(ApprovalRequest::for_task(&task).id, first.id);
+    }
+
+    #[test]
+    fn test_file_queue_round_trip() {
+        let dir = std::env::temp_dir().join(format!("seize-approval-{}", std::process::id()));
+        fs::remove_dir_all(&dir).ok();
+        let queue = FileQueueApprover::open(&dir).unwrap();
+        let request = ApprovalRequest::new(ApprovalKind::Decision, "方針決定", "選択肢: A, B");
+
+        assert_eq!(queue.review(&request).unwrap(), ApprovalDecision::Pending);
+        assert_eq!(queue.pending().unwrap().len(), 1);
+
+        let decision = ApprovalDecision::Rejected {
+            approver: "reviewer".to_string(),
+            reason: "根拠が不足".to_string(),
+        };
+        queue.respond(&request.id, &decision).unwrap();
+
+        assert!(queue.pending().unwrap().is_empty());
+        assert_eq!(queue.review(&request).unwrap(), decision);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,282 @@
+//! # 資源の予算 (Resource Budget)
+//!
+//! 実行計画全体の予算を θ₃ Allocate でタスクごとに配分し、
+//! θ₄ Execute で実行時間・トークン・ファイル書き込み・プロセス起動の上限を守らせる。
+//! 使用量はタスクの実行記録に残り、エージェントの実行結果でコストとして報告される。
+
+use crate::command::{Task, TaskType};
+use crate::world::TaskRecord;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// 資源の予算（`None` の項目は無制限）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceBudget {
+    /// 実行時間の上限（ミリ秒）
+    #[serde(default)]
+    pub wall_time_ms: Option<u64>,
+
+    /// 言語モデルのトークン数の上限
+    #[serde(default)]
+    pub tokens: Option<u64>,
+
+    /// ファイル書き込み回数の上限
+    #[serde(default)]
+    pub file_writes: Option<u32>,
+
+    /// プロセス起動回数の上限
+    #[serde(default)]
+    pub processes: Option<u32>,
+}
+
+impl ResourceBudget {
+    /// 無制限の予算
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    /// 実行時間の上限を設定
+    pub fn with_wall_time(mut self, wall_time: Duration) -> Self {
+        self.wall_time_ms = Some(wall_time.as_millis() as u64);
+        self
+    }
+
+    /// トークン数の上限を設定
+    pub fn with_tokens(mut self, tokens: u64) -> Self {
+        self.tokens = Some(tokens);
+        self
+    }
+
+    /// ファイル書き込み回数の上限を設定
+    pub fn with_file_writes(mut self, file_writes: u32) -> Self {
+        self.file_writes = Some(file_writes);
+        self
+    }
+
+    /// プロセス起動回数の上限を設定
+    pub fn with_processes(mut self, processes: u32) -> Self {
+        self.processes = Some(processes);
+        self
+    }
+
+    /// どの項目にも上限がないか
+    pub fn is_unlimited(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 実行時間の上限
+    pub fn wall_time(&self) -> Option<Duration> {
+        self.wall_time_ms.map(Duration::from_millis)
+    }
+
+    /// タスクタイプが使う資源の上限が0なら、その理由
+    ///
+    /// 実行する前に判断できる上限（書き込み・プロセス・トークン）だけを見る
+    pub fn refusal(&self, task_type: &TaskType) -> Option<String> {
+        let exhausted = match task_type {
+            TaskType::FileWrite => self.file_writes == Some(0),
+            TaskType::Validation => self.processes == Some(0),
+            TaskType::Analysis | TaskType::CodeGeneration | TaskType::Decision => self.tokens == Some(0),
+            TaskType::FileRead => false,
+        };
+        exhausted.then(|| format!("{:?} に配分された予算がありません ({})", task_type, self.describe()))
+    }
+
+    /// 使用量が上限を超えた項目
+    pub fn exceeded(&self, usage: &ResourceUsage) -> Vec<String> {
+        let mut exceeded = Vec::new();
+        if let Some(limit) = self.wall_time_ms.filter(|&limit| usage.wall_time_ms > limit) {
+            exceeded.push(format!("実行時間 {}ms > {}ms", usage.wall_time_ms, limit));
+        }
+        if let Some(limit) = self.tokens.filter(|&limit| usage.tokens > limit) {
+            exceeded.push(format!("トークン {} > {}", usage.tokens, limit));
+        }
+        if let Some(limit) = self.file_writes.filter(|&limit| usage.file_writes > limit) {
+            exceeded.push(format!("ファイル書き込み {} > {}", usage.file_writes, limit));
+        }
+        if let Some(limit) = self.processes.filter(|&limit| usage.processes > limit) {
+            exceeded.push(format!("プロセス起動 {} > {}", usage.processes, limit));
+        }
+        exceeded
+    }
+
+    /// 予算の要約
+    pub fn describe(&self) -> String {
+        let limits: Vec<String> = [
+            self.wall_time_ms.map(|v| format!("時間≤{}ms", v)),
+            self.tokens.map(|v| format!("トークン≤{}", v)),
+            self.file_writes.map(|v| format!("書き込み≤{}", v)),
+            self.processes.map(|v| format!("プロセス≤{}", v)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        if limits.is_empty() {
+            "無制限".to_string()
+        } else {
+            limits.join(", ")
+        }
+    }
+
+    /// 計画全体の予算をタスクに配分する
+    ///
+    /// - 実行時間: すべてのタスクで等分
+    /// - トークン: 言語モデルを使うタスク（分析・コード生成・意思決定）で等分
+    /// - ファイル書き込み・プロセス起動: 書き込み・検証タスクに計画の順で1回ずつ、上限に達したら0
+    ///
+    /// 上限のない項目と、タスクが使わない資源の項目は無制限のままにする
+    pub fn allocate(&self, tasks: &[Task]) -> Vec<ResourceBudget> {
+        let uses_model = |task: &Task| {
+            matches!(task.task_type, TaskType::Analysis | TaskType::CodeGeneration | TaskType::Decision)
+        };
+        let model_tasks = tasks.iter().filter(|t| uses_model(t)).count() as u64;
+        let mut file_writes = self.file_writes;
+        let mut processes = self.processes;
+
+        let take = |remaining: &mut Option<u32>| {
+            remaining.map(|left| {
+                *remaining = Some(left.saturating_sub(1));
+                left.min(1)
+            })
+        };
+
+        tasks
+            .iter()
+            .map(|task| ResourceBudget {
+                wall_time_ms: self.wall_time_ms.map(|total| total / tasks.len().max(1) as u64),
+                tokens: self
+                    .tokens
+                    .filter(|_| uses_model(task))
+                    .map(|total| total / model_tasks.max(1)),
+                file_writes: if task.task_type == TaskType::FileWrite { take(&mut file_writes) } else { None },
+                processes: if task.task_type == TaskType::Validation { take(&mut processes) } else { None },
+            })
+            .collect()
+    }
+}
+
+/// 資源の使用量
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceUsage {
+    /// 実行時間（ミリ秒）
+    #[serde(default)]
+    pub wall_time_ms: u64,
+
+    /// 言語モデルのトークン数
+    #[serde(default)]
+    pub tokens: u64,
+
+    /// ファイル書き込み回数
+    #[serde(default)]
+    pub file_writes: u32,
+
+    /// プロセス起動回数
+    #[serde(default)]
+    pub processes: u32,
+}
+
+impl ResourceUsage {
+    /// 使用量を加算
+    pub fn add(&mut self, other: &ResourceUsage) {
+        self.wall_time_ms += other.wall_time_ms;
+        self.tokens += other.tokens;
+        self.file_writes += other.file_writes;
+        self.processes += other.processes;
+    }
+}
+
+/// タスクのコスト
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskCost {
+    /// 実行時の世界のバージョン
+    pub world_version: usize,
+    pub task_id: String,
+    /// 配分された予算
+    pub budget: ResourceBudget,
+    /// 使用量
+    pub usage: ResourceUsage,
+}
+
+/// エージェントの実行にかかったコスト
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CostReport {
+    /// 1回の反復（実行計画）あたりの予算
+    pub plan_budget: ResourceBudget,
+
+    /// タスクの使用量の合計
+    pub usage: ResourceUsage,
+
+    /// ℐ・𝒞・Θ を通じて言語モデルが使ったトークン数
+    pub model_tokens: u64,
+
+    /// タスクごとのコスト（実行順）
+    pub tasks: Vec<TaskCost>,
+}
+
+impl CostReport {
+    /// タスクの実行記録からコストを集計
+    pub fn from_records(plan_budget: ResourceBudget, records: &[TaskRecord], model_tokens: u64) -> Self {
+        let tasks: Vec<TaskCost> = records
+            .iter()
+            .map(|record| TaskCost {
+                world_version: record.world_version,
+                task_id: record.task_id.clone(),
+                budget: record.budget.clone(),
+                usage: record.usage.clone(),
+            })
+            .collect();
+
+        let mut usage = ResourceUsage::default();
+        for task in &tasks {
+            usage.add(&task.usage);
+        }
+
+        Self {
+            plan_budget,
+            usage,
+            model_tokens,
+            tasks,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allocate_plan_budget() {
+        let tasks = vec![
+            Task::new("read", TaskType::FileRead),
+            Task::new("analyze", TaskType::Analysis),
+            Task::new("write_a", TaskType::FileWrite),
+            Task::new("write_b", TaskType::FileWrite),
+            Task::new("generate", TaskType::CodeGeneration),
+            Task::new("check", TaskType::Validation),
+        ];
+        let plan = ResourceBudget::unlimited()
+            .with_wall_time(Duration::from_secs(6))
+            .with_tokens(1000)
+            .with_file_writes(1);
+
+        let budgets = plan.allocate(&tasks);
+        assert!(budgets.iter().all(|b| b.wall_time_ms == Some(1000)));
+        assert_eq!(budgets[0].tokens, None);
+        assert_eq!(budgets[1].tokens, Some(500));
+        assert_eq!(budgets[2].file_writes, Some(1));
+        assert_eq!(budgets[3].file_writes, Some(0));
+        assert_eq!(budgets[5].processes, None);
+
+        assert!(budgets[2].refusal(&TaskType::FileWrite).is_none());
+        assert!(budgets[3].refusal(&TaskType::FileWrite).is_some());
+
+        let usage = ResourceUsage {
+            wall_time_ms: 1500,
+            tokens: 200,
+            ..Default::default()
+        };
+        assert_eq!(budgets[1].exceeded(&usage), vec!["実行時間 1500ms > 1000ms".to_string()]);
+        assert!(ResourceBudget::unlimited().exceeded(&usage).is_empty());
+    }
+}
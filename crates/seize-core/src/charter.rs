@@ -0,0 +1,477 @@
+//! # 組織憲章 (Charter)
+//!
+//! 組織の原則と、機械的に検証できるルールを憲章ファイル（TOML / YAML / JSON）から読み込む。
+//! 意図解決・世界変換・要求工学はすべて同じ `CharterEngine` で検証する。
+//!
+//! 組み込みの憲章は `charter/default.toml` にある。
+//!
+//! ## ルール
+//!
+//! ```toml
+//! [[rules]]
+//! id = "HUMAN-001"
+//! severity = "error"            # error / warn / info
+//! message = "最終判断は人間が行う必要があります"
+//! rationale = "AIは情報を示す。最終的な選択と責任は人間にある"
+//! applies_to = ["intent", "plan"]   # 省略時はすべて
+//! when = [                       # すべての条件を満たすと該当
+//!   { text_contains = ["自動判断"] },
+//!   { not = { text_contains = ["人間"] } },
+//! ]
+//! ```
+//!
+//! 条件は `Condition` を参照。
+
+use crate::intent::GoalCategory;
+use crate::requirements::{Requirement, RequirementType};
+use crate::world::{Principle, World};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+
+/// 組み込みの憲章
+const BUILTIN_CHARTER: &str = include_str!("../charter/default.toml");
+
+/// 組織憲章
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Charter {
+    /// 憲章の名前
+    pub name: String,
+
+    /// 組織の原則
+    #[serde(default)]
+    pub principles: Vec<Principle>,
+
+    /// 検証ルール
+    #[serde(default)]
+    pub rules: Vec<CharterRule>,
+}
+
+/// 検証ルールの適用対象
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleScope {
+    /// 入力（意図解決）
+    Intent,
+    /// 実行計画（世界変換）
+    Plan,
+    /// 要求
+    Requirement,
+}
+
+/// 違反の重大度
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// 情報
+    Info,
+    /// 警告（処理は続ける）
+    #[default]
+    Warn,
+    /// 違反（処理を止める）
+    Error,
+}
+
+/// 検証ルール
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CharterRule {
+    /// ルールID
+    pub id: String,
+
+    /// 重大度
+    #[serde(default)]
+    pub severity: Severity,
+
+    /// 該当したときのメッセージ
+    pub message: String,
+
+    /// ルールの根拠
+    #[serde(default)]
+    pub rationale: String,
+
+    /// 適用対象（空ならすべて）
+    #[serde(default)]
+    pub applies_to: Vec<RuleScope>,
+
+    /// 該当条件（すべて満たすと該当）
+    pub when: Vec<Condition>,
+}
+
+/// ルールの条件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Condition {
+    /// テキストがいずれかの語を含む（英字は大文字小文字を区別しない）
+    TextContains(Vec<String>),
+
+    /// テキストが正規表現に一致する（読み込み時にコンパイルし、不正な正規表現は読み込みの失敗になる）
+    TextMatches(#[serde(with = "pattern")] Regex),
+
+    /// 目標のカテゴリがいずれかに一致する
+    GoalCategory(Vec<GoalCategory>),
+
+    /// 要求の種類がいずれかに一致する（`NonFunctional` / `NonFunctional.Security` など）
+    RequirementType(Vec<String>),
+
+    /// 要求に受入基準があるか
+    HasAcceptanceCriteria(bool),
+
+    /// 世界のファイルシステムにパスが存在する
+    WorldHasFile(String),
+
+    /// 世界のメタデータがすべて一致する
+    WorldMetadata(HashMap<String, String>),
+
+    /// すべての条件を満たす
+    All(Vec<Condition>),
+
+    /// いずれかの条件を満たす
+    Any(Vec<Condition>),
+
+    /// 条件を満たさない
+    Not(Box<Condition>),
+}
+
+/// 検証の対象
+#[derive(Debug, Clone, Copy)]
+pub struct CharterSubject<'a> {
+    pub scope: RuleScope,
+    pub text: &'a str,
+    pub goal_category: Option<&'a GoalCategory>,
+    pub requirement: Option<&'a Requirement>,
+    pub world: Option<&'a World>,
+}
+
+impl<'a> CharterSubject<'a> {
+    /// テキストだけを持つ検証対象
+    pub fn new(scope: RuleScope, text: &'a str) -> Self {
+        Self {
+            scope,
+            text,
+            goal_category: None,
+            requirement: None,
+            world: None,
+        }
+    }
+
+    /// 目標のカテゴリを設定
+    pub fn with_goal_category(mut self, category: &'a GoalCategory) -> Self {
+        self.goal_category = Some(category);
+        self
+    }
+
+    /// 要求を設定
+    pub fn with_requirement(mut self, requirement: &'a Requirement) -> Self {
+        self.requirement = Some(requirement);
+        self
+    }
+
+    /// 世界を設定
+    pub fn with_world(mut self, world: &'a World) -> Self {
+        self.world = Some(world);
+        self
+    }
+}
+
+impl Condition {
+    /// 条件を評価する（対象が持たない情報に関する条件は満たさない）
+    fn holds(&self, subject: &CharterSubject) -> bool {
+        match self {
+            Self::TextContains(words) => {
+                let text = subject.text.to_lowercase();
+                words.iter().any(|word| text.contains(&word.to_lowercase()))
+            }
+            Self::TextMatches(pattern) => pattern.is_match(subject.text),
+            Self::GoalCategory(categories) => subject.goal_category.is_some_and(|c| categories.contains(c)),
+            Self::RequirementType(types) => subject.requirement.is_some_and(|requirement| {
+                let names = requirement_type_names(&requirement.req_type);
+                types.iter().any(|t| names.contains(t))
+            }),
+            Self::HasAcceptanceCriteria(expected) => subject
+                .requirement
+                .is_some_and(|requirement| requirement.acceptance_criteria.is_empty() != *expected),
+            Self::WorldHasFile(path) => subject.world.is_some_and(|world| world.filesystem.contains_key(path)),
+            Self::WorldMetadata(expected) => subject
+                .world
+                .is_some_and(|world| expected.iter().all(|(k, v)| world.metadata.get(k) == Some(v))),
+            Self::All(conditions) => conditions.iter().all(|c| c.holds(subject)),
+            Self::Any(conditions) => conditions.iter().any(|c| c.holds(subject)),
+            Self::Not(condition) => !condition.holds(subject),
+        }
+    }
+}
+
+/// 正規表現の条件を文字列として読み書きする
+mod pattern {
+    use regex::Regex;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(regex: &Regex, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(regex.as_str())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Regex, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        Regex::new(&pattern).map_err(|e| D::Error::custom(format!("正規表現が不正です ({}): {}", pattern, e)))
+    }
+}
+
+/// 要求の種類の名前（`NonFunctional` なら `NonFunctional.<カテゴリ>` も含む）
+fn requirement_type_names(req_type: &RequirementType) -> Vec<String> {
+    match req_type {
+        RequirementType::NonFunctional { category } => {
+            vec!["NonFunctional".to_string(), format!("NonFunctional.{:?}", category)]
+        }
+        other => vec![format!("{:?}", other)],
+    }
+}
+
+impl CharterRule {
+    fn matches(&self, subject: &CharterSubject) -> bool {
+        (self.applies_to.is_empty() || self.applies_to.contains(&subject.scope))
+            && self.when.iter().all(|condition| condition.holds(subject))
+    }
+}
+
+impl Charter {
+    /// 組み込みの憲章
+    pub fn builtin() -> Self {
+        toml::from_str(BUILTIN_CHARTER).expect("組み込みの憲章の形式が不正です")
+    }
+
+    /// 憲章ファイルを読み込む（拡張子で形式を判断: .toml / .yaml / .yml / .json）
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("憲章ファイルを読み込めません ({}): {}", path.display(), e))?;
+
+        let charter: Self = match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => toml::from_str(&content).map_err(anyhow::Error::from),
+            // serde_yaml は列挙型を YAML タグで表すため、JSON と同じマップ形式で読む
+            Some("yaml") | Some("yml") => serde_yaml::from_str::<serde_json::Value>(&content)
+                .map_err(anyhow::Error::from)
+                .and_then(|value| serde_json::from_value(value).map_err(anyhow::Error::from)),
+            Some("json") => serde_json::from_str(&content).map_err(anyhow::Error::from),
+            _ => anyhow::bail!("憲章ファイルの形式を判断できません（.toml / .yaml / .json）: {}", path.display()),
+        }
+        .map_err(|e| anyhow::anyhow!("憲章ファイルの形式が不正です ({}): {}", path.display(), e))?;
+
+        charter.validate()?;
+        Ok(charter)
+    }
+
+    /// ルールIDの重複を検査する（正規表現は読み込み時に検査済み）
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let mut ids = HashSet::new();
+        for rule in &self.rules {
+            if !ids.insert(rule.id.as_str()) {
+                anyhow::bail!("ルールIDが重複しています: {}", rule.id);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for Charter {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+/// 憲章違反（ルールに該当したもの）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CharterViolation {
+    pub rule_id: String,
+    pub severity: Severity,
+    pub message: String,
+    pub rationale: String,
+    pub scope: RuleScope,
+}
+
+impl fmt::Display for CharterViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.rule_id, self.message)
+    }
+}
+
+/// 重大度 `Error` の違反（処理を止めた理由）
+#[derive(Debug, Clone, thiserror::Error)]
+pub struct CharterViolations(pub Vec<CharterViolation>);
+
+impl fmt::Display for CharterViolations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let messages: Vec<String> = self.0.iter().map(|v| v.to_string()).collect();
+        write!(f, "憲章違反: {}", messages.join(", "))
+    }
+}
+
+/// 憲章に基づく検証
+#[derive(Debug, Clone, Default)]
+pub struct CharterEngine {
+    charter: Charter,
+}
+
+impl CharterEngine {
+    /// 憲章から検証器を作成
+    pub fn new(charter: Charter) -> Self {
+        Self { charter }
+    }
+
+    /// 検証に使う憲章
+    pub fn charter(&self) -> &Charter {
+        &self.charter
+    }
+
+    /// 該当するすべてのルールを違反として返す
+    pub fn evaluate(&self, subject: &CharterSubject) -> Vec<CharterViolation> {
+        self.charter
+            .rules
+            .iter()
+            .filter(|rule| rule.matches(subject))
+            .map(|rule| CharterViolation {
+                rule_id: rule.id.clone(),
+                severity: rule.severity,
+                message: rule.message.clone(),
+                rationale: rule.rationale.clone(),
+                scope: subject.scope,
+            })
+            .collect()
+    }
+
+    /// 検証し、重大度 `Error` の違反があればエラーを返す
+    ///
+    /// それ以外の違反はログに出力して返す
+    pub fn enforce(&self, subject: &CharterSubject) -> Result<Vec<CharterViolation>, CharterViolations> {
+        let (errors, others): (Vec<_>, Vec<_>) = self
+            .evaluate(subject)
+            .into_iter()
+            .partition(|v| v.severity == Severity::Error);
+
+        if !errors.is_empty() {
+            return Err(CharterViolations(errors));
+        }
+
+        for violation in &others {
+            match violation.severity {
+                Severity::Warn => tracing::warn!("警告: {}", violation),
+                _ => tracing::info!("憲章: {}", violation),
+            }
+        }
+
+        Ok(others)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_charter() {
+        let engine = CharterEngine::default();
+        assert_eq!(engine.charter().principles.len(), 4);
+        engine.charter().validate().unwrap();
+
+        let error = engine
+            .enforce(&CharterSubject::new(RuleScope::Intent, "自動判断で承認する"))
+            .unwrap_err();
+        assert_eq!(error.0[0].rule_id, "HUMAN-001");
+        assert!(!error.0[0].rationale.is_empty());
+        assert!(engine
+            .enforce(&CharterSubject::new(RuleScope::Intent, "人間が自動判断の結果を確認する"))
+            .unwrap()
+            .is_empty());
+
+        let violations = engine.evaluate(&CharterSubject::new(RuleScope::Requirement, "全データを保存する"));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].severity, Severity::Warn);
+    }
+
+    #[test]
+    fn test_load_custom_charter() {
+        let dir = std::env::temp_dir().join(format!("seize-charter-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("charter.yaml");
+        std::fs::write(
+            &path,
+            r#"
+name: 社内AI憲章
+principles:
+  - name: 顧客第一
+    description: 顧客の利益を最優先する
+    category: Organization
+rules:
+  - id: OPS-001
+    severity: error
+    message: 本番環境の変更にはレビューが必要です
+    rationale: 障害の影響が顧客に及ぶため
+    applies_to: [plan]
+    when:
+      - text_matches: "本番|production"
+      - not: { world_metadata: { reviewed: "true" } }
+  - id: OPS-002
+    severity: info
+    message: 設計の変更は ADR に残してください
+    when:
+      - goal_category: [SystemDesign]
+"#,
+        )
+        .unwrap();
+
+        let charter = Charter::load(&path).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+        let engine = CharterEngine::new(charter);
+        assert_eq!(engine.charter().principles[0].name, "顧客第一");
+
+        let mut reviewed = World::new();
+        reviewed.metadata.insert("reviewed".to_string(), "true".to_string());
+        let world = World::new();
+        let plan = CharterSubject::new(RuleScope::Plan, "productionに反映する").with_world(&world);
+        assert!(engine.enforce(&plan).is_err());
+        assert!(engine.enforce(&CharterSubject { scope: RuleScope::Intent, ..plan }).is_ok());
+        assert!(engine.enforce(&plan.with_world(&reviewed)).is_ok());
+
+        let design = CharterSubject::new(RuleScope::Intent, "認証基盤").with_goal_category(&GoalCategory::SystemDesign);
+        let violations = engine.enforce(&design).unwrap();
+        assert_eq!(violations[0].rule_id, "OPS-002");
+        assert_eq!(violations[0].severity, Severity::Info);
+    }
+
+    #[test]
+    fn test_invalid_charter_is_rejected() {
+        // 不正な正規表現は読み込みの時点で失敗する
+        let error = toml::from_str::<Charter>(
+            r#"
+name = "壊れた憲章"
+
+[[rules]]
+id = "X-1"
+message = "a"
+when = [{ text_matches = "(" }]
+"#,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("正規表現が不正です"));
+
+        let charter: Charter = toml::from_str(
+            r#"
+name = "重複した憲章"
+
+[[rules]]
+id = "X-1"
+message = "a"
+when = [{ text_matches = "^a" }]
+
+[[rules]]
+id = "X-1"
+message = "b"
+when = [{ text_contains = ["b"] }]
+"#,
+        )
+        .unwrap();
+        assert!(charter.validate().unwrap_err().to_string().contains("X-1"));
+        assert_eq!(toml::to_string(&charter).unwrap().matches(r#"text_matches = "^a""#).count(), 1);
+    }
+}
@@ -0,0 +1,153 @@
+//! # 時計 (Clock)
+//!
+//! 学習・意思決定の記録に付ける時刻の取得元。
+//! テストでは `FixedClock` を注入して時刻を固定できる。
+//!
+//! 旧形式のスナップショットでは時刻が `SystemTime` の Debug 表記
+//! （`SystemTime { tv_sec: .., tv_nsec: .. }`）で保存されていたため、
+//! 読み込み時に RFC 3339 と併せて解釈する。
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Deserializer};
+use std::sync::Mutex;
+
+/// UTC の時刻
+pub type Timestamp = DateTime<Utc>;
+
+/// 時計
+pub trait Clock: Send + Sync {
+    /// 現在時刻
+    fn now(&self) -> Timestamp;
+}
+
+/// システム時計
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Timestamp {
+        Utc::now()
+    }
+}
+
+/// 固定された時計（`advance` で進める）
+#[derive(Debug)]
+pub struct FixedClock {
+    now: Mutex<Timestamp>,
+}
+
+impl FixedClock {
+    /// 指定した時刻で止まった時計を作成
+    pub fn new(now: Timestamp) -> Self {
+        Self { now: Mutex::new(now) }
+    }
+
+    /// 時計を進める
+    pub fn advance(&self, duration: chrono::Duration) {
+        let mut now = self.now.lock().unwrap_or_else(|e| e.into_inner());
+        *now += duration;
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> Timestamp {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// 時刻の文字列を解釈する
+///
+/// RFC 3339 に加えて、旧形式の `SystemTime` の Debug 表記
+/// （Unix: `tv_sec` / `tv_nsec`、Windows: 1601年からの100ns単位の `intervals`）を受け付ける
+pub fn parse_timestamp(text: &str) -> Option<Timestamp> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(text.trim()) {
+        return Some(timestamp.with_timezone(&Utc));
+    }
+
+    let field = |name: &str| -> Option<i64> {
+        let start = text.find(&format!("{}:", name))? + name.len() + 1;
+        let digits: String = text[start..]
+            .trim_start()
+            .chars()
+            .take_while(|c| c.is_ascii_digit() || *c == '-')
+            .collect();
+        digits.parse().ok()
+    };
+
+    if let Some(secs) = field("tv_sec") {
+        let nanos = field("tv_nsec").unwrap_or(0);
+        return Utc.timestamp_opt(secs, nanos as u32).single();
+    }
+
+    if let Some(intervals) = field("intervals") {
+        // 1601-01-01 から 1970-01-01 までの秒数
+        const WINDOWS_EPOCH_OFFSET: i64 = 11_644_473_600;
+        let secs = intervals / 10_000_000 - WINDOWS_EPOCH_OFFSET;
+        let nanos = (intervals % 10_000_000) * 100;
+        return Utc.timestamp_opt(secs, nanos as u32).single();
+    }
+
+    None
+}
+
+/// 旧形式の文字列も受け付けて時刻を読み込む（`#[serde(deserialize_with)]` 用）
+///
+/// 解釈できない文字列はスナップショット全体を読めなくしないよう、
+/// 警告を出して Unix エポックとして扱う
+pub fn deserialize_timestamp<'de, D>(deserializer: D) -> Result<Timestamp, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let text = String::deserialize(deserializer)?;
+    Ok(parse_timestamp(&text).unwrap_or_else(|| {
+        tracing::warn!("時刻を解釈できないため Unix エポックとして扱います: {}", text);
+        DateTime::UNIX_EPOCH
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_current_and_legacy_formats() {
+        let expected = Utc.timestamp_opt(1_700_000_000, 500).unwrap();
+
+        assert_eq!(parse_timestamp(&expected.to_rfc3339()), Some(expected));
+        assert_eq!(
+            parse_timestamp("SystemTime { tv_sec: 1700000000, tv_nsec: 500 }"),
+            Some(expected)
+        );
+        assert_eq!(
+            parse_timestamp("SystemTime { intervals: 133444736000000005 }"),
+            Some(expected)
+        );
+        assert_eq!(parse_timestamp("昨日"), None);
+    }
+
+    #[test]
+    fn test_fixed_clock() {
+        let start = Utc.timestamp_opt(0, 0).unwrap();
+        let clock = FixedClock::new(start);
+        clock.advance(chrono::Duration::seconds(90));
+
+        assert_eq!(clock.now(), start + chrono::Duration::seconds(90));
+    }
+
+    #[test]
+    fn test_legacy_learning_round_trip() {
+        let legacy = r#"{
+            "timestamp": "SystemTime { tv_sec: 1700000000, tv_nsec: 500 }",
+            "content": "旧形式の学習",
+            "source": "UnifiedAgentFormula",
+            "confidence": 0.85
+        }"#;
+        let learning: crate::world::Learning = serde_json::from_str(legacy).unwrap();
+        assert_eq!(learning.timestamp, Utc.timestamp_opt(1_700_000_000, 500).unwrap());
+
+        let json = serde_json::to_value(&learning).unwrap();
+        assert_eq!(json["timestamp"], "2023-11-14T22:13:20.000000500Z");
+        let reloaded: crate::world::Learning = serde_json::from_value(json).unwrap();
+        assert_eq!(reloaded.timestamp, learning.timestamp);
+    }
+}
@@ -3,8 +3,16 @@
 //! 目標を実行可能なタスクに分解
 //! 𝒞 = C₃ ◦ C₂ ◦ C₁
 
+use crate::budget::ResourceBudget;
+use crate::error::{SeizeError, SeizeResult};
 use crate::intent::Goal;
+use crate::lexicon::{Language, Lexicon};
+use crate::model::{self, LanguageModel, ModelRequest, ModelTask};
+use crate::recipe::RecipeBook;
+use crate::scheduler::{Replanner, TaskGraph};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 /// 実行計画
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +25,18 @@ pub struct ExecutionPlan {
 
     /// 実行戦略
     pub strategy: ExecutionStrategy,
+
+    /// まとめる前の目標（1つの目標から作った計画では `goal` のみ）
+    #[serde(default)]
+    pub goals: Vec<Goal>,
+
+    /// 平らにする前のタスクツリー（`tasks` はこれを葉のタスクのDAGにしたもの）
+    #[serde(default)]
+    pub tree: Vec<Task>,
+
+    /// 計画全体の資源の予算（θ₃ でタスクに配分する）
+    #[serde(default)]
+    pub budget: ResourceBudget,
 }
 
 /// タスク
@@ -37,12 +57,70 @@ pub struct Task {
     /// プロンプト（実行用）
     pub prompt: String,
 
+    /// θ₁ が想起した関連する過去の知見（プロンプトとは別に持ち、実行時にモデルへ渡す）
+    #[serde(default)]
+    pub recalled: Vec<String>,
+
+    /// 実行パラメータ（例: path, content, command）
+    #[serde(default)]
+    pub parameters: HashMap<String, String>,
+
     /// 状態
     pub status: TaskStatus,
+
+    /// サブタスク（あれば実行計画ではサブタスクに置き換わる）
+    #[serde(default)]
+    pub subtasks: Vec<Task>,
+
+    /// θ₃ で配分された資源の予算
+    #[serde(default)]
+    pub budget: ResourceBudget,
+}
+
+impl Task {
+    /// モデルに渡すプロンプト（想起した知見があれば末尾に添える）
+    pub fn model_prompt(&self) -> String {
+        if self.recalled.is_empty() {
+            return self.prompt.clone();
+        }
+        let recalled: Vec<String> = self.recalled.iter().map(|r| format!("- {}", r)).collect();
+        format!("{}\n\n## 関連する過去の知見\n{}", self.prompt, recalled.join("\n"))
+    }
+}
+
+#[cfg(test)]
+impl Task {
+    /// テスト用のタスクを作成（説明はIDと同じ。依存タスク・パラメータ・プロンプトはない）
+    pub(crate) fn new(id: &str, task_type: TaskType) -> Self {
+        Self {
+            id: id.to_string(),
+            description: id.to_string(),
+            task_type,
+            dependencies: Vec::new(),
+            prompt: String::new(),
+            recalled: Vec::new(),
+            parameters: HashMap::new(),
+            status: TaskStatus::Pending,
+            subtasks: Vec::new(),
+            budget: ResourceBudget::default(),
+        }
+    }
+
+    /// テスト用に依存タスクを設定
+    pub(crate) fn with_dependencies(mut self, dependencies: &[&str]) -> Self {
+        self.dependencies = dependencies.iter().map(|d| d.to_string()).collect();
+        self
+    }
+
+    /// テスト用にパラメータを設定
+    pub(crate) fn with_parameters(mut self, parameters: &[(&str, &str)]) -> Self {
+        self.parameters = parameters.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        self
+    }
 }
 
 /// タスクタイプ
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum TaskType {
     /// ファイル読み込み
     FileRead,
@@ -78,13 +156,246 @@ pub enum ExecutionStrategy {
     Adaptive,
 }
 
+/// モデルが生成するタスクの下書き（C₂でプロンプトを付与する前）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskDraft {
+    /// タスクID
+    pub id: String,
+
+    /// タスクの説明
+    pub description: String,
+
+    /// タスクタイプ
+    pub task_type: TaskType,
+
+    /// 依存タスク
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+
+    /// 実行パラメータ
+    #[serde(default)]
+    pub parameters: HashMap<String, String>,
+
+    /// サブタスク（IDと依存関係は兄弟のタスクの間で閉じる）
+    #[serde(default)]
+    pub subtasks: Vec<TaskDraft>,
+}
+
+impl TaskDraft {
+    pub(crate) fn into_task(self) -> Task {
+        Task {
+            id: self.id,
+            description: self.description,
+            task_type: self.task_type,
+            dependencies: self.dependencies,
+            prompt: String::new(), // C₂で生成
+            recalled: Vec::new(),
+            parameters: self.parameters,
+            status: TaskStatus::Pending,
+            subtasks: self.subtasks.into_iter().map(TaskDraft::into_task).collect(),
+            budget: ResourceBudget::default(),
+        }
+    }
+}
+
+/// タスクツリーを葉のタスクだけのDAGに平らにする
+///
+/// サブタスクのIDには親のIDを付ける（`task_1/design`）。
+/// 兄弟に依存しないサブタスクは親の依存先を引き継ぎ、親に依存するタスクは
+/// 親の最後のサブタスク（兄弟から依存されないもの）すべての後に実行する
+pub fn flatten_tasks(tree: &[Task]) -> SeizeResult<Vec<Task>> {
+    let mut tasks = Vec::new();
+    flatten_into(tree, None, &[], &mut tasks)?;
+    Ok(tasks)
+}
+
+fn flatten_into(siblings: &[Task], parent: Option<&str>, inherited: &[String], out: &mut Vec<Task>) -> SeizeResult<()> {
+    let full_id = |id: &str| parent.map_or_else(|| id.to_string(), |parent| format!("{}/{}", parent, id));
+    let sinks: HashMap<&str, Vec<String>> = siblings
+        .iter()
+        .map(|task| (task.id.as_str(), sink_ids(task, &full_id(&task.id))))
+        .collect();
+
+    for task in siblings {
+        let id = full_id(&task.id);
+        let dependencies = if task.dependencies.is_empty() {
+            inherited.to_vec()
+        } else {
+            let mut dependencies = Vec::new();
+            for dependency in &task.dependencies {
+                let ids = sinks.get(dependency.as_str()).ok_or_else(|| {
+                    SeizeError::InvalidPlan(format!("タスク {} の依存先 {} が存在しません", id, dependency))
+                })?;
+                dependencies.extend(ids.iter().cloned());
+            }
+            dependencies
+        };
+
+        if task.subtasks.is_empty() {
+            out.push(Task {
+                id,
+                dependencies,
+                ..task.clone()
+            });
+        } else {
+            flatten_into(&task.subtasks, Some(&id), &dependencies, out)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// タスクを平らにしたときの最後の葉のタスクのID
+fn sink_ids(task: &Task, id: &str) -> Vec<String> {
+    if task.subtasks.is_empty() {
+        return vec![id.to_string()];
+    }
+
+    let depended: HashSet<&str> = task
+        .subtasks
+        .iter()
+        .flat_map(|t| t.dependencies.iter().map(String::as_str))
+        .collect();
+    task.subtasks
+        .iter()
+        .filter(|t| !depended.contains(t.id.as_str()))
+        .flat_map(|t| sink_ids(t, &format!("{}/{}", id, t.id)))
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct Decomposition {
+    tasks: Vec<TaskDraft>,
+}
+
+const DECOMPOSE_SYSTEM_PROMPT: &str = "あなたはタスク分解器です。目標を実行可能なタスクに分解し、次の形式のJSONのみを返してください: \
+    {\"tasks\": [{\"id\": string, \"description\": string, \
+    \"task_type\": \"FileRead\"|\"FileWrite\"|\"CodeGeneration\"|\"Validation\"|\"Analysis\"|\"Decision\", \
+    \"dependencies\": [string], \
+    \"parameters\": {\"path\"?: string, \"content\"?: string, \"command\"?: string}, \
+    \"subtasks\"?: [同じ形式のタスク]}]}";
+
+const REPLAN_SYSTEM_PROMPT: &str = "あなたはタスクの再計画器です。失敗したタスクの代わりに目標を進める別の手順を1つ考え、\
+    次の形式のJSONのみを返してください: \
+    {\"tasks\": [{\"id\": string, \"description\": string, \
+    \"task_type\": \"FileRead\"|\"FileWrite\"|\"CodeGeneration\"|\"Validation\"|\"Analysis\"|\"Decision\", \
+    \"parameters\": {\"path\"?: string, \"content\"?: string, \"command\"?: string}}]}。\
+    代わりの手順がなければ空の tasks を返してください。";
+
+/// 失敗したタスクの代わりをモデルに計画させる再計画器
+///
+/// モデルが書いた代わりのタスク（検証のコマンドなど）は、スケジューラが元の計画と同じく承認者に諮ってから実行する
+pub struct ModelReplanner {
+    model: Arc<dyn LanguageModel>,
+
+    /// 失敗したタスクが属する目標
+    goal: Goal,
+}
+
+impl ModelReplanner {
+    /// 目標のための再計画器を作成
+    pub fn new(model: Arc<dyn LanguageModel>, goal: Goal) -> Self {
+        Self { model, goal }
+    }
+}
+
+impl Replanner for ModelReplanner {
+    fn replan(&self, task: &Task, error: &str) -> anyhow::Result<Option<Task>> {
+        // レシピは目標全体の手順なので、代わりのタスクには参考手順を渡さない
+        let mut payload = serde_json::to_value(Goal {
+            description: task.description.clone(),
+            ..self.goal.clone()
+        })?;
+        payload["recipe"] = serde_json::json!([]);
+
+        let request = ModelRequest {
+            task: ModelTask::DecomposeGoal,
+            system: REPLAN_SYSTEM_PROMPT.to_string(),
+            prompt: format!(
+                "目標: {}\n失敗したタスク: {}（{:?}）\nパラメータ: {}\nエラー: {}\n\
+                 同じ手順を繰り返さず、目標を進める別のタスクを1つ返してください。",
+                self.goal.description,
+                task.description,
+                task.task_type,
+                serde_json::to_string(&task.parameters)?,
+                error
+            ),
+            payload,
+        };
+
+        let response = self.model.complete(&request)?;
+        let decomposition = model::parse_json_response::<Decomposition>(&response.text)?;
+        let Some(draft) = decomposition.tasks.into_iter().next() else {
+            return Ok(None);
+        };
+
+        let mut replacement = draft.into_task();
+        replacement.prompt = Lexicon::builtin(self.goal.language).task_prompt(&replacement.task_type, &replacement.description);
+        Ok(Some(replacement))
+    }
+}
+
 /// コマンドスタック
-pub struct CommandStack;
+pub struct CommandStack {
+    /// 推論に使う言語モデル
+    model: Arc<dyn LanguageModel>,
+
+    /// 分解レシピ
+    recipes: Arc<RecipeBook>,
+
+    /// タスクを再帰的に細分化する深さ（0 なら細分化しない）
+    max_depth: usize,
+
+    /// 実行計画に付ける資源の予算
+    budget: ResourceBudget,
+
+    /// 目標によらず使う実行戦略（`None` なら目標の優先度から決める）
+    strategy: Option<ExecutionStrategy>,
+}
 
 impl CommandStack {
     /// 新しいコマンドスタックを作成
     pub fn new() -> Self {
-        Self
+        Self {
+            model: model::default_model(),
+            recipes: Arc::new(RecipeBook::builtin()),
+            max_depth: 0,
+            budget: ResourceBudget::default(),
+            strategy: None,
+        }
+    }
+
+    /// 言語モデルを設定
+    pub fn with_model(mut self, model: Arc<dyn LanguageModel>) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// 分解レシピを設定
+    pub fn with_recipes(mut self, recipes: Arc<RecipeBook>) -> Self {
+        self.recipes = recipes;
+        self
+    }
+
+    /// タスクを再帰的に細分化する深さを設定
+    ///
+    /// 読み書き以外のタスクをモデルにさらに分解させ、モデルが2つ以上の
+    /// サブタスクを返さなくなるか、この深さに達したところで止める
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// 実行計画に付ける資源の予算を設定（既定は無制限）
+    pub fn with_budget(mut self, budget: ResourceBudget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// 実行戦略を設定（既定は優先度が高い目標なら `Adaptive`、それ以外は `Sequential`）
+    pub fn with_strategy(mut self, strategy: ExecutionStrategy) -> Self {
+        self.strategy = Some(strategy);
+        self
     }
 
     /// 目標を実行計画に分解
@@ -92,153 +403,186 @@ impl CommandStack {
     /// ## プロセス
     /// 1. C₁: 構造化 - 目標をタスクツリーに分解
     /// 2. C₂: プロンプト化 - 各タスクを実行可能なプロンプトに変換
-    /// 3. C₃: 連鎖実行 - 依存関係を考慮した実行順序を決定
-    pub fn decompose(&self, goal: Goal) -> anyhow::Result<ExecutionPlan> {
+    /// 3. C₃: 連鎖実行 - タスクツリーを平らにし、依存関係を考慮した実行順序を決定
+    pub fn decompose(&self, goal: Goal) -> SeizeResult<ExecutionPlan> {
         // C₁: 構造化
         let task_tree = self.structure_goal(&goal)?;
 
         // C₂: プロンプト化
-        let prompted_tasks = self.generate_prompts(task_tree)?;
+        let tree = self.generate_prompts(task_tree, goal.language)?;
 
         // C₃: 連鎖実行の準備
+        let tasks = flatten_tasks(&tree)?;
+        TaskGraph::build(&tasks)?;
         let strategy = self.determine_strategy(&goal);
 
         Ok(ExecutionPlan {
+            goals: vec![goal.clone()],
             goal,
-            tasks: prompted_tasks,
+            tasks,
             strategy,
+            tree,
+            budget: self.budget.clone(),
         })
     }
 
-    /// C₁: 目標を構造化されたタスクツリーに分解
-    fn structure_goal(&self, goal: &Goal) -> anyhow::Result<Vec<Task>> {
-        let mut tasks = Vec::new();
-        let mut task_counter = 0;
-
-        // 目標のカテゴリに基づいてタスクを生成
-        match &goal.category {
-            crate::intent::GoalCategory::Understanding => {
-                tasks.push(Task {
-                    id: format!("task_{}", task_counter),
-                    description: "情報収集".to_string(),
-                    task_type: TaskType::FileRead,
-                    dependencies: vec![],
-                    prompt: String::new(), // C₂で生成
-                    status: TaskStatus::Pending,
-                });
-                task_counter += 1;
-
-                tasks.push(Task {
-                    id: format!("task_{}", task_counter),
-                    description: "分析・理解".to_string(),
-                    task_type: TaskType::Analysis,
-                    dependencies: vec!["task_0".to_string()],
-                    prompt: String::new(),
-                    status: TaskStatus::Pending,
-                });
-            }
-            crate::intent::GoalCategory::CodeGeneration => {
-                tasks.push(Task {
-                    id: format!("task_{}", task_counter),
-                    description: "既存コードの読み込み".to_string(),
-                    task_type: TaskType::FileRead,
-                    dependencies: vec![],
-                    prompt: String::new(),
-                    status: TaskStatus::Pending,
-                });
-                task_counter += 1;
-
-                tasks.push(Task {
-                    id: format!("task_{}", task_counter),
-                    description: "コード生成".to_string(),
-                    task_type: TaskType::CodeGeneration,
-                    dependencies: vec!["task_0".to_string()],
-                    prompt: String::new(),
-                    status: TaskStatus::Pending,
-                });
-                task_counter += 1;
-
-                tasks.push(Task {
-                    id: format!("task_{}", task_counter),
-                    description: "検証".to_string(),
-                    task_type: TaskType::Validation,
-                    dependencies: vec!["task_1".to_string()],
-                    prompt: String::new(),
-                    status: TaskStatus::Pending,
-                });
+    /// 複数の目標を1つの実行計画に分解
+    ///
+    /// 目標ごとに分解したタスクツリーを、目標をまとめるタスクのサブタスクにして平らにする
+    /// （`goal_1/task_0`）。依存元の目標の最初のタスク（依存のないもの）は、依存先の目標の
+    /// 最後のタスク（他のタスクから依存されないもの）がすべて終わってから始まる
+    pub fn decompose_all(&self, goals: Vec<Goal>) -> SeizeResult<ExecutionPlan> {
+        if goals.len() <= 1 {
+            let goal = goals
+                .into_iter()
+                .next()
+                .ok_or_else(|| SeizeError::InvalidPlan("目標がありません".to_string()))?;
+            return self.decompose(goal);
+        }
+
+        let combined = Goal::combine(&goals)?;
+        let mut tree = Vec::new();
+
+        for (i, goal) in goals.iter().enumerate() {
+            if let Some(dependency) = goal.depends_on.iter().find(|d| !goals[..i].iter().any(|g| &g.id == *d)) {
+                return Err(SeizeError::InvalidPlan(format!(
+                    "目標 {} の依存先 {} がそれより前にありません",
+                    goal.id, dependency
+                )));
             }
-            crate::intent::GoalCategory::DecisionSupport => {
-                tasks.push(Task {
-                    id: format!("task_{}", task_counter),
-                    description: "情報収集".to_string(),
-                    task_type: TaskType::FileRead,
-                    dependencies: vec![],
-                    prompt: String::new(),
-                    status: TaskStatus::Pending,
-                });
-                task_counter += 1;
-
-                tasks.push(Task {
-                    id: format!("task_{}", task_counter),
-                    description: "選択肢の分析".to_string(),
-                    task_type: TaskType::Analysis,
-                    dependencies: vec!["task_0".to_string()],
-                    prompt: String::new(),
-                    status: TaskStatus::Pending,
-                });
-                task_counter += 1;
-
-                tasks.push(Task {
-                    id: format!("task_{}", task_counter),
-                    description: "意思決定記録の作成".to_string(),
-                    task_type: TaskType::Decision,
-                    dependencies: vec!["task_1".to_string()],
-                    prompt: String::new(),
-                    status: TaskStatus::Pending,
-                });
+
+            let plan = self.decompose(goal.clone())?;
+            tree.push(Task {
+                id: goal.id.clone(),
+                description: goal.description.clone(),
+                task_type: TaskType::Analysis,
+                dependencies: goal.depends_on.clone(),
+                prompt: String::new(),
+                recalled: Vec::new(),
+                parameters: HashMap::new(),
+                status: TaskStatus::Pending,
+                subtasks: plan.tree,
+                budget: ResourceBudget::default(),
+            });
+        }
+
+        let tasks = flatten_tasks(&tree)?;
+        TaskGraph::build(&tasks)?;
+
+        Ok(ExecutionPlan {
+            strategy: self.determine_strategy(&combined),
+            goal: combined,
+            tasks,
+            goals,
+            tree,
+            budget: self.budget.clone(),
+        })
+    }
+
+    /// C₁: 目標を構造化されたタスクツリーに分解
+    ///
+    /// 目標に合うレシピの手順をモデルに参考として渡し、
+    /// モデルが失敗した場合はレシピの手順どおりに分解する。
+    /// そのうえで `max_depth` までタスクを細分化する
+    fn structure_goal(&self, goal: &Goal) -> SeizeResult<Vec<Task>> {
+        let recipe = self.recipes.draft_tasks(goal);
+        let steps: Vec<String> = recipe
+            .iter()
+            .map(|draft| format!("- {} ({:?}): {}", draft.id, draft.task_type, draft.description))
+            .collect();
+        let mut payload = serde_json::to_value(goal)?;
+        payload["recipe"] = serde_json::to_value(&recipe)?;
+
+        let request = ModelRequest {
+            task: ModelTask::DecomposeGoal,
+            system: DECOMPOSE_SYSTEM_PROMPT.to_string(),
+            prompt: format!(
+                "目標: {}\n本質的な問い: {}\nカテゴリ: {:?}\n制約: {}\n参考手順:\n{}",
+                goal.description,
+                goal.essential_question,
+                goal.category,
+                goal.constraints.join(", "),
+                steps.join("\n")
+            ),
+            payload,
+        };
+
+        let mut tasks = self.request_tasks(&request).unwrap_or_else(|e| {
+            tracing::warn!("モデル {} によるタスク分解に失敗したためレシピの手順を使用します: {}", self.model.name(), e);
+            recipe
+                .into_iter()
+                .map(TaskDraft::into_task)
+                .collect()
+        });
+
+        self.refine(goal, &mut tasks, 1)?;
+        Ok(tasks)
+    }
+
+    /// タスクを `max_depth` の深さまで再帰的に細分化する
+    fn refine(&self, goal: &Goal, tasks: &mut [Task], depth: usize) -> SeizeResult<()> {
+        if depth > self.max_depth {
+            return Ok(());
+        }
+
+        for task in tasks.iter_mut() {
+            if task.subtasks.is_empty() && !matches!(task.task_type, TaskType::FileRead | TaskType::FileWrite) {
+                task.subtasks = self.decompose_task(goal, task)?;
             }
-            _ => {
-                // デフォルトタスク
-                tasks.push(Task {
-                    id: format!("task_{}", task_counter),
-                    description: goal.description.clone(),
-                    task_type: TaskType::Analysis,
-                    dependencies: vec![],
-                    prompt: String::new(),
-                    status: TaskStatus::Pending,
-                });
+            self.refine(goal, &mut task.subtasks, depth + 1)?;
+        }
+
+        Ok(())
+    }
+
+    /// タスクをサブタスクに分解する（分解できない・不要なら空）
+    fn decompose_task(&self, goal: &Goal, task: &Task) -> SeizeResult<Vec<Task>> {
+        // レシピは目標全体の手順なので、タスクの分解には参考手順を渡さない
+        let mut payload = serde_json::to_value(Goal {
+            description: task.description.clone(),
+            ..goal.clone()
+        })?;
+        payload["recipe"] = serde_json::json!([]);
+
+        let request = ModelRequest {
+            task: ModelTask::DecomposeGoal,
+            system: DECOMPOSE_SYSTEM_PROMPT.to_string(),
+            prompt: format!(
+                "目標: {}\nこの目標のためのタスク「{}」（{:?}）を、より小さなサブタスクに分解してください。\
+                 これ以上分解する必要がなければ空の tasks を返してください。",
+                goal.description, task.description, task.task_type
+            ),
+            payload,
+        };
+
+        match self.request_tasks(&request) {
+            Ok(subtasks) if subtasks.len() >= 2 => Ok(subtasks),
+            Ok(_) => Ok(Vec::new()),
+            Err(e) => {
+                tracing::debug!("タスク {} は細分化しません: {}", task.id, e);
+                Ok(Vec::new())
             }
         }
+    }
 
+    /// モデルにタスクの分解を依頼し、平らにして実行できるタスクツリーかを検査する
+    fn request_tasks(&self, request: &ModelRequest) -> anyhow::Result<Vec<Task>> {
+        let response = self.model.complete(request)?;
+        let decomposition = model::parse_json_response::<Decomposition>(&response.text)?;
+        let tasks: Vec<Task> = decomposition.tasks.into_iter().map(TaskDraft::into_task).collect();
+        if tasks.is_empty() {
+            anyhow::bail!("タスクが1つも生成されませんでした");
+        }
+        TaskGraph::build(&flatten_tasks(&tasks)?)?;
         Ok(tasks)
     }
 
-    /// C₂: タスクを実行可能なプロンプトに変換
-    fn generate_prompts(&self, mut tasks: Vec<Task>) -> anyhow::Result<Vec<Task>> {
+    /// C₂: タスクを実行可能なプロンプトに変換（目標の言語の語彙を使う）
+    fn generate_prompts(&self, mut tasks: Vec<Task>, language: Language) -> SeizeResult<Vec<Task>> {
+        let lexicon = Lexicon::builtin(language);
         for task in &mut tasks {
-            task.prompt = match task.task_type {
-                TaskType::FileRead => {
-                    format!("以下のファイルを読み込んで分析してください:\n{}", task.description)
-                }
-                TaskType::FileWrite => {
-                    format!("以下の内容でファイルを作成してください:\n{}", task.description)
-                }
-                TaskType::CodeGeneration => {
-                    format!("以下の要件に基づいてコードを生成してください:\n{}", task.description)
-                }
-                TaskType::Validation => {
-                    format!("以下の内容を検証してください:\n{}", task.description)
-                }
-                TaskType::Analysis => {
-                    format!("以下について分析してください:\n{}", task.description)
-                }
-                TaskType::Decision => {
-                    format!(
-                        "以下の意思決定を記録してください（目的・入力・選択肢・根拠を含む）:\n{}",
-                        task.description
-                    )
-                }
-            };
+            task.prompt = lexicon.task_prompt(&task.task_type, &task.description);
+            task.subtasks = self.generate_prompts(std::mem::take(&mut task.subtasks), language)?;
         }
 
         Ok(tasks)
@@ -246,6 +590,10 @@ impl CommandStack {
 
     /// C₃: 実行戦略を決定
     fn determine_strategy(&self, goal: &Goal) -> ExecutionStrategy {
+        if let Some(strategy) = &self.strategy {
+            return strategy.clone();
+        }
+
         // 優先度が高い場合は適応的実行
         if goal.priority >= crate::intent::Priority::High {
             ExecutionStrategy::Adaptive
@@ -264,20 +612,162 @@ impl Default for CommandStack {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::intent::{Goal, GoalCategory, Priority};
+    use crate::intent::{Goal, GoalCategory};
+    use crate::model::ReplayModel;
 
     #[test]
     fn test_command_decomposition() {
         let stack = CommandStack::new();
+        let goal = Goal::for_test("コードを生成する", GoalCategory::CodeGeneration);
+
+        let plan = stack.decompose(goal.clone()).unwrap();
+        assert_eq!(plan.tasks.len(), 3); // Read, Generate, Validate
+
+        // 英語の目標には英語のタスクとプロンプトを使う
         let goal = Goal {
-            description: "コードを生成する".to_string(),
-            essential_question: "何を実現したいのか?".to_string(),
-            category: GoalCategory::CodeGeneration,
-            priority: Priority::Medium,
-            constraints: vec![],
+            description: "Fix the login bug".to_string(),
+            language: Language::En,
+            ..goal
+        };
+        let plan = stack.decompose(goal).unwrap();
+        assert_eq!(plan.tasks[0].description, "Read the existing code");
+        assert!(plan.tasks[0].prompt.starts_with("Read and analyze the following file:"));
+    }
+
+    #[test]
+    fn test_command_decomposition_with_replay_model() {
+        let model = ReplayModel::new().with_response(
+            ModelTask::DecomposeGoal,
+            None,
+            r#"{"tasks": [
+                {"id": "read", "description": "設計書を読む", "task_type": "FileRead"},
+                {"id": "review", "description": "設計をレビューする", "task_type": "Analysis", "dependencies": ["read"]}
+            ]}"#,
+        );
+        let stack = CommandStack::new().with_model(Arc::new(model));
+        let goal = Goal {
+            essential_question: "何を守りたいのか?".to_string(),
+            ..Goal::for_test("設計をレビューする", GoalCategory::SystemDesign)
+        };
+
+        let plan = stack.decompose(goal.clone()).unwrap();
+        assert_eq!(plan.tasks.len(), 2);
+        assert_eq!(plan.tasks[1].dependencies, vec!["read".to_string()]);
+        assert!(!plan.tasks[1].prompt.is_empty());
+
+        // 循環する分解結果は既定のタスクに置き換える
+        let cyclic = ReplayModel::new().with_response(
+            ModelTask::DecomposeGoal,
+            None,
+            r#"{"tasks": [
+                {"id": "a", "description": "A", "task_type": "Analysis", "dependencies": ["b"]},
+                {"id": "b", "description": "B", "task_type": "Analysis", "dependencies": ["a"]}
+            ]}"#,
+        );
+        let plan = CommandStack::new().with_model(Arc::new(cyclic)).decompose(goal).unwrap();
+        assert_eq!(plan.tasks[0].id, "task_0");
+    }
+
+    #[test]
+    fn test_decompose_multiple_goals() {
+        let goals = crate::IntentResolver::default()
+            .resolve_all("緊急で障害を調査してから、コードを修正して、判断を記録して")
+            .unwrap();
+        assert_eq!(goals.len(), 3);
+        assert_eq!(goals[1].depends_on, vec!["goal_0".to_string()]);
+        assert!(goals[2].depends_on.is_empty());
+
+        let plan = CommandStack::new().decompose_all(goals).unwrap();
+        assert_eq!(plan.goals.len(), 3);
+        assert!(matches!(plan.strategy, ExecutionStrategy::Adaptive));
+
+        // goal_1 の最初のタスクは goal_0 の最後のタスクの後に始まる
+        let first = plan.tasks.iter().find(|t| t.id == "goal_1/task_0").unwrap();
+        assert_eq!(first.dependencies, vec!["goal_0/task_1".to_string()]);
+        let last = plan.tasks.iter().find(|t| t.id == "goal_1/task_2").unwrap();
+        assert_eq!(last.dependencies, vec!["goal_1/task_1".to_string()]);
+    }
+
+    #[test]
+    fn test_recursive_decomposition_is_flattened() {
+        let model = ReplayModel::new()
+            .with_response(
+                ModelTask::DecomposeGoal,
+                Some("「認証を実装する」"),
+                r#"{"tasks": [
+                    {"id": "design", "description": "認証方式を設計する", "task_type": "Analysis"},
+                    {"id": "implement", "description": "ログイン処理を書く", "task_type": "CodeGeneration", "dependencies": ["design"]},
+                    {"id": "test", "description": "テストを書く", "task_type": "CodeGeneration", "dependencies": ["implement"]}
+                ]}"#,
+            )
+            .with_response(
+                ModelTask::DecomposeGoal,
+                Some("参考手順"),
+                r#"{"tasks": [
+                    {"id": "read", "description": "既存コードを読む", "task_type": "FileRead"},
+                    {"id": "auth", "description": "認証を実装する", "task_type": "CodeGeneration", "dependencies": ["read"]},
+                    {"id": "check", "description": "結合テスト", "task_type": "Validation", "dependencies": ["auth"]}
+                ]}"#,
+            );
+        let stack = CommandStack::new().with_model(Arc::new(model)).with_max_depth(1);
+        let goal = Goal {
+            essential_question: "何を守りたいのか?".to_string(),
+            ..Goal::for_test("認証機能を実装する", GoalCategory::CodeGeneration)
         };
 
         let plan = stack.decompose(goal).unwrap();
-        assert_eq!(plan.tasks.len(), 3); // Read, Generate, Validate
+        assert_eq!(plan.tree.len(), 3);
+        assert_eq!(plan.tree[1].subtasks.len(), 3);
+        assert!(!plan.tree[1].subtasks[0].prompt.is_empty());
+
+        let ids: Vec<&str> = plan.tasks.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["read", "auth/design", "auth/implement", "auth/test", "check"]);
+        assert_eq!(plan.tasks[1].dependencies, vec!["read".to_string()]);
+        assert_eq!(plan.tasks[4].dependencies, vec!["auth/test".to_string()]);
+        assert!(plan.tasks.iter().all(|t| t.subtasks.is_empty()));
+    }
+
+    #[test]
+    fn test_strategy_option_and_model_replanner() {
+        let goal = Goal {
+            essential_question: "何を保証したいのか?".to_string(),
+            ..Goal::for_test("テストを通す", GoalCategory::CodeGeneration)
+        };
+
+        let plan = CommandStack::new().decompose(goal.clone()).unwrap();
+        assert!(matches!(plan.strategy, ExecutionStrategy::Sequential));
+        let plan = CommandStack::new()
+            .with_strategy(ExecutionStrategy::Parallel)
+            .decompose(goal.clone())
+            .unwrap();
+        assert!(matches!(plan.strategy, ExecutionStrategy::Parallel));
+
+        let failed = TaskDraft {
+            id: "test".to_string(),
+            description: "テストを実行".to_string(),
+            task_type: TaskType::Validation,
+            dependencies: vec![],
+            parameters: HashMap::from([("command".to_string(), "make test".to_string())]),
+            subtasks: vec![],
+        }
+        .into_task();
+
+        let model = ReplayModel::new().with_response(
+            ModelTask::DecomposeGoal,
+            Some("make test"),
+            r#"{"tasks": [{"id": "retry", "description": "cargo でテストを実行", "task_type": "Validation",
+                "parameters": {"command": "cargo test"}}]}"#,
+        );
+        let replacement = ModelReplanner::new(Arc::new(model), goal.clone())
+            .replan(&failed, "make: command not found")
+            .unwrap()
+            .unwrap();
+        assert_eq!(replacement.description, "cargo でテストを実行");
+        assert_eq!(replacement.parameters["command"], "cargo test");
+        assert!(!replacement.prompt.is_empty());
+
+        // 代わりの手順を持たないモデルは再計画しない
+        let heuristic = ModelReplanner::new(model::default_model(), goal);
+        assert!(heuristic.replan(&failed, "make: command not found").unwrap().is_none());
     }
 }
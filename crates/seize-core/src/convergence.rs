@@ -0,0 +1,377 @@
+//! # 収束判定 (Convergence)
+//!
+//! ```text
+//! lim_{n→∞} World_n = World_∞
+//! ```
+//!
+//! 反復ごとに世界の変化を評価し、反復を止めるかどうかを判定する。
+//!
+//! - `GoalSatisfaction`: 目標の受入チェック（タスクの完了・検証）が通ったか
+//! - `WorldFixpoint`: 連続する世界の間に差分がないか（不動点）
+//! - `Budget`: トークン・時間の予算を使い切ったか
+//! - `AllOf` / `AnyOf`: 複数の判定基準の合成
+
+use crate::command::TaskStatus;
+use crate::intent::Goal;
+use crate::world::World;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 収束判定に渡す反復の状態
+pub struct ConvergenceState<'a> {
+    /// 反復回数（1始まり）
+    pub iteration: usize,
+
+    /// この反復で解決された目標
+    pub goal: &'a Goal,
+
+    /// 反復前の世界
+    pub previous: &'a World,
+
+    /// 反復後の世界
+    pub current: &'a World,
+
+    /// 実行開始からの経過時間
+    pub elapsed: Duration,
+
+    /// 実行開始からのトークン使用量
+    pub tokens_used: u64,
+}
+
+impl ConvergenceState<'_> {
+    /// この反復で実行されたタスクの記録
+    pub fn iteration_records(&self) -> impl Iterator<Item = &crate::world::TaskRecord> {
+        let version = self.previous.version;
+        self.current
+            .context
+            .task_records
+            .iter()
+            .filter(move |record| record.world_version == version)
+    }
+}
+
+/// 判定結果
+#[derive(Debug, Clone)]
+pub struct Evaluation {
+    /// 収束したか
+    pub converged: bool,
+
+    /// 収束度（0.0〜1.0）
+    pub score: f64,
+
+    /// 収束を判定した基準の名前（未収束なら `None`）
+    pub fired_by: Option<String>,
+}
+
+/// 収束判定基準
+pub trait ConvergenceCriterion: Send + Sync {
+    /// 基準の名前
+    fn name(&self) -> String;
+
+    /// 反復の状態を評価
+    fn evaluate(&self, state: &ConvergenceState) -> Evaluation;
+}
+
+fn evaluation(criterion: &dyn ConvergenceCriterion, converged: bool, score: f64) -> Evaluation {
+    Evaluation {
+        converged,
+        score: score.clamp(0.0, 1.0),
+        fired_by: converged.then(|| criterion.name()),
+    }
+}
+
+/// 受入チェック（世界に対する述語）
+pub type AcceptanceCheck = Arc<dyn Fn(&World) -> bool + Send + Sync>;
+
+/// 目標充足
+///
+/// この反復のタスクがすべて完了したこと（検証タスクは受入チェックとして扱う）と、
+/// 追加の受入チェックが通った割合を収束度とし、閾値以上で収束とみなす
+pub struct GoalSatisfaction {
+    threshold: f64,
+    checks: Vec<(String, AcceptanceCheck)>,
+}
+
+impl GoalSatisfaction {
+    /// 新しい判定基準を作成
+    pub fn new(threshold: f64) -> Self {
+        Self {
+            threshold,
+            checks: Vec::new(),
+        }
+    }
+
+    /// 受入チェックを追加
+    pub fn with_check(
+        mut self,
+        name: impl Into<String>,
+        check: impl Fn(&World) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.checks.push((name.into(), Arc::new(check)));
+        self
+    }
+}
+
+impl ConvergenceCriterion for GoalSatisfaction {
+    fn name(&self) -> String {
+        "goal_satisfaction".to_string()
+    }
+
+    fn evaluate(&self, state: &ConvergenceState) -> Evaluation {
+        let mut passed = 0;
+        let mut total = 0;
+
+        for record in state.iteration_records() {
+            total += 1;
+            if record.status == TaskStatus::Completed {
+                passed += 1;
+            }
+        }
+
+        for (name, check) in &self.checks {
+            total += 1;
+            if check(state.current) {
+                passed += 1;
+            } else {
+                tracing::debug!("受入チェック未達: {}", name);
+            }
+        }
+
+        if total == 0 {
+            return evaluation(self, false, 0.0);
+        }
+
+        let score = passed as f64 / total as f64;
+        evaluation(self, score >= self.threshold, score)
+    }
+}
+
+/// 世界の不動点
+///
+/// 反復の前後でファイルシステムに差分がなく、
+/// タスクの結果（IDと状態）が直前の反復と同じなら収束とみなす
+pub struct WorldFixpoint;
+
+impl ConvergenceCriterion for WorldFixpoint {
+    fn name(&self) -> String {
+        "world_fixpoint".to_string()
+    }
+
+    fn evaluate(&self, state: &ConvergenceState) -> Evaluation {
+        let outcomes = |world: &World, version: usize| {
+            let mut outcomes: Vec<(String, TaskStatus)> = world
+                .context
+                .task_records
+                .iter()
+                .filter(|record| record.world_version == version)
+                .map(|record| (record.task_id.clone(), record.status.clone()))
+                .collect();
+            outcomes.sort_by(|a, b| a.0.cmp(&b.0));
+            outcomes
+        };
+
+        let current = outcomes(state.current, state.previous.version);
+        let prior = match state.previous.version.checked_sub(1) {
+            Some(version) => outcomes(state.previous, version),
+            None => Vec::new(),
+        };
+        let stable = !prior.is_empty() && prior == current;
+
+        let diff = state.previous.diff(state.current);
+        let files = state.current.filesystem.len().max(state.previous.filesystem.len()).max(1);
+        let unchanged = 1.0 - diff.changes.len() as f64 / files as f64;
+        let score = if stable { unchanged } else { unchanged * 0.5 };
+
+        evaluation(self, diff.is_empty() && stable, score)
+    }
+}
+
+/// 予算
+///
+/// トークン使用量・経過時間のいずれかが上限に達したら反復を止める。
+/// 収束度は予算の消費率
+#[derive(Debug, Clone, Default)]
+pub struct Budget {
+    pub max_tokens: Option<u64>,
+    pub max_duration: Option<Duration>,
+}
+
+impl ConvergenceCriterion for Budget {
+    fn name(&self) -> String {
+        "budget".to_string()
+    }
+
+    fn evaluate(&self, state: &ConvergenceState) -> Evaluation {
+        let token_ratio = self
+            .max_tokens
+            .map(|max| state.tokens_used as f64 / max.max(1) as f64)
+            .unwrap_or(0.0);
+        let time_ratio = self
+            .max_duration
+            .map(|max| state.elapsed.as_secs_f64() / max.as_secs_f64().max(f64::EPSILON))
+            .unwrap_or(0.0);
+
+        let ratio = token_ratio.max(time_ratio);
+        evaluation(self, ratio >= 1.0, ratio)
+    }
+}
+
+/// すべての基準が収束したら収束（収束度は最小値）
+pub struct AllOf(pub Vec<Box<dyn ConvergenceCriterion>>);
+
+impl ConvergenceCriterion for AllOf {
+    fn name(&self) -> String {
+        let names: Vec<String> = self.0.iter().map(|c| c.name()).collect();
+        format!("all({})", names.join(", "))
+    }
+
+    fn evaluate(&self, state: &ConvergenceState) -> Evaluation {
+        let evaluations: Vec<Evaluation> = self.0.iter().map(|c| c.evaluate(state)).collect();
+        let converged = !evaluations.is_empty() && evaluations.iter().all(|e| e.converged);
+        let score = evaluations.iter().map(|e| e.score).fold(f64::INFINITY, f64::min);
+        evaluation(self, converged, if score.is_finite() { score } else { 0.0 })
+    }
+}
+
+/// いずれかの基準が収束したら収束（収束度は最大値）
+pub struct AnyOf(pub Vec<Box<dyn ConvergenceCriterion>>);
+
+impl ConvergenceCriterion for AnyOf {
+    fn name(&self) -> String {
+        let names: Vec<String> = self.0.iter().map(|c| c.name()).collect();
+        format!("any({})", names.join(", "))
+    }
+
+    fn evaluate(&self, state: &ConvergenceState) -> Evaluation {
+        let evaluations: Vec<Evaluation> = self.0.iter().map(|c| c.evaluate(state)).collect();
+        let score = evaluations.iter().map(|e| e.score).fold(0.0, f64::max);
+
+        Evaluation {
+            converged: evaluations.iter().any(|e| e.converged),
+            score,
+            // 最初に収束した基準を報告する
+            fired_by: evaluations.into_iter().find_map(|e| e.fired_by),
+        }
+    }
+}
+
+/// 既定の判定基準: 目標充足（閾値 `threshold`）または世界の不動点
+pub fn default_criterion(threshold: f64) -> AnyOf {
+    AnyOf(vec![Box::new(GoalSatisfaction::new(threshold)), Box::new(WorldFixpoint)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::TaskType;
+    use crate::intent::GoalCategory;
+    use crate::world::TaskRecord;
+
+    fn goal() -> Goal {
+        Goal {
+            essential_question: "何を知りたいのか?".to_string(),
+            ..Goal::for_test("調査", GoalCategory::Understanding)
+        }
+    }
+
+    fn record(status: TaskStatus) -> TaskRecord {
+        TaskRecord {
+            world_version: 0,
+            task_id: "task_0".to_string(),
+            description: "調査".to_string(),
+            task_type: TaskType::Analysis,
+            status,
+            attempts: 1,
+            duration_ms: 0,
+            summary: String::new(),
+            error: None,
+            budget: Default::default(),
+            usage: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_goal_satisfaction_and_fixpoint() {
+        let goal = goal();
+        let previous = World::new();
+        let mut current = previous.clone();
+        current.context.task_records.push(record(TaskStatus::Completed));
+        current.context.task_records.push(record(TaskStatus::Failed));
+        current.filesystem.insert("notes.md".to_string(), "memo".to_string());
+
+        let state = ConvergenceState {
+            iteration: 1,
+            goal: &goal,
+            previous: &previous,
+            current: &current,
+            elapsed: Duration::from_secs(1),
+            tokens_used: 0,
+        };
+
+        let satisfied = GoalSatisfaction::new(0.8).evaluate(&state);
+        assert!(!satisfied.converged);
+        assert_eq!(satisfied.score, 0.5);
+
+        assert!(!WorldFixpoint.evaluate(&state).converged);
+
+        let any = AnyOf(vec![Box::new(GoalSatisfaction::new(0.5)), Box::new(WorldFixpoint)]).evaluate(&state);
+        assert_eq!(any.fired_by.as_deref(), Some("goal_satisfaction"));
+
+        let all = AllOf(vec![Box::new(GoalSatisfaction::new(0.5)), Box::new(WorldFixpoint)]).evaluate(&state);
+        assert!(!all.converged);
+        assert_eq!(all.score, 0.0);
+    }
+
+    #[test]
+    fn test_fixpoint_requires_stable_outcomes() {
+        let goal = goal();
+        let mut previous = World::new();
+        previous.context.task_records.push(record(TaskStatus::Completed));
+        previous.advance();
+
+        let mut current = previous.clone();
+        let mut repeated = record(TaskStatus::Completed);
+        repeated.world_version = 1;
+        current.context.task_records.push(repeated);
+
+        let state = ConvergenceState {
+            iteration: 2,
+            goal: &goal,
+            previous: &previous,
+            current: &current,
+            elapsed: Duration::ZERO,
+            tokens_used: 0,
+        };
+        let evaluation = WorldFixpoint.evaluate(&state);
+        assert!(evaluation.converged);
+        assert_eq!(evaluation.score, 1.0);
+    }
+
+    #[test]
+    fn test_budget() {
+        let goal = goal();
+        let world = World::new();
+        let state = ConvergenceState {
+            iteration: 1,
+            goal: &goal,
+            previous: &world,
+            current: &world,
+            elapsed: Duration::from_secs(5),
+            tokens_used: 1_500,
+        };
+
+        let within = Budget {
+            max_tokens: Some(3_000),
+            max_duration: Some(Duration::from_secs(60)),
+        };
+        assert!(!within.evaluate(&state).converged);
+
+        let exhausted = Budget {
+            max_tokens: Some(1_000),
+            max_duration: None,
+        };
+        let evaluation = exhausted.evaluate(&state);
+        assert!(evaluation.converged);
+        assert_eq!(evaluation.fired_by.as_deref(), Some("budget"));
+    }
+}
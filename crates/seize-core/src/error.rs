@@ -0,0 +1,68 @@
+//! # エラー (Errors)
+//!
+//! ℐ・𝒞・Θ・統一エージェント・要求工学の公開APIが返すエラー。
+//! 利用側は憲章違反・不正な計画・入出力の失敗などを種類ごとに区別できる。
+
+use crate::charter::CharterViolations;
+use std::path::PathBuf;
+
+/// seize-core のエラー
+#[derive(Debug, thiserror::Error)]
+pub enum SeizeError {
+    /// 重大度 Error の憲章ルールに該当した
+    #[error(transparent)]
+    CharterViolation(#[from] CharterViolations),
+
+    /// 実行計画が不正（重複したタスクID・存在しない依存先など）
+    #[error("実行計画が不正です: {0}")]
+    InvalidPlan(String),
+
+    /// 変換フェーズのパイプラインの構成が不正（存在しないフェーズ・重複したフェーズ）
+    #[error("変換フェーズの構成が不正です: {0}")]
+    InvalidPipeline(String),
+
+    /// タスクの依存関係が循環している
+    #[error("タスクの依存関係が循環しています: {}", .0.join(", "))]
+    CyclicDependency(Vec<String>),
+
+    /// タスクの実行を続けられない（個々のタスクの失敗は世界に記録され、エラーにはならない）
+    #[error("タスクの実行に失敗しました: {0}")]
+    ExecutionFailed(String),
+
+    /// 実行計画全体の資源の予算を超えた
+    #[error("実行計画の予算を超えました: {0}")]
+    BudgetExceeded(String),
+
+    /// 作業ディレクトリの外を指すパス（絶対パス・`..` を含むパス）
+    #[error("作業ディレクトリの外を指すパスは使えません: {0}")]
+    UnsafePath(String),
+
+    /// 承認者の呼び出しに失敗した
+    #[error("承認者の呼び出しに失敗しました: {0}")]
+    Approval(String),
+
+    /// シリアライズ・デシリアライズに失敗した
+    #[error("シリアライズに失敗しました: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    /// ファイルの読み書きに失敗した
+    #[error("ファイルを読み書きできません ({}): {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+/// seize-core の結果型
+pub type SeizeResult<T> = Result<T, SeizeError>;
+
+impl SeizeError {
+    /// パス付きの入出力エラー
+    pub(crate) fn io(path: impl Into<PathBuf>, source: std::io::Error) -> Self {
+        Self::Io {
+            path: path.into(),
+            source,
+        }
+    }
+}
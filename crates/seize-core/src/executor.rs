@@ -0,0 +1,538 @@
+//! # タスク実行器 (Task Executor)
+//!
+//! θ₄ Execute でタスクを実際に実行する。
+//! タスクタイプごとに実行器を登録し、必要に応じて差し替えられる。
+
+use crate::budget::{ResourceBudget, ResourceUsage};
+use crate::command::{Task, TaskType};
+use crate::error::SeizeResult;
+use crate::filesystem;
+use crate::model::{LanguageModel, ModelRequest, ModelTask};
+use crate::transaction::Compensation;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output, Stdio};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// タスク実行時のコンテキスト
+#[derive(Debug, Clone)]
+pub struct TaskContext {
+    /// 作業ディレクトリ（相対パスの基準）
+    pub working_directory: PathBuf,
+
+    /// 依存タスクの出力（タスクID → 出力）
+    pub dependency_outputs: HashMap<String, String>,
+
+    /// θ₃ で配分された資源の予算
+    pub budget: ResourceBudget,
+
+    /// 書き込みを段階的に留める場合の、世界のファイルシステム（パス → 内容）
+    ///
+    /// `Some` ならファイル書き込みは作業ディレクトリに触れずに結果にだけ残し、
+    /// ファイル読み込みはまずここから探し、検証はここを反映した作業ディレクトリの写しで実行する。
+    /// `None` なら作業ディレクトリを直接読み書きする
+    pub staged_files: Option<Arc<HashMap<String, String>>>,
+}
+
+/// タスクの実行結果
+#[derive(Debug, Clone, Default)]
+pub struct TaskOutcome {
+    /// 実行結果の出力
+    pub output: String,
+
+    /// 読み書きしたファイル（パス → 内容）。世界のファイルシステムに反映される
+    pub files: HashMap<String, String>,
+
+    /// Θ が失敗したときに副作用を取り消す補償操作
+    pub compensations: Vec<Compensation>,
+
+    /// 使った資源（実行時間はスケジューラが計測する）
+    pub usage: ResourceUsage,
+}
+
+impl TaskOutcome {
+    /// 出力のみの結果を作成
+    pub fn output(output: impl Into<String>) -> Self {
+        Self {
+            output: output.into(),
+            files: HashMap::new(),
+            compensations: Vec::new(),
+            usage: ResourceUsage::default(),
+        }
+    }
+}
+
+/// タスク実行器
+pub trait TaskExecutor: Send + Sync {
+    /// タスクを実行
+    fn execute(&self, task: &Task, context: &TaskContext) -> anyhow::Result<TaskOutcome>;
+}
+
+/// タスクタイプごとの実行器の登録簿
+#[derive(Clone)]
+pub struct ExecutorRegistry {
+    executors: HashMap<TaskType, Arc<dyn TaskExecutor>>,
+}
+
+impl ExecutorRegistry {
+    /// 既定の実行器を登録した登録簿を作成
+    ///
+    /// - `FileRead` / `FileWrite`: ファイルシステムを直接操作
+    /// - `Validation`: シェルコマンドを実行
+    /// - `Analysis` / `CodeGeneration` / `Decision`: 言語モデルを呼び出す
+    pub fn new(model: Arc<dyn LanguageModel>) -> Self {
+        let mut registry = Self {
+            executors: HashMap::new(),
+        };
+        registry.register(TaskType::FileRead, Arc::new(FileReadExecutor));
+        registry.register(TaskType::FileWrite, Arc::new(FileWriteExecutor));
+        registry.register(TaskType::Validation, Arc::new(ValidationExecutor));
+        registry.register_model(model);
+        registry
+    }
+
+    /// 実行器を登録（既存の登録は置き換える）
+    pub fn register(&mut self, task_type: TaskType, executor: Arc<dyn TaskExecutor>) {
+        self.executors.insert(task_type, executor);
+    }
+
+    /// 言語モデルを使う実行器を登録し直す
+    pub fn register_model(&mut self, model: Arc<dyn LanguageModel>) {
+        let executor: Arc<dyn TaskExecutor> = Arc::new(ModelExecutor::new(model));
+        for task_type in [TaskType::Analysis, TaskType::CodeGeneration, TaskType::Decision] {
+            self.register(task_type, executor.clone());
+        }
+    }
+
+    /// タスクタイプに対応する実行器を取得
+    pub fn get(&self, task_type: &TaskType) -> Option<Arc<dyn TaskExecutor>> {
+        self.executors.get(task_type).cloned()
+    }
+}
+
+/// タスクのパラメータを取得
+fn parameter<'a>(task: &'a Task, key: &str) -> anyhow::Result<&'a str> {
+    task.parameters
+        .get(key)
+        .map(String::as_str)
+        .ok_or_else(|| anyhow::anyhow!("タスク {} に {} パラメータがありません", task.id, key))
+}
+
+/// 作業ディレクトリを基準にパスを解決
+///
+/// 作業ディレクトリの外を指すパス（絶対パス・`..` を含むパス）は拒否する
+fn resolve_path(context: &TaskContext, path: &str) -> SeizeResult<PathBuf> {
+    Ok(context.working_directory.join(filesystem::relative_path(path)?))
+}
+
+/// ファイル読み込み実行器
+///
+/// `path` パラメータのファイルを読み込む。
+/// `path` がない場合は作業ディレクトリのエントリ一覧を出力する。
+pub struct FileReadExecutor;
+
+impl TaskExecutor for FileReadExecutor {
+    fn execute(&self, task: &Task, context: &TaskContext) -> anyhow::Result<TaskOutcome> {
+        let Some(path) = task.parameters.get("path") else {
+            let mut entries = fs::read_dir(&context.working_directory)?
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                .collect::<Vec<_>>();
+            // 留めた書き込みで新しくできたエントリも含める
+            if let Some(files) = &context.staged_files {
+                entries.extend(files.keys().filter_map(|path| path.split('/').next()).map(str::to_string));
+            }
+            entries.sort();
+            entries.dedup();
+            return Ok(TaskOutcome::output(entries.join("\n")));
+        };
+
+        let resolved = resolve_path(context, path)?;
+        let key = filesystem::normalize_path(path)?;
+        if let Some(content) = context.staged_files.as_ref().and_then(|files| files.get(&key)) {
+            return Ok(TaskOutcome {
+                output: content.clone(),
+                files: HashMap::from([(key, content.clone())]),
+                compensations: Vec::new(),
+                usage: ResourceUsage::default(),
+            });
+        }
+        let content = fs::read_to_string(&resolved)
+            .map_err(|e| anyhow::anyhow!("ファイルを読み込めません ({}): {}", resolved.display(), e))?;
+
+        Ok(TaskOutcome {
+            output: content.clone(),
+            files: HashMap::from([(key, content)]),
+            compensations: Vec::new(),
+            usage: ResourceUsage::default(),
+        })
+    }
+}
+
+/// ファイル書き込み実行器
+///
+/// `path` パラメータのファイルに `content` パラメータの内容を書き込む。
+/// 書き込む前の内容（なければ削除）に戻す補償操作を結果に含める。
+/// 書き込みを段階的に留める場合は、ディスクに触れず世界のファイルシステムにだけ反映する。
+pub struct FileWriteExecutor;
+
+impl TaskExecutor for FileWriteExecutor {
+    fn execute(&self, task: &Task, context: &TaskContext) -> anyhow::Result<TaskOutcome> {
+        let path = parameter(task, "path")?;
+        let content = parameter(task, "content")?;
+
+        let resolved = resolve_path(context, path)?;
+        let key = filesystem::normalize_path(path)?;
+        let usage = ResourceUsage {
+            file_writes: 1,
+            ..ResourceUsage::default()
+        };
+        if context.staged_files.is_some() {
+            return Ok(TaskOutcome {
+                output: format!("{} に {} バイト書き込みました（未適用）", path, content.len()),
+                files: HashMap::from([(key, content.to_string())]),
+                compensations: Vec::new(),
+                usage,
+            });
+        }
+
+        let created_dirs = match resolved.parent() {
+            Some(parent) => create_dirs(parent)?,
+            None => Vec::new(),
+        };
+        let previous = fs::read(&resolved).ok();
+        fs::write(&resolved, content)
+            .map_err(|e| anyhow::anyhow!("ファイルに書き込めません ({}): {}", resolved.display(), e))?;
+
+        Ok(TaskOutcome {
+            output: format!("{} に {} バイト書き込みました", path, content.len()),
+            files: HashMap::from([(key, content.to_string())]),
+            compensations: vec![Compensation::RestoreFile {
+                path: resolved,
+                previous,
+                created_dirs,
+            }],
+            usage,
+        })
+    }
+}
+
+/// ディレクトリを作成し、新しく作ったディレクトリを深い順に返す
+fn create_dirs(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let created = dir.ancestors().take_while(|d| !d.exists()).map(Path::to_path_buf).collect();
+    fs::create_dir_all(dir)?;
+    Ok(created)
+}
+
+/// 検証実行器
+///
+/// `command` パラメータのコマンドを作業ディレクトリで実行し、
+/// 終了コードが0なら成功とする。
+/// `command` パラメータがなければ何も実行せず、検証を省略したことを出力にして成功とする
+/// （組み込みのレシピの検証タスクはプロジェクトごとのコマンドを持たない）。
+/// 書き込みを段階的に留めている場合は、留めた内容を反映した作業ディレクトリの写しで実行する。
+/// 実行時間の予算があれば、それを超えたコマンドは停止して失敗とする。
+pub struct ValidationExecutor;
+
+impl TaskExecutor for ValidationExecutor {
+    fn execute(&self, task: &Task, context: &TaskContext) -> anyhow::Result<TaskOutcome> {
+        let Some(command) = task.parameters.get("command") else {
+            tracing::warn!("タスク {} に検証コマンドがないため検証を省略します", task.id);
+            return Ok(TaskOutcome::output(format!(
+                "検証コマンドがないため検証を省略しました: {}",
+                task.description
+            )));
+        };
+        let staged = match &context.staged_files {
+            Some(files) => filesystem::StagedTree::prepare(&context.working_directory, files)?,
+            None => None,
+        };
+        let directory = staged.as_ref().map_or(context.working_directory.as_path(), |tree| tree.path());
+
+        let mut shell = if cfg!(windows) {
+            let mut shell = Command::new("cmd");
+            shell.arg("/C");
+            shell
+        } else {
+            let mut shell = Command::new("sh");
+            shell.arg("-c");
+            shell
+        };
+        let child = shell
+            .arg(command)
+            .current_dir(directory)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| anyhow::anyhow!("コマンドを起動できません ({}): {}", command, e))?;
+
+        let output = match context.budget.wall_time() {
+            Some(limit) => wait_with_timeout(child, limit)
+                .map_err(|e| anyhow::anyhow!("コマンドを完了できません ({}): {}", command, e))?,
+            None => child.wait_with_output()?,
+        };
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        if !output.status.success() {
+            anyhow::bail!("コマンドが失敗しました ({}, {}): {}", command, output.status, stderr.trim());
+        }
+
+        Ok(TaskOutcome {
+            usage: ResourceUsage {
+                processes: 1,
+                ..ResourceUsage::default()
+            },
+            ..TaskOutcome::output(format!("{}{}", stdout, stderr).trim().to_string())
+        })
+    }
+}
+
+/// 子プロセスの終了を待つ（上限を超えたら停止してエラーにする）
+fn wait_with_timeout(mut child: Child, limit: Duration) -> anyhow::Result<Output> {
+    fn drain(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<Vec<u8>> {
+        thread::spawn(move || {
+            let mut buffer = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut buffer);
+            }
+            buffer
+        })
+    }
+
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+    let started = Instant::now();
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if started.elapsed() >= limit {
+            child.kill()?;
+            child.wait()?;
+            anyhow::bail!("実行時間の上限 {}ms を超えたため停止しました", limit.as_millis());
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+
+    Ok(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+/// 言語モデルによる実行器（分析・コード生成・意思決定）
+///
+/// タスクのプロンプトに依存タスクの出力を添えてモデルに渡す。
+pub struct ModelExecutor {
+    model: Arc<dyn LanguageModel>,
+}
+
+impl ModelExecutor {
+    /// 新しい実行器を作成
+    pub fn new(model: Arc<dyn LanguageModel>) -> Self {
+        Self { model }
+    }
+}
+
+impl TaskExecutor for ModelExecutor {
+    fn execute(&self, task: &Task, context: &TaskContext) -> anyhow::Result<TaskOutcome> {
+        let mut prompt = task.model_prompt();
+        let mut dependency_ids = context.dependency_outputs.keys().collect::<Vec<_>>();
+        dependency_ids.sort();
+        for id in dependency_ids {
+            prompt.push_str(&format!("\n\n## {} の結果\n{}", id, context.dependency_outputs[id]));
+        }
+
+        let request = ModelRequest {
+            task: ModelTask::ExecuteTask,
+            system: "あなたはタスク実行者です。指示されたタスクを実行し、結果を簡潔に報告してください。"
+                .to_string(),
+            prompt,
+            payload: serde_json::to_value(task)?,
+        };
+
+        let response = self.model.complete(&request)?;
+        Ok(TaskOutcome {
+            usage: ResourceUsage {
+                tokens: response.usage.total(),
+                ..ResourceUsage::default()
+            },
+            ..TaskOutcome::output(response.text)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_write_then_read() {
+        let dir = std::env::temp_dir().join(format!("seize-executor-{}", std::process::id()));
+        let context = TaskContext {
+            working_directory: dir.clone(),
+            dependency_outputs: HashMap::new(),
+            budget: ResourceBudget::default(),
+            staged_files: None,
+        };
+
+        let write = Task::new("task_0", TaskType::FileWrite)
+            .with_parameters(&[("path", "out/note.txt"), ("content", "瞬く景色")]);
+        let outcome = FileWriteExecutor.execute(&write, &context).unwrap();
+        assert_eq!(outcome.files["out/note.txt"], "瞬く景色");
+
+        let read = Task::new("task_0", TaskType::FileRead).with_parameters(&[("path", "out/note.txt")]);
+        let outcome = FileReadExecutor.execute(&read, &context).unwrap();
+        assert_eq!(outcome.output, "瞬く景色");
+
+        // 取り消すと書き込みのために作ったディレクトリも消える
+        fs::write(dir.join("keep.txt"), "keep").unwrap();
+        let nested = Task::new("task_0", TaskType::FileWrite)
+            .with_parameters(&[("path", "new/deep/note.txt"), ("content", "x")]);
+        let outcome = FileWriteExecutor.execute(&nested, &context).unwrap();
+        outcome.compensations[0].run().unwrap();
+        assert!(!dir.join("new").exists());
+        assert!(dir.join("keep.txt").exists());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_staged_write_does_not_touch_disk() {
+        let dir = std::env::temp_dir().join(format!("seize-executor-staged-{}", std::process::id()));
+        let mut context = TaskContext {
+            working_directory: dir.clone(),
+            dependency_outputs: HashMap::new(),
+            budget: ResourceBudget::default(),
+            staged_files: Some(Arc::new(HashMap::new())),
+        };
+
+        let write = Task::new("task_0", TaskType::FileWrite)
+            .with_parameters(&[("path", "./out//note.txt"), ("content", "瞬く景色")]);
+        let outcome = FileWriteExecutor.execute(&write, &context).unwrap();
+        assert_eq!(outcome.files["out/note.txt"], "瞬く景色");
+        assert!(outcome.compensations.is_empty());
+        assert_eq!(outcome.usage.file_writes, 1);
+        assert!(!dir.exists());
+
+        context.staged_files = Some(Arc::new(outcome.files));
+        for path in ["out/note.txt", "./out/note.txt", "out//note.txt"] {
+            let read = Task::new("task_0", TaskType::FileRead).with_parameters(&[("path", path)]);
+            let outcome = FileReadExecutor.execute(&read, &context).unwrap();
+            assert_eq!(outcome.output, "瞬く景色");
+            assert!(outcome.files.contains_key("out/note.txt"));
+        }
+    }
+
+    #[test]
+    fn test_validation_sees_staged_writes() {
+        let dir = std::env::temp_dir().join(format!("seize-executor-staged-validation-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("memo.txt"), "old").unwrap();
+        let mut context = TaskContext {
+            working_directory: dir.clone(),
+            dependency_outputs: HashMap::new(),
+            budget: ResourceBudget::default(),
+            staged_files: Some(Arc::new(HashMap::from([("memo.txt".to_string(), "old".to_string())]))),
+        };
+
+        let write = Task::new("task_0", TaskType::FileWrite)
+            .with_parameters(&[("path", "memo.txt"), ("content", "new")]);
+        let outcome = FileWriteExecutor.execute(&write, &context).unwrap();
+        context.staged_files = Some(Arc::new(outcome.files));
+
+        let check = Task::new("task_0", TaskType::Validation)
+            .with_parameters(&[("command", "grep -q new memo.txt && touch touched.txt")]);
+        assert!(ValidationExecutor.execute(&check, &context).is_ok());
+        let unchanged = fs::read_to_string(dir.join("memo.txt")).unwrap();
+        let touched = dir.join("touched.txt").exists();
+        fs::remove_dir_all(&dir).ok();
+
+        // 検証は写しで実行され、作業ディレクトリには触れない
+        assert_eq!(unchanged, "old");
+        assert!(!touched);
+    }
+
+    #[test]
+    fn test_paths_outside_working_directory_are_rejected() {
+        let dir = std::env::temp_dir().join(format!("seize-executor-escape-{}", std::process::id()));
+        let context = TaskContext {
+            working_directory: dir.join("work"),
+            dependency_outputs: HashMap::new(),
+            budget: ResourceBudget::default(),
+            staged_files: None,
+        };
+        let outside = dir.join("outside.txt");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&outside, "secret").unwrap();
+
+        let absolute = outside.to_string_lossy().into_owned();
+        for path in [absolute.as_str(), "../outside.txt", "notes/../../outside.txt"] {
+            let read = Task::new("task_0", TaskType::FileRead).with_parameters(&[("path", path)]);
+            let error = FileReadExecutor.execute(&read, &context).unwrap_err();
+            assert!(matches!(
+                error.downcast_ref::<crate::error::SeizeError>(),
+                Some(crate::error::SeizeError::UnsafePath(p)) if p == path
+            ));
+
+            let write = Task::new("task_0", TaskType::FileWrite)
+                .with_parameters(&[("path", path), ("content", "overwritten")]);
+            assert!(FileWriteExecutor.execute(&write, &context).is_err());
+        }
+        let content = fs::read_to_string(&outside).unwrap();
+        fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(content, "secret");
+    }
+
+    #[test]
+    fn test_validation_reports_command_failure() {
+        let context = TaskContext {
+            working_directory: std::env::temp_dir(),
+            dependency_outputs: HashMap::new(),
+            budget: ResourceBudget::default(),
+            staged_files: None,
+        };
+
+        let ok = Task::new("task_0", TaskType::Validation).with_parameters(&[("command", "echo ok")]);
+        assert_eq!(ValidationExecutor.execute(&ok, &context).unwrap().output, "ok");
+
+        let failing = Task::new("task_0", TaskType::Validation).with_parameters(&[("command", "exit 3")]);
+        assert!(ValidationExecutor.execute(&failing, &context).is_err());
+
+        // コマンドがなければ何も起動せずに省略する
+        let missing = Task::new("task_0", TaskType::Validation);
+        let skipped = ValidationExecutor.execute(&missing, &context).unwrap();
+        assert!(skipped.output.contains("省略"));
+        assert_eq!(skipped.usage.processes, 0);
+    }
+
+    #[test]
+    fn test_validation_is_stopped_at_wall_time_budget() {
+        let context = TaskContext {
+            working_directory: std::env::temp_dir(),
+            dependency_outputs: HashMap::new(),
+            budget: ResourceBudget::unlimited().with_wall_time(Duration::from_millis(200)),
+            staged_files: None,
+        };
+
+        let quick = Task::new("task_0", TaskType::Validation).with_parameters(&[("command", "echo ok")]);
+        let outcome = ValidationExecutor.execute(&quick, &context).unwrap();
+        assert_eq!(outcome.output, "ok");
+        assert_eq!(outcome.usage.processes, 1);
+
+        let started = Instant::now();
+        let slow = Task::new("task_0", TaskType::Validation).with_parameters(&[("command", "sleep 5")]);
+        let error = ValidationExecutor.execute(&slow, &context).unwrap_err();
+        assert!(error.to_string().contains("200ms"));
+        assert!(started.elapsed() < Duration::from_secs(3));
+    }
+}
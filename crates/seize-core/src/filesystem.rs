@@ -0,0 +1,542 @@
+//! # ファイルシステム同期 (Filesystem Sync)
+//!
+//! `World.filesystem` と実ディレクトリを同期する。
+//!
+//! - スナップショット: ディレクトリを読み込んで世界に取り込む（`.gitignore` を尊重）
+//! - 差分: 2つの世界のファイルシステムを比較する
+//! - 適用: 差分を実ディレクトリに書き戻す（ドライラン可）
+//! - 写し: 世界のファイルシステムを反映した作業ディレクトリの一時的な写しを作る（検証用）
+
+use crate::error::{SeizeError, SeizeResult};
+use crate::world::World;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// 作業ディレクトリからの相対パスとして検証する
+///
+/// 空のパス、絶対パス（Windows のドライブ指定を含む）、`..` を含むパスは
+/// 作業ディレクトリの外を指しうるため `UnsafePath` とする
+pub fn relative_path(path: &str) -> SeizeResult<&Path> {
+    let candidate = Path::new(path);
+    let escapes = candidate
+        .components()
+        .any(|c| matches!(c, Component::ParentDir | Component::RootDir | Component::Prefix(_)));
+    if path.trim().is_empty() || escapes {
+        return Err(SeizeError::UnsafePath(path.to_string()));
+    }
+    Ok(candidate)
+}
+
+/// 相対パスを検証し、世界のファイルシステムのキーの形（区切り文字は `/`）に揃える
+///
+/// `./a.txt` や `dir//a.txt` は `a.txt`・`dir/a.txt` になる
+pub fn normalize_path(path: &str) -> SeizeResult<String> {
+    let key = relative_path(path)?
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    if key.is_empty() {
+        return Err(SeizeError::UnsafePath(path.to_string()));
+    }
+    Ok(key)
+}
+
+/// ディレクトリのテキストファイルを読み込む
+///
+/// `.gitignore` に一致するファイル・隠しファイル・UTF-8でないファイルは除外する。
+/// キーは `root` からの相対パス（区切り文字は `/`）。
+pub fn snapshot_directory(root: impl AsRef<Path>) -> anyhow::Result<HashMap<String, String>> {
+    let root = root.as_ref();
+    let mut files = HashMap::new();
+
+    let walker = ignore::WalkBuilder::new(root)
+        .require_git(false)
+        .build();
+
+    for entry in walker {
+        let entry = entry?;
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+
+        let path = entry.path();
+        let Ok(content) = fs::read_to_string(path) else {
+            tracing::debug!("テキストでないファイルをスキップ: {}", path.display());
+            continue;
+        };
+
+        files.insert(relative_key(root, path)?, content);
+    }
+
+    Ok(files)
+}
+
+fn relative_key(root: &Path, path: &Path) -> anyhow::Result<String> {
+    let relative = path.strip_prefix(root)?;
+    Ok(relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/"))
+}
+
+/// ファイルの変更
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum FileChange {
+    /// 追加
+    Added { path: String, content: String },
+    /// 変更
+    Modified {
+        path: String,
+        before: String,
+        after: String,
+    },
+    /// 削除
+    Removed { path: String, content: String },
+}
+
+impl FileChange {
+    /// 対象のパス
+    pub fn path(&self) -> &str {
+        match self {
+            FileChange::Added { path, .. }
+            | FileChange::Modified { path, .. }
+            | FileChange::Removed { path, .. } => path,
+        }
+    }
+
+    /// 変更前の内容（存在しなかった場合は `None`）
+    fn before(&self) -> Option<&str> {
+        match self {
+            FileChange::Added { .. } => None,
+            FileChange::Modified { before, .. } => Some(before),
+            FileChange::Removed { content, .. } => Some(content),
+        }
+    }
+
+    /// 変更後の内容（削除される場合は `None`）
+    fn after(&self) -> Option<&str> {
+        match self {
+            FileChange::Added { content, .. } => Some(content),
+            FileChange::Modified { after, .. } => Some(after),
+            FileChange::Removed { .. } => None,
+        }
+    }
+}
+
+/// 2つの世界の間のファイル差分
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorldDiff {
+    /// パス順に並んだ変更
+    pub changes: Vec<FileChange>,
+}
+
+/// 差分適用の結果
+#[derive(Debug, Clone, Default)]
+pub struct ApplyReport {
+    /// 適用した（ドライランでは適用予定の）パス
+    pub applied: Vec<String>,
+
+    /// 既に適用済みだったためスキップしたパス
+    pub skipped: Vec<String>,
+
+    /// ドライランかどうか
+    pub dry_run: bool,
+}
+
+impl WorldDiff {
+    /// 2つのファイルシステム状態の差分を計算
+    pub fn between(before: &HashMap<String, String>, after: &HashMap<String, String>) -> Self {
+        let paths: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+
+        let changes = paths
+            .into_iter()
+            .filter_map(|path| match (before.get(path), after.get(path)) {
+                (None, Some(content)) => Some(FileChange::Added {
+                    path: path.clone(),
+                    content: content.clone(),
+                }),
+                (Some(old), Some(new)) if old != new => Some(FileChange::Modified {
+                    path: path.clone(),
+                    before: old.clone(),
+                    after: new.clone(),
+                }),
+                (Some(content), None) => Some(FileChange::Removed {
+                    path: path.clone(),
+                    content: content.clone(),
+                }),
+                _ => None,
+            })
+            .collect();
+
+        Self { changes }
+    }
+
+    /// 差分がないか
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// 差分をディレクトリに適用
+    ///
+    /// ## 手順
+    /// 1. 競合検出: ディレクトリの外を指すパスがあるか、
+    ///    ディスク上の内容が変更前とも変更後とも異なれば中止する
+    /// 2. ステージング: 新しい内容を一時ファイルに書き出す（失敗時は何も変更しない）
+    /// 3. コミット: 一時ファイルをリネームし、削除対象を消す
+    ///    （途中で失敗した場合は適用済みの変更を元に戻す）
+    ///
+    /// `dry_run` が真の場合は手順1のみ行い、適用予定の変更を返す。
+    pub fn apply(&self, root: impl AsRef<Path>, dry_run: bool) -> anyhow::Result<ApplyReport> {
+        let root = root.as_ref();
+        let mut report = ApplyReport {
+            dry_run,
+            ..ApplyReport::default()
+        };
+
+        // 1. 競合検出
+        let mut pending = Vec::new();
+        let mut conflicts = Vec::new();
+        for change in &self.changes {
+            let target = root.join(relative_path(change.path())?);
+            let current = read_optional(&target)?;
+
+            if current.as_deref() == change.after() {
+                report.skipped.push(change.path().to_string());
+            } else if current.as_deref() == change.before() {
+                pending.push((change, target));
+            } else {
+                conflicts.push(change.path().to_string());
+            }
+        }
+
+        if !conflicts.is_empty() {
+            anyhow::bail!("ディスク上のファイルが変更されています（競合）: {}", conflicts.join(", "));
+        }
+
+        if dry_run {
+            report.applied = pending.iter().map(|(c, _)| c.path().to_string()).collect();
+            return Ok(report);
+        }
+
+        // 2. ステージング
+        let mut staged: Vec<(&FileChange, PathBuf, Option<PathBuf>)> = Vec::new();
+        for (change, target) in pending {
+            let temp = match change.after() {
+                Some(content) => match stage(&target, content) {
+                    Ok(temp) => Some(temp),
+                    Err(e) => {
+                        discard(&staged);
+                        return Err(e);
+                    }
+                },
+                None => None,
+            };
+            staged.push((change, target, temp));
+        }
+
+        // 3. コミット
+        for (index, (change, target, temp)) in staged.iter().enumerate() {
+            let result = match temp {
+                Some(temp) => fs::rename(temp, target),
+                None => fs::remove_file(target),
+            };
+
+            if let Err(e) = result {
+                rollback(&staged[..index]);
+                discard(&staged[index..]);
+                anyhow::bail!("{} の適用に失敗したため変更を元に戻しました: {}", change.path(), e);
+            }
+            report.applied.push(change.path().to_string());
+        }
+
+        Ok(report)
+    }
+}
+
+fn read_optional(path: &Path) -> anyhow::Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(anyhow::anyhow!("ファイルを読み込めません ({}): {}", path.display(), e)),
+    }
+}
+
+/// 対象と同じディレクトリに一時ファイルを書き出す
+fn stage(target: &Path, content: &str) -> anyhow::Result<PathBuf> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let file_name = target
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("不正なパスです: {}", target.display()))?;
+    let temp = target.with_file_name(format!(".{}.seize-tmp", file_name.to_string_lossy()));
+
+    fs::write(&temp, content)
+        .map_err(|e| anyhow::anyhow!("一時ファイルを書き込めません ({}): {}", temp.display(), e))?;
+    Ok(temp)
+}
+
+/// 未コミットの一時ファイルを削除
+fn discard(staged: &[(&FileChange, PathBuf, Option<PathBuf>)]) {
+    for (_, _, temp) in staged {
+        if let Some(temp) = temp {
+            fs::remove_file(temp).ok();
+        }
+    }
+}
+
+/// コミット済みの変更を元に戻す
+fn rollback(committed: &[(&FileChange, PathBuf, Option<PathBuf>)]) {
+    for (change, target, _) in committed {
+        let restored = match change.before() {
+            Some(content) => fs::write(target, content),
+            None => fs::remove_file(target),
+        };
+        if let Err(e) = restored {
+            tracing::error!("{} を元に戻せませんでした: {}", target.display(), e);
+        }
+    }
+}
+
+/// 世界のファイルシステムを反映した作業ディレクトリの一時的な写し（破棄すると削除する）
+///
+/// 書き込みをディスクに反映する前に、その内容に対して検証のコマンドを実行するために使う。
+/// `.gitignore` に一致するファイル（ビルド成果物など）と `.git` は写さない
+#[derive(Debug)]
+pub struct StagedTree {
+    dir: PathBuf,
+}
+
+impl StagedTree {
+    /// `root` に `files` と内容が異なるファイルがあれば写しを作る（すべて同じなら `None`）
+    pub fn prepare(root: &Path, files: &HashMap<String, String>) -> anyhow::Result<Option<Self>> {
+        let mut changed = Vec::new();
+        for (path, content) in files {
+            let target = root.join(relative_path(path)?);
+            if read_optional(&target).ok().flatten().as_deref() != Some(content.as_str()) {
+                changed.push((target, path, content));
+            }
+        }
+        if changed.is_empty() {
+            return Ok(None);
+        }
+
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "seize-staged-{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::SeqCst)
+        ));
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(&dir)?;
+        let tree = Self { dir };
+
+        if root.is_dir() {
+            let walker = ignore::WalkBuilder::new(root)
+                .require_git(false)
+                .hidden(false)
+                .filter_entry(|entry| entry.file_name() != ".git")
+                .build();
+            for entry in walker {
+                let entry = entry?;
+                if !entry.path().is_file() {
+                    continue;
+                }
+                let target = tree.dir.join(entry.path().strip_prefix(root)?);
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::copy(entry.path(), &target)?;
+            }
+        }
+
+        for (_, path, content) in changed {
+            let target = tree.dir.join(relative_path(path)?);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&target, content)?;
+        }
+        Ok(Some(tree))
+    }
+
+    /// 写しのディレクトリ
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+}
+
+impl Drop for StagedTree {
+    fn drop(&mut self) {
+        fs::remove_dir_all(&self.dir).ok();
+    }
+}
+
+impl World {
+    /// ディレクトリのスナップショットを取り込む
+    ///
+    /// 作業ディレクトリを `root` に設定し、ファイルシステムを置き換える
+    pub fn load_directory(&mut self, root: impl AsRef<Path>) -> anyhow::Result<()> {
+        let root = root.as_ref();
+        self.filesystem = snapshot_directory(root)?;
+        self.context.working_directory = root.to_string_lossy().into_owned();
+        Ok(())
+    }
+
+    /// この世界から `other` へのファイル差分を計算
+    pub fn diff(&self, other: &World) -> WorldDiff {
+        WorldDiff::between(&self.filesystem, &other.filesystem)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("seize-fs-{}-{}", name, std::process::id()));
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path("./a.txt").unwrap(), "a.txt");
+        assert_eq!(normalize_path("dir//a.txt").unwrap(), "dir/a.txt");
+        assert_eq!(normalize_path("dir/./a.txt").unwrap(), "dir/a.txt");
+        assert!(normalize_path(".").is_err());
+        assert!(normalize_path("../a.txt").is_err());
+    }
+
+    #[test]
+    fn test_snapshot_respects_gitignore() {
+        let dir = temp_dir("snapshot");
+        fs::write(dir.join(".gitignore"), "target/\n*.log\n").unwrap();
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::create_dir_all(dir.join("target")).unwrap();
+        fs::write(dir.join("src/main.rs"), "fn main() {}").unwrap();
+        fs::write(dir.join("target/out.txt"), "build").unwrap();
+        fs::write(dir.join("debug.log"), "log").unwrap();
+
+        let mut world = World::new();
+        world.load_directory(&dir).unwrap();
+        fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(world.filesystem.len(), 1);
+        assert_eq!(world.filesystem["src/main.rs"], "fn main() {}");
+    }
+
+    #[test]
+    fn test_diff_and_apply() {
+        let dir = temp_dir("apply");
+        fs::write(dir.join("keep.txt"), "keep").unwrap();
+        fs::write(dir.join("edit.txt"), "old").unwrap();
+        fs::write(dir.join("drop.txt"), "drop").unwrap();
+
+        let mut before = World::new();
+        before.load_directory(&dir).unwrap();
+        let mut after = before.clone();
+        after.filesystem.insert("edit.txt".to_string(), "new".to_string());
+        after.filesystem.insert("docs/new.md".to_string(), "# new".to_string());
+        after.filesystem.remove("drop.txt");
+
+        let diff = before.diff(&after);
+        assert_eq!(diff.changes.len(), 3);
+
+        let report = diff.apply(&dir, true).unwrap();
+        assert_eq!(report.applied.len(), 3);
+        assert_eq!(fs::read_to_string(dir.join("edit.txt")).unwrap(), "old");
+
+        let report = diff.apply(&dir, false).unwrap();
+        assert_eq!(report.applied.len(), 3);
+        assert_eq!(snapshot_directory(&dir).unwrap(), after.filesystem);
+
+        // 再適用は何もしない
+        let report = diff.apply(&dir, false).unwrap();
+        assert_eq!(report.skipped.len(), 3);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_staged_tree_reflects_staged_files() {
+        let dir = temp_dir("staged-tree");
+        fs::write(dir.join(".gitignore"), "target/\n").unwrap();
+        fs::write(dir.join("keep.txt"), "keep").unwrap();
+        fs::create_dir_all(dir.join("target")).unwrap();
+        fs::write(dir.join("target/out.bin"), [0u8, 159, 146, 150]).unwrap();
+
+        let mut files = snapshot_directory(&dir).unwrap();
+        assert!(StagedTree::prepare(&dir, &files).unwrap().is_none());
+
+        files.insert("docs/new.md".to_string(), "# new".to_string());
+        let tree = StagedTree::prepare(&dir, &files).unwrap().unwrap();
+        let copy = tree.path().to_path_buf();
+        assert_eq!(fs::read_to_string(copy.join("keep.txt")).unwrap(), "keep");
+        assert_eq!(fs::read_to_string(copy.join("docs/new.md")).unwrap(), "# new");
+        assert!(copy.join(".gitignore").exists());
+        assert!(!copy.join("target").exists());
+        assert!(!dir.join("docs").exists());
+
+        drop(tree);
+        assert!(!copy.exists());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_apply_detects_conflicts() {
+        let dir = temp_dir("conflict");
+        fs::write(dir.join("edit.txt"), "changed on disk").unwrap();
+
+        let diff = WorldDiff {
+            changes: vec![FileChange::Modified {
+                path: "edit.txt".to_string(),
+                before: "old".to_string(),
+                after: "new".to_string(),
+            }],
+        };
+
+        assert!(diff.apply(&dir, false).is_err());
+        assert_eq!(fs::read_to_string(dir.join("edit.txt")).unwrap(), "changed on disk");
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_apply_rejects_paths_outside_root() {
+        let dir = temp_dir("escape");
+        let root = dir.join("work");
+        fs::create_dir_all(&root).unwrap();
+
+        let diff = WorldDiff {
+            changes: vec![
+                FileChange::Added {
+                    path: "inside.txt".to_string(),
+                    content: "inside".to_string(),
+                },
+                FileChange::Added {
+                    path: "../outside.txt".to_string(),
+                    content: "outside".to_string(),
+                },
+            ],
+        };
+
+        let error = diff.apply(&root, false).unwrap_err();
+        assert!(matches!(error.downcast_ref::<SeizeError>(), Some(SeizeError::UnsafePath(_))));
+        assert!(!root.join("inside.txt").exists());
+        assert!(!dir.join("outside.txt").exists());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
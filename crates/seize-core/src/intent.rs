@@ -2,7 +2,14 @@
 //!
 //! ユーザーの曖昧な入力から明確な目標へ変換する
 
+use crate::charter::{CharterEngine, CharterSubject, CharterViolation, RuleScope};
+use crate::error::{SeizeError, SeizeResult};
+use crate::command::TaskStatus;
+use crate::lexicon::{Language, Lexicon};
+use crate::model::{self, LanguageModel, ModelRequest, ModelTask};
+use crate::world::World;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 /// 解決された目標
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,10 +28,102 @@ pub struct Goal {
 
     /// 制約条件
     pub constraints: Vec<String>,
+
+    /// 憲章に基づく警告・情報（重大度 Error の違反があれば解決自体が失敗する）
+    #[serde(default)]
+    pub violations: Vec<CharterViolation>,
+
+    /// 反復ごとの見直しの経緯（古い順、最初の反復では空）
+    #[serde(default)]
+    pub lineage: Vec<GoalRefinement>,
+
+    /// 入力の言語（プロンプト・既定のタスクの文言に使う）
+    #[serde(default)]
+    pub language: Language,
+
+    /// 目標ID（複数の目標に分けたときの参照用）
+    #[serde(default)]
+    pub id: String,
+
+    /// 先に達成すべき目標のID
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+}
+
+#[cfg(test)]
+impl Goal {
+    /// テスト用の目標を作成（日本語・優先度は中・ID は `goal_0`・制約や依存はない）
+    pub(crate) fn for_test(description: &str, category: GoalCategory) -> Self {
+        Self {
+            description: description.to_string(),
+            essential_question: "何を実現したいのか?".to_string(),
+            category,
+            priority: Priority::Medium,
+            constraints: Vec::new(),
+            violations: Vec::new(),
+            lineage: Vec::new(),
+            language: Language::Ja,
+            id: "goal_0".to_string(),
+            depends_on: Vec::new(),
+        }
+    }
+}
+
+impl Goal {
+    /// 複数の目標を1つの目標にまとめる（統合した実行計画の目標として使う）
+    ///
+    /// 記述・本質的な問いは並べてつなぎ、優先度は最も高いもの、
+    /// 制約・憲章の警告は重複を除いた和、カテゴリ・言語・見直しの経緯は最初の目標に揃える。
+    /// 目標が1つもなければ `InvalidPlan` を返す
+    pub fn combine(goals: &[Goal]) -> SeizeResult<Goal> {
+        let mut combined = goals
+            .first()
+            .cloned()
+            .ok_or_else(|| SeizeError::InvalidPlan("目標がありません".to_string()))?;
+        if goals.len() == 1 {
+            return Ok(combined);
+        }
+
+        combined.description = goals.iter().map(|g| g.description.as_str()).collect::<Vec<_>>().join(" / ");
+        combined.essential_question = goals
+            .iter()
+            .map(|g| g.essential_question.as_str())
+            .collect::<Vec<_>>()
+            .join(" / ");
+        combined.priority = goals.iter().map(|g| g.priority.clone()).max().unwrap_or(Priority::Medium);
+        for goal in &goals[1..] {
+            for constraint in &goal.constraints {
+                if !combined.constraints.contains(constraint) {
+                    combined.constraints.push(constraint.clone());
+                }
+            }
+            for violation in &goal.violations {
+                if !combined.violations.iter().any(|v| v.rule_id == violation.rule_id) {
+                    combined.violations.push(violation.clone());
+                }
+            }
+        }
+        combined.id = "goals".to_string();
+        combined.depends_on = Vec::new();
+        Ok(combined)
+    }
+}
+
+/// 目標の見直し
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GoalRefinement {
+    /// 見直した時点の世界のバージョン
+    pub world_version: usize,
+
+    /// 見直しの理由
+    pub reason: String,
+
+    /// この見直しで加えた制約
+    pub constraints: Vec<String>,
 }
 
 /// 目標のカテゴリ
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum GoalCategory {
     /// 情報収集・理解
     Understanding,
@@ -47,133 +146,249 @@ pub enum Priority {
     Critical,
 }
 
+/// モデルが推定する目標の属性
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntentAnalysis {
+    /// 本質的な問い (Step-Back Question)
+    pub essential_question: String,
+
+    /// 目標のカテゴリ
+    pub category: GoalCategory,
+
+    /// 優先度
+    pub priority: Priority,
+
+    /// 制約条件
+    #[serde(default)]
+    pub constraints: Vec<String>,
+}
+
 /// 意図解決器
 pub struct IntentResolver {
     /// 憲章の原則に基づく検証フラグ
     enforce_charter: bool,
+
+    /// 憲章に基づく検証器
+    charter: Arc<CharterEngine>,
+
+    /// 推論に使う言語モデル
+    model: Arc<dyn LanguageModel>,
 }
 
 impl IntentResolver {
     /// 新しい意図解決器を作成
     pub fn new(enforce_charter: bool) -> Self {
-        Self { enforce_charter }
+        Self {
+            enforce_charter,
+            charter: Arc::new(CharterEngine::default()),
+            model: model::default_model(),
+        }
+    }
+
+    /// 検証に使う憲章を設定
+    pub fn with_charter(mut self, charter: Arc<CharterEngine>) -> Self {
+        self.charter = charter;
+        self
+    }
+
+    /// 言語モデルを設定
+    pub fn with_model(mut self, model: Arc<dyn LanguageModel>) -> Self {
+        self.model = model;
+        self
     }
 
     /// 入力から目標を解決
     ///
     /// ## Step-Back Question
     /// より本質的な問いを生成し、表面的な要求から本当の目的を引き出す
-    pub fn resolve(&self, input: &str) -> anyhow::Result<Goal> {
+    pub fn resolve(&self, input: &str) -> SeizeResult<Goal> {
+        self.resolve_in(input, &World::new(), None)
+    }
+
+    /// 現在の世界と前回の目標を踏まえて目標を解決
+    ///
+    /// 前回の目標があれば、直前の反復で失敗したタスクを制約として取り込み、
+    /// 優先度を上げて適応的実行（再試行）に切り替える。見直しの経緯は `Goal::lineage` に残る
+    pub fn resolve_in(&self, input: &str, world: &World, previous: Option<&Goal>) -> SeizeResult<Goal> {
         // Step 1: 入力の解析
         let description = input.trim().to_string();
+        let language = Language::detect(&description);
 
-        // Step 2: Step-Back Questionの生成
-        let essential_question = self.generate_step_back_question(&description);
+        // Step 2-5: Step-Back Question・カテゴリ・優先度・制約の推定
+        let mut analysis = self.analyze(&description, language, &world_context(world, language));
 
-        // Step 3: カテゴリの推定
-        let category = self.infer_category(&description);
-
-        // Step 4: 優先度の決定
-        let priority = self.determine_priority(&description);
-
-        // Step 5: 制約の抽出
-        let constraints = self.extract_constraints(&description);
-
-        // Step 6: 憲章に基づく検証
-        if self.enforce_charter {
-            self.validate_against_charter(&description)?;
+        // 前回の目標の見直し
+        let mut lineage = Vec::new();
+        if let Some(previous) = previous {
+            let refinement = refine(world, language);
+            for constraint in &refinement.constraints {
+                if !analysis.constraints.contains(constraint) {
+                    analysis.constraints.push(constraint.clone());
+                }
+            }
+            if !refinement.constraints.is_empty() {
+                analysis.priority = analysis.priority.max(Priority::High);
+            }
+            lineage = previous.lineage.clone();
+            lineage.push(refinement);
         }
 
+        // Step 6: 憲章に基づく検証（重大度 Error の違反があれば失敗する）
+        let violations = if self.enforce_charter {
+            let subject = CharterSubject::new(RuleScope::Intent, &description).with_goal_category(&analysis.category);
+            self.charter.enforce(&subject)?
+        } else {
+            Vec::new()
+        };
+
         Ok(Goal {
             description,
-            essential_question,
-            category,
-            priority,
-            constraints,
+            essential_question: analysis.essential_question,
+            category: analysis.category,
+            priority: analysis.priority,
+            constraints: analysis.constraints,
+            violations,
+            lineage,
+            language,
+            id: "goal_0".to_string(),
+            depends_on: Vec::new(),
         })
     }
 
-    /// Step-Back Questionを生成
+    /// 複数の依頼を含む入力を目標ごとに解決
     ///
-    /// 例:
-    /// - "ファイルを読み込んで" → "どのような情報を得たいのか?"
-    /// - "コードを修正して" → "何を実現したいのか?"
-    fn generate_step_back_question(&self, input: &str) -> String {
-        // 簡易実装: より高度なNLP処理が可能
-        if input.contains("読み込") || input.contains("取得") {
-            "どのような情報を得て、何を実現したいのか?".to_string()
-        } else if input.contains("修正") || input.contains("変更") {
-            "この変更によって何を達成したいのか?".to_string()
-        } else if input.contains("作成") || input.contains("生成") {
-            "なぜこれが必要で、どのような価値を生むのか?".to_string()
-        } else {
-            "この要求の本質的な目的は何か?".to_string()
-        }
+    /// 入力を依頼ごとの節に分け、節ごとに目標を解決する（優先度も節ごとに推定する）。
+    /// 順序を表す語（「その後」など）で続けられた目標は直前の目標に、
+    /// それ以外の目標は同じ対象を扱う最も近い前の目標に依存し、どちらもなければ独立して進める
+    pub fn resolve_all(&self, input: &str) -> SeizeResult<Vec<Goal>> {
+        self.resolve_all_in(input, &World::new(), None)
     }
 
-    /// カテゴリを推定
-    fn infer_category(&self, input: &str) -> GoalCategory {
-        if input.contains("理解") || input.contains("調査") || input.contains("確認") {
-            GoalCategory::Understanding
-        } else if input.contains("コード") || input.contains("実装") || input.contains("修正") {
-            GoalCategory::CodeGeneration
-        } else if input.contains("設計") || input.contains("アーキテクチャ") {
-            GoalCategory::SystemDesign
-        } else if input.contains("組織") || input.contains("運営") {
-            GoalCategory::OrganizationalManagement
-        } else if input.contains("判断") || input.contains("決定") {
-            GoalCategory::DecisionSupport
-        } else {
-            GoalCategory::Understanding
+    /// 現在の世界と前回の目標を踏まえて、複数の依頼を含む入力を目標ごとに解決
+    pub fn resolve_all_in(&self, input: &str, world: &World, previous: Option<&Goal>) -> SeizeResult<Vec<Goal>> {
+        let lexicon = Lexicon::detect(input);
+        let clauses = lexicon.split_intent_clauses(input);
+        if clauses.len() <= 1 {
+            return Ok(vec![self.resolve_in(input, world, previous)?]);
         }
-    }
 
-    /// 優先度を決定
-    fn determine_priority(&self, input: &str) -> Priority {
-        if input.contains("緊急") || input.contains("至急") {
-            Priority::Critical
-        } else if input.contains("重要") || input.contains("優先") {
-            Priority::High
-        } else if input.contains("後で") || input.contains("余裕") {
-            Priority::Low
-        } else {
-            Priority::Medium
+        let targets: Vec<_> = clauses.iter().map(|clause| lexicon.targets(&clause.text)).collect();
+        let mut goals: Vec<Goal> = Vec::new();
+        for (index, clause) in clauses.iter().enumerate() {
+            let mut goal = self.resolve_in(&clause.text, world, previous)?;
+            goal.id = format!("goal_{}", index);
+            let dependency = if clause.follows_previous {
+                index.checked_sub(1)
+            } else {
+                (0..index).rev().find(|&before| !targets[before].is_disjoint(&targets[index]))
+            };
+            if let Some(before) = dependency {
+                goal.depends_on.push(goals[before].id.clone());
+            }
+            goals.push(goal);
         }
-    }
 
-    /// 制約を抽出
-    fn extract_constraints(&self, input: &str) -> Vec<String> {
-        let mut constraints = Vec::new();
+        Ok(goals)
+    }
 
-        // 簡易実装: パターンマッチング
-        if input.contains("安全") {
-            constraints.push("安全性を確保すること".to_string());
-        }
-        if input.contains("説明") {
-            constraints.push("説明可能性を維持すること".to_string());
-        }
-        if input.contains("記録") {
-            constraints.push("プロセスを記録すること".to_string());
-        }
+    /// モデルに目標の属性を推定させる
+    ///
+    /// モデルが失敗した場合はヒューリスティックにフォールバックする
+    fn analyze(&self, description: &str, language: Language, context: &str) -> IntentAnalysis {
+        let request = ModelRequest {
+            task: ModelTask::ResolveIntent,
+            system: format!(
+                "あなたは意図解決器です。ユーザーの入力から本質的な問い (Step-Back Question)、\
+                カテゴリ、優先度、制約を推定し、次の形式のJSONのみを返してください: \
+                {{\"essential_question\": string, \
+                \"category\": \"Understanding\"|\"CodeGeneration\"|\"SystemDesign\"|\"OrganizationalManagement\"|\"DecisionSupport\", \
+                \"priority\": \"Low\"|\"Medium\"|\"High\"|\"Critical\", \
+                \"constraints\": [string]}}\n\
+                本質的な問いと制約は入力と同じ言語 ({}) で書いてください。",
+                language.code()
+            ),
+            prompt: if context.is_empty() {
+                description.to_string()
+            } else {
+                format!("{}\n\n{}", description, context)
+            },
+            payload: serde_json::Value::String(description.to_string()),
+        };
 
-        constraints
+        self.model
+            .complete(&request)
+            .and_then(|response| model::parse_json_response(&response.text))
+            .unwrap_or_else(|e| {
+                tracing::warn!("モデル {} による意図解決に失敗したためヒューリスティックを使用します: {}", self.model.name(), e);
+                model::heuristic::analyze_intent(description)
+            })
     }
+}
 
-    /// 憲章に基づく検証
-    fn validate_against_charter(&self, input: &str) -> anyhow::Result<()> {
-        // 禁止パターンのチェック
-        if input.contains("自動判断") && !input.contains("人間") {
-            anyhow::bail!("憲章違反: 最終判断は人間が行う必要があります");
-        }
+/// 直前の反復の結果から目標の見直しを作る
+fn refine(world: &World, language: Language) -> GoalRefinement {
+    let messages = &Lexicon::builtin(language).refinement;
+    let failures: Vec<_> = recent_records(world)
+        .filter(|r| r.status == TaskStatus::Failed)
+        .collect();
 
-        if input.contains("データ収集") && !input.contains("最小限") {
-            tracing::warn!("警告: 必要最小限のデータ収集を推奨します");
-        }
+    let constraints = failures
+        .iter()
+        .map(|r| {
+            messages
+                .failed_task
+                .replace("{task}", &r.description)
+                .replace("{error}", r.error.as_deref().unwrap_or("-"))
+        })
+        .collect();
+    let reason = if failures.is_empty() {
+        messages.no_failures.clone()
+    } else {
+        messages.failures.replace("{count}", &failures.len().to_string())
+    };
 
-        Ok(())
+    GoalRefinement {
+        world_version: world.version,
+        reason,
+        constraints,
     }
 }
 
+/// 直前の反復（現在のバージョンの1つ前）のタスクの実行記録
+fn recent_records(world: &World) -> impl Iterator<Item = &crate::world::TaskRecord> {
+    world
+        .context
+        .task_records
+        .iter()
+        .filter(move |r| r.world_version + 1 == world.version)
+}
+
+/// モデルに渡す世界の状況（直近の学習・履歴・失敗、見出しとラベルは入力の言語の語彙から）
+///
+/// 状況がなければ空文字列
+fn world_context(world: &World, language: Language) -> String {
+    let labels = &Lexicon::builtin(language).context;
+    let mut lines = Vec::new();
+    for learning in world.knowledge.learnings.iter().rev().take(3) {
+        lines.push(format!("- {}: {}", labels.learning, learning.content));
+    }
+    for entry in world.context.history.iter().rev().take(5) {
+        lines.push(format!("- {}: {}", labels.history, entry));
+    }
+    for record in recent_records(world).filter(|r| r.status == TaskStatus::Failed) {
+        lines.push(format!(
+            "- {}: {} ({})",
+            labels.failure,
+            record.description,
+            record.error.as_deref().unwrap_or("-")
+        ));
+    }
+    if lines.is_empty() {
+        return String::new();
+    }
+    format!("{}:\n{}", labels.heading, lines.join("\n"))
+}
+
 impl Default for IntentResolver {
     fn default() -> Self {
         Self::new(true)
@@ -183,6 +398,7 @@ impl Default for IntentResolver {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::ReplayModel;
 
     #[test]
     fn test_intent_resolution() {
@@ -192,4 +408,116 @@ mod tests {
         assert_eq!(goal.category, GoalCategory::Understanding);
         assert!(!goal.essential_question.is_empty());
     }
+
+    #[test]
+    fn test_intent_resolution_with_replay_model() {
+        let model = ReplayModel::new().with_response(
+            ModelTask::ResolveIntent,
+            None,
+            r#"{"essential_question": "何を守りたいのか?", "category": "SystemDesign", "priority": "High", "constraints": ["既存APIを壊さない"]}"#,
+        );
+        let resolver = IntentResolver::default().with_model(Arc::new(model));
+        let goal = resolver.resolve("認証基盤を見直したい").unwrap();
+
+        assert_eq!(goal.category, GoalCategory::SystemDesign);
+        assert_eq!(goal.priority, Priority::High);
+        assert_eq!(goal.constraints, vec!["既存APIを壊さない".to_string()]);
+    }
+
+    #[test]
+    fn test_intent_resolution_falls_back_on_model_failure() {
+        let resolver = IntentResolver::default().with_model(Arc::new(ReplayModel::new()));
+        let goal = resolver.resolve("コードを修正してください").unwrap();
+
+        assert_eq!(goal.category, GoalCategory::CodeGeneration);
+    }
+
+    #[test]
+    fn test_refinement_from_previous_failures() {
+        use crate::command::TaskType;
+        use crate::world::TaskRecord;
+
+        let resolver = IntentResolver::default();
+        let first = resolver.resolve("現状を調査してください").unwrap();
+        assert!(first.lineage.is_empty());
+
+        let mut world = World::new();
+        world.context.task_records.push(TaskRecord {
+            world_version: 0,
+            task_id: "task_0".to_string(),
+            description: "設定ファイルを読む".to_string(),
+            task_type: TaskType::FileRead,
+            status: TaskStatus::Failed,
+            attempts: 1,
+            duration_ms: 0,
+            summary: String::new(),
+            error: Some("ファイルがありません".to_string()),
+            budget: Default::default(),
+            usage: Default::default(),
+        });
+        world.advance();
+
+        let second = resolver.resolve_in("現状を調査してください", &world, Some(&first)).unwrap();
+        assert_eq!(second.lineage.len(), 1);
+        assert_eq!(second.lineage[0].world_version, 1);
+        assert_eq!(second.priority, Priority::High);
+        assert!(second.constraints.iter().any(|c| c.contains("設定ファイルを読む")));
+
+        // 失敗がなければ目標は変えずに経緯だけを残す
+        world.advance();
+        let third = resolver.resolve_in("現状を調査してください", &world, Some(&second)).unwrap();
+        assert_eq!(third.lineage.len(), 2);
+        assert!(third.lineage[1].constraints.is_empty());
+        assert_eq!(third.priority, Priority::Medium);
+    }
+
+    #[test]
+    fn test_english_intent_resolution() {
+        let goal = IntentResolver::default()
+            .resolve("Urgently fix the payment bug")
+            .unwrap();
+
+        assert_eq!(goal.language, Language::En);
+        assert_eq!(goal.category, GoalCategory::CodeGeneration);
+        assert_eq!(goal.priority, Priority::Critical);
+        assert_eq!(goal.essential_question, "What do you want to achieve with this change?");
+    }
+
+    #[test]
+    fn test_world_context_follows_language() {
+        let mut world = World::new();
+        assert!(world_context(&world, Language::En).is_empty());
+
+        world.context.history.push("Iteration 1: 3 task(s)".to_string());
+        let english = world_context(&world, Language::En);
+        assert_eq!(english, "World context:\n- History: Iteration 1: 3 task(s)");
+        assert!(world_context(&world, Language::Ja).starts_with("世界の状況:\n- 履歴: "));
+    }
+
+    #[test]
+    fn test_compound_input_keeps_clause_priorities_and_dependencies() {
+        let resolver = IntentResolver::default();
+
+        // 優先度は節ごと、順序を表す語があれば直前の目標に、同じ対象を扱えばその目標に依存する
+        let goals = resolver
+            .resolve_all("Urgently investigate the outage, then fix the login bug; update the login docs")
+            .unwrap();
+        let priorities: Vec<Priority> = goals.iter().map(|g| g.priority.clone()).collect();
+        assert_eq!(priorities, vec![Priority::Critical, Priority::Medium, Priority::Medium]);
+        assert!(goals[0].depends_on.is_empty());
+        assert_eq!(goals[1].depends_on, vec!["goal_0".to_string()]);
+        assert_eq!(goals[2].depends_on, vec!["goal_1".to_string()]);
+
+        // 順序の語も共通の対象もない依頼は独立して進める
+        let goals = resolver
+            .resolve_all("ログイン画面を調査して、決済処理を修正して、ログイン画面の文言を変更して")
+            .unwrap();
+        assert_eq!(goals.len(), 3);
+        assert!(goals[1].depends_on.is_empty());
+        assert_eq!(goals[2].depends_on, vec!["goal_0".to_string()]);
+
+        let combined = Goal::combine(&goals).unwrap();
+        assert_eq!(combined.id, "goals");
+        assert!(matches!(Goal::combine(&[]), Err(SeizeError::InvalidPlan(_))));
+    }
 }
@@ -0,0 +1,280 @@
+//! # 学習の信頼度 (Learning Confidence)
+//!
+//! θ₆ Learn はタスクの結果を観測として学習を更新する。
+//! 学習ごとに確認・否定の重みを持ち、信頼度は一様な事前分布に対するベータ分布の事後平均とする。
+//! 似た学習は1つにまとめ、重みは半減期で減衰させ、
+//! 根拠がほとんど残っていない学習と上限を超えた古い学習は忘れる。
+
+use crate::clock::Timestamp;
+use crate::world::Learning;
+use std::collections::HashSet;
+
+/// 学習の観測（記述が確認されたか、否定されたか）
+#[derive(Debug, Clone, PartialEq)]
+pub struct Observation {
+    /// 学習の記述
+    pub statement: String,
+
+    /// 観測の出所（同じ出所の学習とだけまとめる）
+    pub source: String,
+
+    /// 記述が確認されたか（偽なら否定された）
+    pub confirmed: bool,
+}
+
+impl Observation {
+    /// 記述を確認する観測
+    pub fn confirms(statement: impl Into<String>, source: impl Into<String>) -> Self {
+        Self {
+            statement: statement.into(),
+            source: source.into(),
+            confirmed: true,
+        }
+    }
+
+    /// 記述を否定する観測
+    pub fn contradicts(statement: impl Into<String>, source: impl Into<String>) -> Self {
+        Self {
+            confirmed: false,
+            ..Self::confirms(statement, source)
+        }
+    }
+}
+
+/// 学習の更新結果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LearningUpdate {
+    /// 新しく加えた学習の数
+    pub added: usize,
+    /// 既存の学習を確認した観測の数
+    pub confirmed: usize,
+    /// 既存の学習を否定した観測の数
+    pub contradicted: usize,
+    /// 忘れた学習の数
+    pub forgotten: usize,
+}
+
+/// 学習の信頼度モデル
+#[derive(Debug, Clone)]
+pub struct LearningModel {
+    /// 確認・否定の重みが半分になるまでの時間
+    half_life: chrono::Duration,
+
+    /// 同じ学習とみなす記述の類似度（文字バイグラムの Jaccard 係数）
+    similarity: f64,
+
+    /// 保持する学習の上限
+    capacity: usize,
+
+    /// これより重みの合計が小さくなった学習は忘れる
+    min_weight: f64,
+}
+
+impl LearningModel {
+    /// 既定の信頼度モデル（半減期30日・類似度0.8・上限200件）
+    pub fn new() -> Self {
+        Self {
+            half_life: chrono::Duration::days(30),
+            similarity: 0.8,
+            capacity: 200,
+            min_weight: 0.1,
+        }
+    }
+
+    /// 重みの半減期を設定
+    pub fn with_half_life(mut self, half_life: chrono::Duration) -> Self {
+        self.half_life = half_life;
+        self
+    }
+
+    /// 同じ学習とみなす類似度を設定（1.0 なら正規化した記述が一致するものだけ）
+    pub fn with_similarity(mut self, similarity: f64) -> Self {
+        self.similarity = similarity;
+        self
+    }
+
+    /// 保持する学習の上限を設定
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// 確認・否定の重みから信頼度を計算（Beta(1, 1) を事前分布とした事後平均）
+    pub fn confidence(support: f64, refute: f64) -> f64 {
+        (support + 1.0) / (support + refute + 2.0)
+    }
+
+    /// 観測で学習を更新する
+    ///
+    /// 1. 観測ごとに同じ出所の似た学習を探し、あれば重みを現在まで減衰させてから加算し、
+    ///    記述と時刻を新しくして末尾（最近の学習）に移す。なければ新しい学習として加える
+    /// 2. 重みを持つ学習の信頼度を現在の重みで計算し直す
+    /// 3. 重みの合計が下限を下回った学習と、上限を超えた分の古い学習を忘れる
+    ///
+    /// 重みを持たない学習（ロールバックの記録など）は信頼度を変えず、上限を超えたときだけ忘れる
+    pub fn update(&self, learnings: &mut Vec<Learning>, observations: &[Observation], now: Timestamp) -> LearningUpdate {
+        let mut update = LearningUpdate::default();
+
+        for observation in observations {
+            let (support, refute) = if observation.confirmed { (1.0, 0.0) } else { (0.0, 1.0) };
+            let key = normalize(&observation.statement);
+
+            let mut learning = match self.find_similar(learnings, &key, &observation.source) {
+                Some(index) => {
+                    if observation.confirmed {
+                        update.confirmed += 1;
+                    } else {
+                        update.contradicted += 1;
+                    }
+                    let mut learning = learnings.remove(index);
+                    let factor = self.decay_factor(learning.timestamp, now);
+                    learning.support = learning.support * factor + support;
+                    learning.refute = learning.refute * factor + refute;
+                    learning
+                }
+                None => {
+                    update.added += 1;
+                    Learning {
+                        timestamp: now,
+                        content: String::new(),
+                        source: observation.source.clone(),
+                        confidence: 0.0,
+                        support,
+                        refute,
+                    }
+                }
+            };
+            learning.timestamp = now;
+            learning.content = observation.statement.clone();
+            learnings.push(learning);
+        }
+
+        let before = learnings.len();
+        learnings.retain_mut(|learning| {
+            if !has_evidence(learning) {
+                return true;
+            }
+            let factor = self.decay_factor(learning.timestamp, now);
+            let (support, refute) = (learning.support * factor, learning.refute * factor);
+            learning.confidence = Self::confidence(support, refute);
+            support + refute >= self.min_weight
+        });
+        if learnings.len() > self.capacity {
+            learnings.drain(..learnings.len() - self.capacity);
+        }
+        update.forgotten = before - learnings.len();
+
+        update
+    }
+
+    /// 同じ出所で最も似た学習のインデックス
+    fn find_similar(&self, learnings: &[Learning], key: &str, source: &str) -> Option<usize> {
+        learnings
+            .iter()
+            .enumerate()
+            .filter(|(_, learning)| learning.source == source && has_evidence(learning))
+            .map(|(index, learning)| (index, similarity(key, &normalize(&learning.content))))
+            .filter(|&(_, score)| score >= self.similarity)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(index, _)| index)
+    }
+
+    /// 時刻 `since` から `now` までの重みの減衰率
+    fn decay_factor(&self, since: Timestamp, now: Timestamp) -> f64 {
+        let elapsed = (now - since).num_milliseconds().max(0) as f64;
+        let half_life = self.half_life.num_milliseconds().max(1) as f64;
+        0.5f64.powf(elapsed / half_life)
+    }
+}
+
+impl Default for LearningModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 確認・否定の重みを持つか（持たない学習は信頼度モデルの対象外）
+fn has_evidence(learning: &Learning) -> bool {
+    learning.support + learning.refute > 0.0
+}
+
+/// 比較用に記述を正規化する（小文字化し、空白・記号を除き、数字の並びを `#` にまとめる）
+fn normalize(text: &str) -> String {
+    let mut normalized = String::new();
+    for c in text.chars().flat_map(char::to_lowercase) {
+        if c.is_ascii_digit() {
+            if !normalized.ends_with('#') {
+                normalized.push('#');
+            }
+        } else if c.is_alphanumeric() {
+            normalized.push(c);
+        }
+    }
+    normalized
+}
+
+/// 正規化した記述の類似度（文字バイグラムの Jaccard 係数）
+fn similarity(a: &str, b: &str) -> f64 {
+    if a == b {
+        return 1.0;
+    }
+
+    let bigrams = |text: &str| -> HashSet<(char, char)> {
+        let chars: Vec<char> = text.chars().collect();
+        chars.windows(2).map(|w| (w[0], w[1])).collect()
+    };
+    let (a, b) = (bigrams(a), bigrams(b));
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f64 / union as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confidence_is_updated_merged_and_decayed() {
+        let model = LearningModel::new()
+            .with_half_life(chrono::Duration::days(1))
+            .with_capacity(3);
+        let now = chrono::Utc::now();
+        let mut learnings = Vec::new();
+
+        let update = model.update(
+            &mut learnings,
+            &[
+                Observation::confirms("Validation task succeeds: run tests 1", "TaskOutcome"),
+                Observation::confirms("Validation task succeeds: run tests 2", "TaskOutcome"),
+                Observation::contradicts("Validation task succeeds: run tests 3", "TaskOutcome"),
+            ],
+            now,
+        );
+        assert_eq!(update, LearningUpdate { added: 1, confirmed: 1, contradicted: 1, forgotten: 0 });
+        assert_eq!(learnings.len(), 1);
+        assert_eq!(learnings[0].content, "Validation task succeeds: run tests 3");
+        assert!((learnings[0].confidence - 0.6).abs() < 1e-9);
+
+        // 1日で重みが半分になり、信頼度は 0.5 に近づく
+        let later = now + chrono::Duration::days(1);
+        model.update(&mut learnings, &[Observation::confirms("Goal: 調査 -> Plan succeeds", "Goal")], later);
+        assert!((learnings[0].confidence - 2.0 / 3.5).abs() < 1e-9);
+        assert_eq!(learnings[1].source, "Goal");
+
+        // 根拠がほとんど残っていない学習と、上限を超えた古い学習は忘れる
+        let much_later = later + chrono::Duration::days(5);
+        let update = model.update(
+            &mut learnings,
+            &[
+                Observation::confirms("Analysis task succeeds: compare options", "TaskOutcome"),
+                Observation::confirms("FileRead task succeeds: gather information", "TaskOutcome"),
+            ],
+            much_later,
+        );
+        assert_eq!(update.forgotten, 2);
+        assert_eq!(learnings.len(), 2);
+        assert!(learnings.iter().all(|l| l.timestamp == much_later));
+    }
+}
@@ -0,0 +1,432 @@
+//! # 語彙 (Lexicon)
+//!
+//! 意図解決のキーワードとタスクの文言を言語ごとにまとめたもの。
+//! 語彙は `lexicon/<言語>.toml` に置かれ、ビルド時に組み込まれる。
+//! 入力の言語は `Language::detect` で推定する。
+
+use crate::command::TaskType;
+use crate::intent::{GoalCategory, Priority};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::OnceLock;
+
+/// 言語
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    /// 日本語
+    #[default]
+    Ja,
+    /// 英語
+    En,
+}
+
+impl Language {
+    /// 入力の言語を推定する
+    ///
+    /// かな・漢字・全角文字が1つでもあれば日本語、なければラテン文字があれば英語とみなす
+    pub fn detect(text: &str) -> Self {
+        let japanese = text
+            .chars()
+            .any(|c| matches!(c, '\u{3040}'..='\u{30ff}' | '\u{4e00}'..='\u{9fff}' | '\u{ff00}'..='\u{ffef}'));
+        if !japanese && text.chars().any(|c| c.is_ascii_alphabetic()) {
+            Language::En
+        } else {
+            Language::Ja
+        }
+    }
+
+    /// 言語コード（"ja" / "en"）
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::Ja => "ja",
+            Language::En => "en",
+        }
+    }
+
+    /// 言語コードから言語を得る（"en_US.UTF-8" のような表記も受け付ける）
+    pub fn from_code(code: &str) -> Option<Self> {
+        let code = code.trim().to_ascii_lowercase();
+        if code.starts_with("ja") {
+            Some(Language::Ja)
+        } else if code.starts_with("en") {
+            Some(Language::En)
+        } else {
+            None
+        }
+    }
+}
+
+/// Step-Back Question の規則
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepBackRule {
+    pub keywords: Vec<String>,
+    pub question: String,
+}
+
+/// カテゴリの規則
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryRule {
+    pub keywords: Vec<String>,
+    pub category: GoalCategory,
+}
+
+/// 優先度の規則
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriorityRule {
+    pub keywords: Vec<String>,
+    pub priority: Priority,
+}
+
+/// 制約の規則
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConstraintRule {
+    pub keywords: Vec<String>,
+    pub constraint: String,
+}
+
+/// タスクタイプごとのプロンプト（`{description}` をタスクの説明に置き換える）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskPrompts {
+    pub file_read: String,
+    pub file_write: String,
+    pub code_generation: String,
+    pub validation: String,
+    pub analysis: String,
+    pub decision: String,
+}
+
+/// 目標の見直しの文言
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefinementMessages {
+    /// 失敗したタスクから加える制約（`{task}` / `{error}`）
+    pub failed_task: String,
+    /// 失敗があったときの理由（`{count}`）
+    pub failures: String,
+    /// 失敗がなかったときの理由
+    pub no_failures: String,
+}
+
+/// θ₄ が記録する意思決定の文言
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecisionMessages {
+    /// 計画の実行の根拠
+    pub plan_rationale: String,
+    /// 計画の実行を取り消す条件
+    pub plan_revocation: String,
+    /// 破壊的なタスクの実行前承認の根拠
+    pub task_rationale: String,
+}
+
+/// モデルに渡す世界の状況の見出しとラベル
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextLabels {
+    /// 世界の状況の見出し
+    pub heading: String,
+    /// 直近の学習
+    pub learning: String,
+    /// 直近の履歴
+    pub history: String,
+    /// 直前の反復で失敗したタスク
+    pub failure: String,
+}
+
+/// 依頼ごとに分けた入力の節
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntentClause {
+    /// 節の文言
+    pub text: String,
+
+    /// 順序を表す語で直前の節に続けられているか
+    pub follows_previous: bool,
+}
+
+/// 語彙
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lexicon {
+    pub language: Language,
+    pub default_step_back: String,
+    pub default_category: GoalCategory,
+    pub default_priority: Priority,
+    /// 複数の依頼を区切る文字列
+    #[serde(default)]
+    pub separators: Vec<String>,
+    /// 独立した依頼とみなす動作のキーワード
+    #[serde(default)]
+    pub actions: Vec<String>,
+    /// 依頼の順序を表す語（節の境目にあれば直前の依頼の後に進める）
+    #[serde(default)]
+    pub sequence_markers: Vec<String>,
+    /// 依頼の対象とみなさない語
+    #[serde(default)]
+    pub stopwords: Vec<String>,
+    pub step_back: Vec<StepBackRule>,
+    pub categories: Vec<CategoryRule>,
+    pub priorities: Vec<PriorityRule>,
+    pub constraints: Vec<ConstraintRule>,
+    pub prompts: TaskPrompts,
+    pub refinement: RefinementMessages,
+    pub context: ContextLabels,
+    pub decisions: DecisionMessages,
+}
+
+const JA: &str = include_str!("../lexicon/ja.toml");
+const EN: &str = include_str!("../lexicon/en.toml");
+
+impl Lexicon {
+    /// 組み込みの語彙
+    pub fn builtin(language: Language) -> &'static Lexicon {
+        static JA_LEXICON: OnceLock<Lexicon> = OnceLock::new();
+        static EN_LEXICON: OnceLock<Lexicon> = OnceLock::new();
+
+        let (cell, source) = match language {
+            Language::Ja => (&JA_LEXICON, JA),
+            Language::En => (&EN_LEXICON, EN),
+        };
+        cell.get_or_init(|| toml::from_str(source).expect("組み込みの語彙の形式が不正です"))
+    }
+
+    /// 入力の言語を推定して組み込みの語彙を得る
+    pub fn detect(text: &str) -> &'static Lexicon {
+        Self::builtin(Language::detect(text))
+    }
+
+    /// Step-Back Question を生成
+    pub fn step_back_question(&self, input: &str) -> String {
+        let input = input.to_lowercase();
+        self.step_back
+            .iter()
+            .find(|rule| contains_any(&input, &rule.keywords))
+            .map_or(&self.default_step_back, |rule| &rule.question)
+            .clone()
+    }
+
+    /// カテゴリを推定
+    pub fn category(&self, input: &str) -> GoalCategory {
+        let input = input.to_lowercase();
+        self.categories
+            .iter()
+            .find(|rule| contains_any(&input, &rule.keywords))
+            .map_or(&self.default_category, |rule| &rule.category)
+            .clone()
+    }
+
+    /// 複数の依頼を含む入力を依頼ごとの節に分ける
+    ///
+    /// 区切り文字列で分けたうえで、動作のキーワードを含まない節は
+    /// 直前の節につなげる（「ファイルを読み込んで、内容を確認して」の前半のような修飾句）
+    pub fn split_intents(&self, input: &str) -> Vec<String> {
+        self.split_intent_clauses(input).into_iter().map(|clause| clause.text).collect()
+    }
+
+    /// 入力を依頼ごとの節に分け、節が直前の節の後に進めるものかを添える
+    ///
+    /// 節の境目（区切り・直前の節の末尾・節の先頭）に順序を表す語があれば直前の節に続くとみなす
+    pub fn split_intent_clauses(&self, input: &str) -> Vec<IntentClause> {
+        // 区切り文字列で分けた節の範囲（バイト位置）
+        let mut pieces = vec![(0, input.len())];
+        for separator in self.separators.iter().filter(|s| !s.is_empty()) {
+            pieces = pieces
+                .into_iter()
+                .flat_map(|(start, end)| {
+                    let mut ranges = Vec::new();
+                    let mut from = start;
+                    for (offset, _) in input[start..end].match_indices(separator.as_str()) {
+                        ranges.push((from, start + offset));
+                        from = start + offset + separator.len();
+                    }
+                    ranges.push((from, end));
+                    ranges
+                })
+                .collect();
+        }
+
+        // 動作を含まない節は元の区切りごと直前の節につなげる
+        let is_action = |text: &str| contains_any(&text.to_lowercase(), &self.actions);
+        let mut clauses: Vec<(usize, usize)> = Vec::new();
+        for (start, end) in pieces {
+            if input[start..end].trim().is_empty() {
+                continue;
+            }
+            match clauses.last_mut() {
+                Some(last) if !is_action(&input[start..end]) || !is_action(&input[last.0..last.1]) => {
+                    last.1 = end;
+                }
+                _ => clauses.push((start, end)),
+            }
+        }
+
+        let trim = |text: &str| text.trim_matches(|c: char| c.is_whitespace() || (!c.is_alphanumeric() && c != '_')).to_lowercase();
+        let has_marker = |text: &str, matches: &dyn Fn(&str, &str) -> bool| {
+            self.sequence_markers
+                .iter()
+                .any(|marker| matches(text, &marker.to_lowercase()))
+        };
+        let mut previous: Option<(usize, usize)> = None;
+        let mut result = Vec::new();
+        for (start, end) in clauses {
+            let text = input[start..end].trim().to_string();
+            let follows_previous = previous.is_some_and(|(previous_start, previous_end)| {
+                let gap = input[previous_end..start].to_lowercase();
+                has_marker(&gap, &|text, marker| text.contains(marker))
+                    || has_marker(&trim(&input[previous_start..previous_end]), &|text, marker| text.ends_with(marker))
+                    || has_marker(&trim(&text), &|text, marker| text.starts_with(marker))
+            });
+            result.push(IntentClause { text, follows_previous });
+            previous = Some((start, end));
+        }
+        result
+    }
+
+    /// 依頼の対象とみなす語（節どうしが同じ対象を扱うかの判定に使う）
+    ///
+    /// ASCII の語（3文字以上）と、ひらがな・記号で区切った2文字以上の語のうち、
+    /// 動作・優先度のキーワードと対象とみなさない語を除いたもの
+    pub fn targets(&self, input: &str) -> BTreeSet<String> {
+        let input = input.to_lowercase();
+        let is_hiragana = |c: char| ('\u{3041}'..='\u{309f}').contains(&c);
+        let mut words: Vec<String> = Vec::new();
+        let mut current = String::new();
+        let mut current_ascii = true;
+        for c in input.chars() {
+            let part_of_word = if c.is_ascii() {
+                c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '/' | '-')
+            } else {
+                c.is_alphanumeric() && !is_hiragana(c)
+            };
+            if part_of_word && (current.is_empty() || current_ascii == c.is_ascii()) {
+                current_ascii = c.is_ascii();
+                current.push(c);
+                continue;
+            }
+            words.push(std::mem::take(&mut current));
+            if part_of_word {
+                current_ascii = c.is_ascii();
+                current.push(c);
+            }
+        }
+        words.push(current);
+
+        let priority_keywords: Vec<String> = self.priorities.iter().flat_map(|rule| rule.keywords.clone()).collect();
+        words
+            .into_iter()
+            .map(|word| word.trim_matches(|c: char| matches!(c, '.' | '-' | '/')).to_string())
+            .filter(|word| {
+                let min = if word.is_ascii() { 3 } else { 2 };
+                word.chars().count() >= min
+            })
+            .filter(|word| {
+                !contains_any(word, &self.actions)
+                    && !contains_any(word, &priority_keywords)
+                    && !self.stopwords.iter().any(|stopword| stopword.to_lowercase() == *word)
+            })
+            .collect()
+    }
+
+    /// 優先度を推定
+    pub fn priority(&self, input: &str) -> Priority {
+        let input = input.to_lowercase();
+        self.priorities
+            .iter()
+            .find(|rule| contains_any(&input, &rule.keywords))
+            .map_or(&self.default_priority, |rule| &rule.priority)
+            .clone()
+    }
+
+    /// 制約を抽出
+    pub fn constraints(&self, input: &str) -> Vec<String> {
+        let input = input.to_lowercase();
+        self.constraints
+            .iter()
+            .filter(|rule| contains_any(&input, &rule.keywords))
+            .map(|rule| rule.constraint.clone())
+            .collect()
+    }
+
+    /// タスクのプロンプトを生成
+    pub fn task_prompt(&self, task_type: &TaskType, description: &str) -> String {
+        let template = match task_type {
+            TaskType::FileRead => &self.prompts.file_read,
+            TaskType::FileWrite => &self.prompts.file_write,
+            TaskType::CodeGeneration => &self.prompts.code_generation,
+            TaskType::Validation => &self.prompts.validation,
+            TaskType::Analysis => &self.prompts.analysis,
+            TaskType::Decision => &self.prompts.decision,
+        };
+        template.replace("{description}", description)
+    }
+}
+
+/// キーワードのいずれかを含むか
+///
+/// 英字で始まるキーワードは単語の先頭でのみ一致させる（"code" は "decode" に一致しないが、
+/// "urgent" は "urgently" に一致する）
+fn contains_any(input: &str, keywords: &[String]) -> bool {
+    keywords.iter().any(|keyword| {
+        let keyword = keyword.to_lowercase();
+        let word = keyword.starts_with(|c: char| c.is_ascii_alphanumeric());
+        input.match_indices(&keyword).any(|(start, _)| {
+            !word || !input[..start].ends_with(|c: char| c.is_ascii_alphanumeric())
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(Language::detect("コードを修正してください"), Language::Ja);
+        assert_eq!(Language::detect("README を更新して"), Language::Ja);
+        assert_eq!(Language::detect("Fix the login bug"), Language::En);
+        assert_eq!(Language::detect(""), Language::Ja);
+        assert_eq!(Language::from_code("en_US.UTF-8"), Some(Language::En));
+    }
+
+    #[test]
+    fn test_builtin_lexicons() {
+        for language in [Language::Ja, Language::En] {
+            assert_eq!(Lexicon::builtin(language).language, language);
+        }
+
+        let en = Lexicon::builtin(Language::En);
+        assert_eq!(en.category("Urgently fix the login bug"), GoalCategory::CodeGeneration);
+        assert_eq!(en.priority("This is URGENT"), Priority::Critical);
+        assert_eq!(en.category("decode the payload"), GoalCategory::Understanding);
+        assert_eq!(
+            en.split_intents("Investigate the outage, then fix the bug; record the process"),
+            vec!["Investigate the outage", "fix the bug", "record the process"]
+        );
+
+        let ja = Lexicon::builtin(Language::Ja);
+        assert_eq!(
+            ja.split_intents("調査して、修正して、記録して"),
+            vec!["調査して", "修正して", "記録して"]
+        );
+        assert_eq!(
+            ja.split_intents("ファイルを読み込んで、内容を確認したい"),
+            vec!["ファイルを読み込んで、内容を確認したい"]
+        );
+        let clauses = en.split_intent_clauses("Investigate the outage, then fix the bug; record the process");
+        assert_eq!(
+            clauses.iter().map(|c| c.follows_previous).collect::<Vec<_>>(),
+            vec![false, true, false]
+        );
+        assert!(ja.split_intent_clauses("障害を調査して、その後修正して")[1].follows_previous);
+        assert_eq!(
+            en.targets("Fix the login bug in src/auth.rs"),
+            ["bug", "login", "src/auth.rs"].map(String::from).into()
+        );
+        assert_eq!(
+            ja.targets("緊急でログイン画面の文言を修正して"),
+            ["ログイン画面", "文言"].map(String::from).into()
+        );
+        assert_eq!(en.priority("low priority cleanup"), Priority::Low);
+        assert_eq!(en.constraints("keep it safe and explainable").len(), 2);
+        assert_eq!(
+            en.task_prompt(&TaskType::Analysis, "the logs"),
+            "Analyze the following:\nthe logs"
+        );
+    }
+}
@@ -15,6 +15,24 @@ pub mod command;
 pub mod transformation;
 pub mod agent;
 pub mod requirements;
+pub mod model;
+pub mod executor;
+pub mod filesystem;
+pub mod scheduler;
+pub mod store;
+pub mod convergence;
+pub mod approval;
+pub mod clock;
+pub mod charter;
+pub mod error;
+pub mod observer;
+pub mod lexicon;
+pub mod recipe;
+pub mod phase;
+pub mod transaction;
+pub mod budget;
+pub mod learning;
+pub mod retrieval;
 
 pub use world::World;
 pub use intent::{IntentResolver, Goal};
@@ -22,6 +40,92 @@ pub use command::{CommandStack, ExecutionPlan};
 pub use transformation::{WorldTransformer, TransformationPhase};
 pub use agent::UnifiedAgent;
 pub use requirements::{RequirementsEngineer, Requirement, RequirementsSpecification};
+pub use model::LanguageModel;
+pub use executor::TaskExecutor;
+pub use filesystem::WorldDiff;
+pub use store::WorldStore;
+pub use convergence::ConvergenceCriterion;
+pub use approval::Approver;
+pub use clock::Clock;
+pub use charter::{Charter, CharterEngine};
+pub use error::{SeizeError, SeizeResult};
+pub use observer::AgentObserver;
+pub use lexicon::{Language, Lexicon};
+pub use recipe::RecipeBook;
+pub use phase::{Phase, PhasePipeline};
+pub use transaction::{Compensation, Transaction};
+pub use budget::{CostReport, ResourceBudget};
+pub use learning::LearningModel;
+
+/// 反復を止めた理由
+///
+/// 表示する文言は利用側で選べるよう、種類と対象だけを持つ。
+/// `Display` は日本語の文言を返す
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub enum HaltReason {
+    /// Θ のフェーズが失敗したため世界を適用前に戻した
+    RolledBack {
+        phase: TransformationPhase,
+        /// 戻した世界のバージョン
+        world_version: usize,
+        error: String,
+    },
+    /// 承認されなかった（却下・判断待ちの）意思決定がある
+    NotApproved(Vec<Refusal>),
+}
+
+/// 承認されなかった意思決定
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Refusal {
+    /// 意思決定の目的
+    pub purpose: String,
+    /// 却下の理由（判断待ちなら `None`。自動却下ポリシーなら `approval::AUTO_DENY_REASON`）
+    pub reason: Option<String>,
+}
+
+impl std::fmt::Display for HaltReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::RolledBack { phase, world_version, error } => write!(
+                f,
+                "{} が失敗したため World v{} に戻しました: {}",
+                phase.label(),
+                world_version,
+                error
+            ),
+            Self::NotApproved(refusals) => {
+                let refusals: Vec<String> = refusals.iter().map(Refusal::to_string).collect();
+                write!(f, "{}", refusals.join(", "))
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Refusal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.reason {
+            Some(reason) => write!(f, "{} ({})", self.purpose, approval::describe_reason(reason)),
+            None => write!(f, "{} (承認者の判断待ち)", self.purpose),
+        }
+    }
+}
+
+/// 反復ごとの実行トレース
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct IterationTrace {
+    /// 反復回数（1始まり）
+    pub iteration: usize,
+    /// ℐ で解決された目標
+    pub goal: Goal,
+    /// 𝒞 で作成された実行計画
+    pub plan: ExecutionPlan,
+    /// θ₁〜θ₆ の結果
+    pub phases: Vec<transformation::TransformationResult>,
+    /// 収束度（却下により止めた反復では `None`）
+    pub score: Option<f64>,
+    /// この反復にかかった時間（ミリ秒）
+    pub wall_time_ms: u64,
+}
 
 /// エージェントのコア実行結果
 #[derive(Debug, Clone)]
@@ -30,4 +134,14 @@ pub struct AgentResult {
     pub final_world: World,
     pub iterations: usize,
     pub converged: bool,
+    /// 収束を判定した基準（未収束なら `None`）
+    pub converged_by: Option<String>,
+    /// 反復ごとの収束度
+    pub scores: Vec<f64>,
+    /// 却下・判断待ち・フェーズの失敗により反復を止めた理由（止めていなければ `None`）
+    pub halted: Option<HaltReason>,
+    /// 反復ごとの実行トレース
+    pub trace: Vec<IterationTrace>,
+    /// タスクが使った資源と言語モデルのトークン数
+    pub cost: CostReport,
 }
@@ -0,0 +1,183 @@
+//! # 言語モデル (Language Model)
+//!
+//! ℐ（意図解決）・𝒞（タスク分解）・Θ（タスク実行）の各段階が呼び出す
+//! 推論バックエンドの抽象化。
+//!
+//! ## 実装
+//! - `HeuristicModel` - キーワードに基づく決定的なフォールバック実装
+//! - `OpenAiCompatibleModel` - OpenAI互換の Chat Completions API を呼び出す実装
+//! - `ReplayModel` - 記録済みの応答を返すオフライン用実装（テスト向け）
+
+pub(crate) mod heuristic;
+mod openai;
+mod replay;
+
+pub use heuristic::HeuristicModel;
+pub use openai::OpenAiCompatibleModel;
+pub use replay::{ReplayEntry, ReplayModel};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// モデルに依頼する処理の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ModelTask {
+    /// ℐ: 入力から目標を解決
+    ResolveIntent,
+    /// 𝒞: 目標をタスクに分解
+    DecomposeGoal,
+    /// θ₄: タスクを実行
+    ExecuteTask,
+}
+
+/// モデルへのリクエスト
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelRequest {
+    /// 処理の種類
+    pub task: ModelTask,
+
+    /// システムプロンプト（応答形式の指示）
+    pub system: String,
+
+    /// ユーザープロンプト
+    pub prompt: String,
+
+    /// 構造化された入力（ヒューリスティック実装が参照する）
+    pub payload: serde_json::Value,
+}
+
+/// モデルからの応答
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelResponse {
+    /// 生成されたテキスト
+    pub text: String,
+
+    /// トークン使用量
+    pub usage: TokenUsage,
+}
+
+/// トークン使用量
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+impl TokenUsage {
+    /// 合計トークン数
+    pub fn total(&self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+}
+
+/// 言語モデルの抽象
+///
+/// 実装は同期的に応答を返す。スレッド間で共有できるよう `Send + Sync` を要求する。
+pub trait LanguageModel: Send + Sync {
+    /// モデル名（ログ・記録用）
+    fn name(&self) -> &str;
+
+    /// リクエストに対する応答を生成
+    fn complete(&self, request: &ModelRequest) -> anyhow::Result<ModelResponse>;
+
+    /// テキストごとの埋め込みベクトル
+    ///
+    /// 埋め込みに対応していないモデルは `None` を返す（知識の検索は語彙の一致だけで行う）
+    fn embed(&self, _texts: &[String]) -> anyhow::Result<Option<Vec<Vec<f32>>>> {
+        Ok(None)
+    }
+}
+
+/// トークン使用量の計測器
+///
+/// 複数のモデル呼び出しから共有され、累計の使用量を保持する
+#[derive(Debug, Default)]
+pub struct TokenMeter {
+    prompt_tokens: AtomicU64,
+    completion_tokens: AtomicU64,
+}
+
+impl TokenMeter {
+    /// 使用量を加算
+    pub fn record(&self, usage: &TokenUsage) {
+        self.prompt_tokens.fetch_add(usage.prompt_tokens, Ordering::Relaxed);
+        self.completion_tokens.fetch_add(usage.completion_tokens, Ordering::Relaxed);
+    }
+
+    /// 累計の使用量
+    pub fn usage(&self) -> TokenUsage {
+        TokenUsage {
+            prompt_tokens: self.prompt_tokens.load(Ordering::Relaxed),
+            completion_tokens: self.completion_tokens.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// トークン使用量を計測するモデルのラッパー
+pub struct MeteredModel {
+    inner: Arc<dyn LanguageModel>,
+    meter: Arc<TokenMeter>,
+}
+
+impl MeteredModel {
+    /// モデルを計測器付きで包む
+    pub fn new(inner: Arc<dyn LanguageModel>, meter: Arc<TokenMeter>) -> Self {
+        Self { inner, meter }
+    }
+}
+
+impl LanguageModel for MeteredModel {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn complete(&self, request: &ModelRequest) -> anyhow::Result<ModelResponse> {
+        let response = self.inner.complete(request)?;
+        self.meter.record(&response.usage);
+        Ok(response)
+    }
+
+    fn embed(&self, texts: &[String]) -> anyhow::Result<Option<Vec<Vec<f32>>>> {
+        self.inner.embed(texts)
+    }
+}
+
+/// 既定のモデル（ヒューリスティック実装）
+pub fn default_model() -> Arc<dyn LanguageModel> {
+    Arc::new(HeuristicModel)
+}
+
+/// 応答テキストからJSONを取り出してデシリアライズ
+///
+/// コードフェンスや前後の説明文を含む応答にも対応する。
+pub(crate) fn parse_json_response<T: DeserializeOwned>(text: &str) -> anyhow::Result<T> {
+    let start = text.find('{');
+    let end = text.rfind('}');
+    let json = match (start, end) {
+        (Some(start), Some(end)) if start < end => &text[start..=end],
+        _ => anyhow::bail!("モデルの応答にJSONが含まれていません: {}", text),
+    };
+
+    serde_json::from_str(json).map_err(|e| anyhow::anyhow!("モデルの応答を解釈できません: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_json_response_with_fence() {
+        #[derive(Deserialize)]
+        struct Answer {
+            value: u32,
+        }
+
+        let text = "結果は以下の通りです。\n```json\n{\"value\": 42}\n```";
+        let answer: Answer = parse_json_response(text).unwrap();
+        assert_eq!(answer.value, 42);
+
+        assert!(parse_json_response::<Answer>("JSONなし").is_err());
+    }
+}
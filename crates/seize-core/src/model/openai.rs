@@ -0,0 +1,246 @@
+//! OpenAI互換 Chat Completions API を呼び出す実装
+//!
+//! 埋め込みモデルを設定すると、知識の検索に Embeddings API も使う
+
+use super::{LanguageModel, ModelRequest, ModelResponse, TokenUsage};
+use serde::Deserialize;
+use std::time::Duration;
+
+/// OpenAI互換モデル
+///
+/// `{base_url}/chat/completions` にリクエストを送信する。
+/// OpenAI 以外にも、同じAPIを提供するローカルサーバー（vLLM, Ollama等）で利用できる。
+pub struct OpenAiCompatibleModel {
+    /// APIのベースURL（例: https://api.openai.com/v1）
+    base_url: String,
+
+    /// モデル名
+    model: String,
+
+    /// APIキー
+    api_key: Option<String>,
+
+    /// サンプリング温度
+    temperature: f32,
+
+    /// 埋め込みモデル名（`None` なら埋め込みを使わない）
+    embedding_model: Option<String>,
+
+    /// HTTPクライアント
+    agent: ureq::Agent,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+    #[serde(default)]
+    usage: Option<ChatUsage>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+    #[serde(default)]
+    finish_reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ChatMessage {
+    /// ツール呼び出し・拒否の応答では `null` になる
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    refusal: Option<String>,
+}
+
+impl ChatChoice {
+    /// 応答のテキスト（本文がない応答は理由を添えてエラーにする）
+    fn into_text(self) -> anyhow::Result<String> {
+        if let Some(content) = self.message.content {
+            return Ok(content);
+        }
+        match self.message.refusal {
+            Some(refusal) => anyhow::bail!("モデルが応答を拒否しました: {}", refusal),
+            None => anyhow::bail!(
+                "モデルAPIの応答に本文がありません (finish_reason: {})",
+                self.finish_reason.as_deref().unwrap_or("-")
+            ),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ChatUsage {
+    prompt_tokens: u64,
+    completion_tokens: u64,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+impl OpenAiCompatibleModel {
+    /// 既定のベースURL
+    pub const DEFAULT_BASE_URL: &'static str = "https://api.openai.com/v1";
+
+    /// 新しいモデルを作成
+    pub fn new(base_url: impl Into<String>, model: impl Into<String>) -> Self {
+        let agent = ureq::Agent::config_builder()
+            .timeout_global(Some(Duration::from_secs(120)))
+            .build()
+            .into();
+
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            model: model.into(),
+            api_key: None,
+            temperature: 0.0,
+            embedding_model: None,
+            agent,
+        }
+    }
+
+    /// 環境変数から作成
+    ///
+    /// - `SEIZE_LLM_BASE_URL`（省略時: OpenAI）
+    /// - `SEIZE_LLM_MODEL`（必須）
+    /// - `SEIZE_LLM_API_KEY` または `OPENAI_API_KEY`
+    /// - `SEIZE_EMBEDDING_MODEL`（省略時: 埋め込みを使わない）
+    pub fn from_env() -> anyhow::Result<Self> {
+        let base_url = std::env::var("SEIZE_LLM_BASE_URL")
+            .unwrap_or_else(|_| Self::DEFAULT_BASE_URL.to_string());
+        let model = std::env::var("SEIZE_LLM_MODEL")
+            .map_err(|_| anyhow::anyhow!("環境変数 SEIZE_LLM_MODEL が設定されていません"))?;
+        let api_key = std::env::var("SEIZE_LLM_API_KEY")
+            .or_else(|_| std::env::var("OPENAI_API_KEY"))
+            .ok();
+
+        let mut llm = Self::new(base_url, model);
+        llm.api_key = api_key;
+        llm.embedding_model = std::env::var("SEIZE_EMBEDDING_MODEL").ok();
+        Ok(llm)
+    }
+
+    /// APIキーを設定
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// サンプリング温度を設定
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = temperature;
+        self
+    }
+
+    /// 埋め込みモデルを設定（`{base_url}/embeddings` を呼び出す）
+    pub fn with_embedding_model(mut self, model: impl Into<String>) -> Self {
+        self.embedding_model = Some(model.into());
+        self
+    }
+
+    /// 認証ヘッダー付きで JSON を送信
+    fn post(&self, url: &str, body: &serde_json::Value) -> anyhow::Result<ureq::http::Response<ureq::Body>> {
+        let mut http_request = self.agent.post(url);
+        if let Some(api_key) = &self.api_key {
+            http_request = http_request.header("Authorization", &format!("Bearer {}", api_key));
+        }
+
+        http_request
+            .send_json(body)
+            .map_err(|e| anyhow::anyhow!("モデルAPIの呼び出しに失敗しました ({}): {}", url, e))
+    }
+}
+
+impl LanguageModel for OpenAiCompatibleModel {
+    fn name(&self) -> &str {
+        &self.model
+    }
+
+    fn complete(&self, request: &ModelRequest) -> anyhow::Result<ModelResponse> {
+        let url = format!("{}/chat/completions", self.base_url);
+        let body = serde_json::json!({
+            "model": self.model,
+            "temperature": self.temperature,
+            "messages": [
+                { "role": "system", "content": request.system },
+                { "role": "user", "content": request.prompt },
+            ],
+        });
+
+        let mut response = self.post(&url, &body)?;
+        let chat: ChatResponse = response
+            .body_mut()
+            .read_json()
+            .map_err(|e| anyhow::anyhow!("モデルAPIの応答を解釈できません: {}", e))?;
+
+        let text = chat
+            .choices
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("モデルAPIの応答に choices がありません"))?
+            .into_text()?;
+        let usage = chat
+            .usage
+            .map(|u| TokenUsage {
+                prompt_tokens: u.prompt_tokens,
+                completion_tokens: u.completion_tokens,
+            })
+            .unwrap_or_default();
+
+        Ok(ModelResponse { text, usage })
+    }
+
+    fn embed(&self, texts: &[String]) -> anyhow::Result<Option<Vec<Vec<f32>>>> {
+        let Some(model) = &self.embedding_model else {
+            return Ok(None);
+        };
+
+        let url = format!("{}/embeddings", self.base_url);
+        let body = serde_json::json!({ "model": model, "input": texts });
+        let mut embeddings: EmbeddingResponse = self
+            .post(&url, &body)?
+            .body_mut()
+            .read_json()
+            .map_err(|e| anyhow::anyhow!("埋め込みAPIの応答を解釈できません: {}", e))?;
+
+        if embeddings.data.len() != texts.len() {
+            anyhow::bail!(
+                "埋め込みAPIの応答の件数が一致しません ({} 件に対して {} 件)",
+                texts.len(),
+                embeddings.data.len()
+            );
+        }
+        embeddings.data.sort_by_key(|d| d.index);
+        Ok(Some(embeddings.data.into_iter().map(|d| d.embedding).collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_null_content_is_reported_clearly() {
+        let choice = |json: &str| serde_json::from_str::<ChatChoice>(json).unwrap().into_text();
+
+        assert_eq!(choice(r#"{"message": {"content": "ok"}}"#).unwrap(), "ok");
+
+        let error = choice(r#"{"message": {"content": null, "tool_calls": []}, "finish_reason": "tool_calls"}"#)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("本文がありません (finish_reason: tool_calls)"));
+
+        let error = choice(r#"{"message": {"content": null, "refusal": "できません"}}"#)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("拒否しました: できません"));
+    }
+}
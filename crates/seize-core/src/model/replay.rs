@@ -0,0 +1,92 @@
+//! 記録済みの応答を返すリプレイ実装
+//!
+//! ネットワークに接続せず決定的に動作するため、テストやデモに使う。
+
+use super::{LanguageModel, ModelRequest, ModelResponse, ModelTask, TokenUsage};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// 記録された応答
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayEntry {
+    /// 対象の処理
+    pub task: ModelTask,
+
+    /// プロンプトに含まれるべき文字列（`None` なら同じ処理のすべてに一致）
+    #[serde(default)]
+    pub prompt_contains: Option<String>,
+
+    /// 返す応答
+    pub response: String,
+}
+
+/// リプレイモデル
+///
+/// リクエストごとに、登録順で最初に一致したエントリの応答を返す。
+/// 一致するエントリがない場合はエラーを返す。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReplayModel {
+    entries: Vec<ReplayEntry>,
+}
+
+impl ReplayModel {
+    /// 空のリプレイモデルを作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 応答を登録
+    pub fn with_response(
+        mut self,
+        task: ModelTask,
+        prompt_contains: Option<&str>,
+        response: impl Into<String>,
+    ) -> Self {
+        self.entries.push(ReplayEntry {
+            task,
+            prompt_contains: prompt_contains.map(str::to_string),
+            response: response.into(),
+        });
+        self
+    }
+
+    /// 記録ファイル（JSON）から読み込む
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("リプレイファイルを読み込めません ({}): {}", path.display(), e))?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// 記録ファイル（JSON）に保存
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+impl LanguageModel for ReplayModel {
+    fn name(&self) -> &str {
+        "replay"
+    }
+
+    fn complete(&self, request: &ModelRequest) -> anyhow::Result<ModelResponse> {
+        let entry = self
+            .entries
+            .iter()
+            .find(|entry| {
+                entry.task == request.task
+                    && entry
+                        .prompt_contains
+                        .as_deref()
+                        .is_none_or(|needle| request.prompt.contains(needle))
+            })
+            .ok_or_else(|| anyhow::anyhow!("{:?} に一致する記録済み応答がありません", request.task))?;
+
+        Ok(ModelResponse {
+            text: entry.response.clone(),
+            usage: TokenUsage::default(),
+        })
+    }
+}
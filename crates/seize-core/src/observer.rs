@@ -0,0 +1,39 @@
+//! # 観測者 (Observer)
+//!
+//! 統一エージェントの反復・ℐ・𝒞・θ₁〜θ₆・収束の各段階を通知する。
+//! ダッシュボード・監査ログ・TUI などはこのトレイトを実装して
+//! `UnifiedAgent::with_observer` で登録する。
+
+use crate::command::ExecutionPlan;
+use crate::convergence::Evaluation;
+use crate::intent::Goal;
+use crate::transformation::TransformationResult;
+use crate::world::World;
+use crate::HaltReason;
+
+/// エージェントの観測者
+///
+/// すべてのメソッドは既定で何もしないため、必要なものだけを実装すればよい。
+/// `iteration` は1始まりの反復回数
+pub trait AgentObserver: Send + Sync {
+    /// 反復の開始
+    fn on_iteration_start(&self, _iteration: usize, _max_iterations: usize) {}
+
+    /// ℐ: 目標が解決された
+    fn on_intent_resolved(&self, _iteration: usize, _goal: &Goal) {}
+
+    /// 𝒞: 実行計画が作成された
+    fn on_plan_created(&self, _iteration: usize, _plan: &ExecutionPlan) {}
+
+    /// Θ: 変換フェーズ（θ₁〜θ₆）が完了した
+    fn on_phase(&self, _iteration: usize, _result: &TransformationResult) {}
+
+    /// 反復の終了（収束判定の後）
+    fn on_iteration_end(&self, _iteration: usize, _world: &World, _evaluation: &Evaluation) {}
+
+    /// 収束した
+    fn on_converged(&self, _iteration: usize, _criterion: &str) {}
+
+    /// 却下・判断待ちにより反復を止めた（この反復の `on_iteration_end` は呼ばれない）
+    fn on_halted(&self, _iteration: usize, _reason: &HaltReason) {}
+}
@@ -0,0 +1,178 @@
+//! # 変換フェーズのパイプライン (Phase Pipeline)
+//!
+//! Θ を構成するフェーズの並びを組み替える。
+//! 組み込みの θ₁〜θ₆ を独自のフェーズで置き換えたり、フェーズの前後に独自のフェーズを
+//! 差し込んだり、計画に応じてフェーズを飛ばしたりできる。
+
+use crate::command::ExecutionPlan;
+use crate::error::{SeizeError, SeizeResult};
+use crate::transaction::Transaction;
+use crate::transformation::{TransformationPhase, TransformationResult};
+use crate::world::World;
+use std::sync::Arc;
+
+/// フェーズに渡す実行中の状態
+pub struct PhaseContext<'a> {
+    /// 実行計画（θ₄ の後はタスクの状態が更新されている）
+    pub plan: &'a mut ExecutionPlan,
+
+    /// 変換中の世界
+    pub world: &'a mut World,
+
+    /// このフェーズより前に実行したフェーズの結果
+    pub results: &'a [TransformationResult],
+
+    /// 外部への副作用を取り消す補償操作の登録先（いずれかのフェーズが失敗すると実行される）
+    pub transaction: &'a Transaction,
+}
+
+/// 変換フェーズ
+///
+/// 独自のフェーズは `TransformationPhase::Custom` を名乗るか、
+/// 置き換える組み込みのフェーズと同じ種類を名乗る
+pub trait Phase: Send + Sync {
+    /// フェーズの種類
+    fn phase(&self) -> TransformationPhase;
+
+    /// フェーズを実行する
+    fn run(&self, context: PhaseContext<'_>) -> SeizeResult<TransformationResult>;
+}
+
+/// フェーズを飛ばす条件
+pub type SkipCondition = Arc<dyn Fn(&ExecutionPlan) -> bool + Send + Sync>;
+
+/// パイプラインの1段
+#[derive(Clone)]
+pub(crate) struct Stage {
+    /// フェーズの種類（差し込み・置き換えの位置の目印）
+    pub(crate) phase: TransformationPhase,
+
+    /// 独自の実装（`None` なら組み込みのフェーズ）
+    pub(crate) implementation: Option<Arc<dyn Phase>>,
+
+    /// 真になる計画ではこの段を飛ばす
+    pub(crate) skip_when: Option<SkipCondition>,
+}
+
+impl Stage {
+    /// 計画に対してこの段を飛ばすか
+    pub(crate) fn skips(&self, plan: &ExecutionPlan) -> bool {
+        self.skip_when.as_ref().is_some_and(|condition| condition(plan))
+    }
+}
+
+/// 変換フェーズのパイプライン
+#[derive(Clone)]
+pub struct PhasePipeline {
+    stages: Vec<Stage>,
+}
+
+impl PhasePipeline {
+    /// 組み込みの θ₁〜θ₆ からなるパイプライン
+    pub fn standard() -> Self {
+        let stages = [
+            TransformationPhase::Understand,
+            TransformationPhase::Generate,
+            TransformationPhase::Allocate,
+            TransformationPhase::Execute,
+            TransformationPhase::Integrate,
+            TransformationPhase::Learn,
+        ]
+        .into_iter()
+        .map(|phase| Stage {
+            phase,
+            implementation: None,
+            skip_when: None,
+        })
+        .collect();
+
+        Self { stages }
+    }
+
+    /// フェーズを独自の実装で置き換える（位置と飛ばす条件は引き継ぐ）
+    pub fn replace(mut self, phase: TransformationPhase, implementation: Arc<dyn Phase>) -> SeizeResult<Self> {
+        let index = self.position(&phase)?;
+        let replacement = implementation.phase();
+        if replacement != phase {
+            self.ensure_absent(&replacement)?;
+        }
+
+        let stage = &mut self.stages[index];
+        stage.phase = replacement;
+        stage.implementation = Some(implementation);
+        Ok(self)
+    }
+
+    /// フェーズの前に独自のフェーズを差し込む
+    pub fn insert_before(self, anchor: TransformationPhase, implementation: Arc<dyn Phase>) -> SeizeResult<Self> {
+        self.insert(anchor, implementation, 0)
+    }
+
+    /// フェーズの後に独自のフェーズを差し込む
+    pub fn insert_after(self, anchor: TransformationPhase, implementation: Arc<dyn Phase>) -> SeizeResult<Self> {
+        self.insert(anchor, implementation, 1)
+    }
+
+    /// フェーズを取り除く
+    pub fn skip(mut self, phase: TransformationPhase) -> SeizeResult<Self> {
+        let index = self.position(&phase)?;
+        self.stages.remove(index);
+        Ok(self)
+    }
+
+    /// 条件が真になる計画ではフェーズを飛ばす
+    pub fn skip_when(
+        mut self,
+        phase: TransformationPhase,
+        condition: impl Fn(&ExecutionPlan) -> bool + Send + Sync + 'static,
+    ) -> SeizeResult<Self> {
+        let index = self.position(&phase)?;
+        self.stages[index].skip_when = Some(Arc::new(condition));
+        Ok(self)
+    }
+
+    /// フェーズの並び
+    pub fn phases(&self) -> Vec<TransformationPhase> {
+        self.stages.iter().map(|stage| stage.phase.clone()).collect()
+    }
+
+    pub(crate) fn stages(&self) -> &[Stage] {
+        &self.stages
+    }
+
+    fn insert(mut self, anchor: TransformationPhase, implementation: Arc<dyn Phase>, offset: usize) -> SeizeResult<Self> {
+        let index = self.position(&anchor)?;
+        let phase = implementation.phase();
+        self.ensure_absent(&phase)?;
+
+        self.stages.insert(
+            index + offset,
+            Stage {
+                phase,
+                implementation: Some(implementation),
+                skip_when: None,
+            },
+        );
+        Ok(self)
+    }
+
+    fn position(&self, phase: &TransformationPhase) -> SeizeResult<usize> {
+        self.stages
+            .iter()
+            .position(|stage| &stage.phase == phase)
+            .ok_or_else(|| SeizeError::InvalidPipeline(format!("フェーズ {} がありません", phase.label())))
+    }
+
+    fn ensure_absent(&self, phase: &TransformationPhase) -> SeizeResult<()> {
+        if self.stages.iter().any(|stage| &stage.phase == phase) {
+            return Err(SeizeError::InvalidPipeline(format!("フェーズ {} はすでにあります", phase.label())));
+        }
+        Ok(())
+    }
+}
+
+impl Default for PhasePipeline {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
@@ -0,0 +1,308 @@
+//! # 分解レシピ (Decomposition Recipes)
+//!
+//! 𝒞 が目標をタスクに分解するときの手順を外部ファイルで定義する。
+//! 組み込みのレシピは `recipes/default.toml` にあり、
+//! プロジェクトのレシピ（`.seize/recipes.toml` など）で同じ名前のレシピを置き換えたり、
+//! キーワードで選ばれる独自の手順を加えたりできる。
+
+use crate::command::{flatten_tasks, Task, TaskDraft, TaskType};
+use crate::intent::{Goal, GoalCategory};
+use crate::lexicon::Language;
+use crate::scheduler::TaskGraph;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+const BUILTIN_RECIPES: &str = include_str!("../recipes/default.toml");
+
+/// レシピの文言（言語ごとに書き分けられる）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RecipeText {
+    /// すべての言語で同じ文言
+    Plain(String),
+    /// 言語コードごとの文言（該当がなければ ja、それもなければ最初のもの）
+    Localized(BTreeMap<String, String>),
+}
+
+impl RecipeText {
+    /// 指定した言語の文言
+    pub fn text(&self, language: Language) -> &str {
+        match self {
+            RecipeText::Plain(text) => text,
+            RecipeText::Localized(texts) => texts
+                .get(language.code())
+                .or_else(|| texts.get(Language::Ja.code()))
+                .or_else(|| texts.values().next())
+                .map_or("", String::as_str),
+        }
+    }
+}
+
+/// タスクのテンプレート
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskTemplate {
+    /// タスクID
+    pub id: String,
+
+    /// タスクの説明（`{goal}` は目標の記述に置き換える）
+    pub description: RecipeText,
+
+    /// タスクタイプ
+    pub task_type: TaskType,
+
+    /// 依存タスク
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+
+    /// 実行パラメータ（`{goal}` は目標の記述に置き換える）
+    #[serde(default)]
+    pub parameters: HashMap<String, String>,
+
+    /// サブタスクのテンプレート
+    #[serde(default)]
+    pub subtasks: Vec<TaskTemplate>,
+}
+
+impl TaskTemplate {
+    /// 目標の記述と言語に合わせてタスクの下書きを作る
+    fn draft(&self, goal: &str, language: Language) -> TaskDraft {
+        let fill = |text: &str| text.replace("{goal}", goal);
+        TaskDraft {
+            id: self.id.clone(),
+            description: fill(self.description.text(language)),
+            task_type: self.task_type.clone(),
+            dependencies: self.dependencies.clone(),
+            parameters: self
+                .parameters
+                .iter()
+                .map(|(key, value)| (key.clone(), fill(value)))
+                .collect(),
+            subtasks: self.subtasks.iter().map(|t| t.draft(goal, language)).collect(),
+        }
+    }
+}
+
+/// 分解レシピ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recipe {
+    /// レシピ名
+    pub name: String,
+
+    /// 対象の目標カテゴリ（`None` ならすべて）
+    #[serde(default)]
+    pub category: Option<GoalCategory>,
+
+    /// 目標の記述に含まれると優先して使うキーワード
+    #[serde(default)]
+    pub keywords: Vec<String>,
+
+    /// タスクのテンプレート
+    pub tasks: Vec<TaskTemplate>,
+}
+
+impl Recipe {
+    /// 目標に合わせてタスクの下書きを作る
+    pub fn draft(&self, goal: &Goal) -> Vec<TaskDraft> {
+        self.tasks
+            .iter()
+            .map(|template| template.draft(&goal.description, goal.language))
+            .collect()
+    }
+
+    fn matches_category(&self, category: &GoalCategory) -> bool {
+        self.category.as_ref().is_none_or(|c| c == category)
+    }
+
+    fn matches_keywords(&self, description: &str) -> bool {
+        let description = description.to_lowercase();
+        self.keywords
+            .iter()
+            .any(|keyword| description.contains(&keyword.to_lowercase()))
+    }
+}
+
+/// レシピ集
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecipeBook {
+    pub recipes: Vec<Recipe>,
+}
+
+impl RecipeBook {
+    /// 組み込みのレシピ集
+    pub fn builtin() -> Self {
+        toml::from_str(BUILTIN_RECIPES).expect("組み込みのレシピの形式が不正です")
+    }
+
+    /// レシピファイルを読み込む（拡張子で形式を判断: .toml / .yaml / .yml / .json）
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("レシピファイルを読み込めません ({}): {}", path.display(), e))?;
+
+        let book: Self = match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => toml::from_str(&content).map_err(anyhow::Error::from),
+            // serde_yaml は列挙型を YAML タグで表すため、JSON と同じマップ形式で読む
+            Some("yaml") | Some("yml") => serde_yaml::from_str::<serde_json::Value>(&content)
+                .map_err(anyhow::Error::from)
+                .and_then(|value| serde_json::from_value(value).map_err(anyhow::Error::from)),
+            Some("json") => serde_json::from_str(&content).map_err(anyhow::Error::from),
+            _ => anyhow::bail!("レシピファイルの形式を判断できません（.toml / .yaml / .json）: {}", path.display()),
+        }
+        .map_err(|e| anyhow::anyhow!("レシピファイルの形式が不正です ({}): {}", path.display(), e))?;
+
+        book.validate()?;
+        Ok(book)
+    }
+
+    /// 組み込みのレシピ集にプロジェクトのレシピを重ねる
+    ///
+    /// プロジェクトのレシピを先に評価し、同じ名前の組み込みのレシピは取り除く
+    pub fn with_overrides(self, overrides: RecipeBook) -> Self {
+        let names: HashSet<String> = overrides.recipes.iter().map(|r| r.name.clone()).collect();
+        let mut recipes = overrides.recipes;
+        recipes.extend(self.recipes.into_iter().filter(|r| !names.contains(&r.name)));
+        Self { recipes }
+    }
+
+    /// レシピ名の重複と、タスクの依存関係（存在・循環）を検査する
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let mut names = HashSet::new();
+        for recipe in &self.recipes {
+            if !names.insert(recipe.name.as_str()) {
+                anyhow::bail!("レシピ名が重複しています: {}", recipe.name);
+            }
+            let tasks: Vec<Task> = recipe
+                .tasks
+                .iter()
+                .map(|template| template.draft("", Language::default()).into_task())
+                .collect();
+            if tasks.is_empty() {
+                anyhow::bail!("レシピ {} にタスクがありません", recipe.name);
+            }
+            flatten_tasks(&tasks)
+                .and_then(|tasks| TaskGraph::build(&tasks))
+                .map_err(|e| anyhow::anyhow!("レシピ {}: {}", recipe.name, e))?;
+        }
+        Ok(())
+    }
+
+    /// 目標に使うレシピを選ぶ
+    ///
+    /// 1. キーワードが目標の記述に含まれ、カテゴリも合うレシピ
+    /// 2. キーワードのない、カテゴリの合うレシピ（カテゴリ指定のあるものを優先）
+    pub fn select(&self, goal: &Goal) -> Option<&Recipe> {
+        self.recipes
+            .iter()
+            .find(|r| !r.keywords.is_empty() && r.matches_keywords(&goal.description) && r.matches_category(&goal.category))
+            .or_else(|| {
+                self.recipes
+                    .iter()
+                    .find(|r| r.keywords.is_empty() && r.category.as_ref() == Some(&goal.category))
+            })
+            .or_else(|| {
+                self.recipes
+                    .iter()
+                    .find(|r| r.keywords.is_empty() && r.category.is_none())
+            })
+    }
+
+    /// 目標をレシピに従ってタスクの下書きに分解する（該当するレシピがなければ目標そのものを分析する）
+    pub fn draft_tasks(&self, goal: &Goal) -> Vec<TaskDraft> {
+        match self.select(goal) {
+            Some(recipe) => recipe.draft(goal),
+            None => vec![TaskDraft {
+                id: "task_0".to_string(),
+                description: goal.description.clone(),
+                task_type: TaskType::Analysis,
+                dependencies: Vec::new(),
+                parameters: HashMap::new(),
+                subtasks: Vec::new(),
+            }],
+        }
+    }
+}
+
+impl Default for RecipeBook {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn goal(description: &str, category: GoalCategory, language: Language) -> Goal {
+        Goal {
+            language,
+            ..Goal::for_test(description, category)
+        }
+    }
+
+    #[test]
+    fn test_builtin_recipes_cover_every_category() {
+        let book = RecipeBook::builtin();
+        book.validate().unwrap();
+
+        for category in [
+            GoalCategory::Understanding,
+            GoalCategory::CodeGeneration,
+            GoalCategory::SystemDesign,
+            GoalCategory::OrganizationalManagement,
+            GoalCategory::DecisionSupport,
+        ] {
+            let recipe = book.select(&goal("x", category.clone(), Language::Ja)).unwrap();
+            assert_eq!(recipe.category, Some(category));
+        }
+
+        let drafts = book.draft_tasks(&goal("Redesign auth", GoalCategory::SystemDesign, Language::En));
+        assert_eq!(drafts.len(), 4);
+        assert_eq!(drafts[0].description, "Survey the current system");
+    }
+
+    #[test]
+    fn test_project_recipes_override_and_extend() {
+        let project: RecipeBook = toml::from_str(
+            r#"
+            [[recipes]]
+            name = "release"
+            keywords = ["リリース"]
+            tasks = [
+              { id = "notes", description = "{goal} のリリースノート", task_type = "FileWrite", parameters = { path = "RELEASE.md" } },
+              { id = "check", description = "リリース前の検証", task_type = "Validation", dependencies = ["notes"] },
+            ]
+
+            [[recipes]]
+            name = "understanding"
+            category = "Understanding"
+            tasks = [{ id = "task_0", description = "読むだけ", task_type = "FileRead" }]
+            "#,
+        )
+        .unwrap();
+        project.validate().unwrap();
+        let book = RecipeBook::builtin().with_overrides(project);
+
+        let drafts = book.draft_tasks(&goal("v2 をリリースする", GoalCategory::CodeGeneration, Language::Ja));
+        assert_eq!(drafts[0].id, "notes");
+        assert_eq!(drafts[0].description, "v2 をリリースする のリリースノート");
+        assert_eq!(drafts[0].parameters["path"], "RELEASE.md");
+
+        let drafts = book.draft_tasks(&goal("調べる", GoalCategory::Understanding, Language::Ja));
+        assert_eq!(drafts.len(), 1);
+        assert_eq!(drafts[0].description, "読むだけ");
+
+        let cyclic: RecipeBook = toml::from_str(
+            r#"
+            [[recipes]]
+            name = "loop"
+            tasks = [
+              { id = "a", description = "A", task_type = "Analysis", dependencies = ["b"] },
+              { id = "b", description = "B", task_type = "Analysis", dependencies = ["a"] },
+            ]
+            "#,
+        )
+        .unwrap();
+        assert!(cyclic.validate().is_err());
+    }
+}
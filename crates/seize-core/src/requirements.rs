@@ -3,8 +3,14 @@
 //! システムズエンジニアリングおよび要求工学の原則に基づいた
 //! 要求の開発・管理・検証を行うモジュール
 
+use crate::charter::{CharterEngine, CharterSubject, CharterViolation, RuleScope, Severity};
+use crate::error::{SeizeError, SeizeResult};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
 
 /// 要求（Requirement）
 ///
@@ -117,7 +123,7 @@ pub struct AcceptanceCriterion {
 }
 
 /// トレーサビリティ
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Traceability {
     /// 上位要求（親要求）
     pub parent_requirements: Vec<String>,
@@ -189,6 +195,23 @@ pub struct RequirementsSpecification {
     pub glossary: HashMap<String, String>,
 }
 
+impl RequirementsSpecification {
+    /// 要求仕様書ファイル（JSON）を読み込む
+    pub fn load(path: impl AsRef<Path>) -> SeizeResult<Self> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path).map_err(|e| SeizeError::io(path, e))?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// 要求仕様書ファイル（JSON）に保存
+    pub fn save(&self, path: impl AsRef<Path>) -> SeizeResult<()> {
+        let path = path.as_ref();
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json).map_err(|e| SeizeError::io(path, e))?;
+        Ok(())
+    }
+}
+
 /// ステークホルダー分析
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StakeholderAnalysis {
@@ -232,6 +255,9 @@ pub struct RequirementsEngineer {
 
     /// 組織憲章準拠フラグ
     enforce_charter: bool,
+
+    /// 憲章に基づく検証器
+    charter: Arc<CharterEngine>,
 }
 
 impl RequirementsEngineer {
@@ -253,9 +279,36 @@ impl RequirementsEngineer {
                 glossary: HashMap::new(),
             },
             enforce_charter,
+            charter: Arc::new(CharterEngine::default()),
         }
     }
 
+    /// 既存の要求仕様書から要求エンジニアを作成
+    pub fn from_specification(specification: RequirementsSpecification, enforce_charter: bool) -> Self {
+        Self {
+            specification,
+            enforce_charter,
+            charter: Arc::new(CharterEngine::default()),
+        }
+    }
+
+    /// 検証に使う憲章を設定
+    pub fn with_charter(mut self, charter: Arc<CharterEngine>) -> Self {
+        self.charter = charter;
+        self
+    }
+
+    /// 要求仕様書ファイル（JSON）を読み込んで要求エンジニアを作成
+    pub fn load(path: impl AsRef<Path>, enforce_charter: bool) -> SeizeResult<Self> {
+        let specification = RequirementsSpecification::load(path)?;
+        Ok(Self::from_specification(specification, enforce_charter))
+    }
+
+    /// 現在の要求仕様書をファイル（JSON）に保存
+    pub fn save(&self, path: impl AsRef<Path>) -> SeizeResult<()> {
+        self.specification.save(path)
+    }
+
     /// 要求を抽出（Elicitation）
     ///
     /// ## 技法
@@ -268,7 +321,7 @@ impl RequirementsEngineer {
         &mut self,
         input: &str,
         stakeholder: &str,
-    ) -> anyhow::Result<Vec<Requirement>> {
+    ) -> SeizeResult<Vec<Requirement>> {
         tracing::info!("要求抽出開始: ステークホルダー={}", stakeholder);
 
         // 自然言語から要求を抽出（簡易実装）
@@ -287,17 +340,17 @@ impl RequirementsEngineer {
     /// - 完全性（Complete）
     /// - 一貫性（Consistent）
     /// - 実現可能性（Feasible）
-    pub fn analyze_requirement(&self, requirement: &Requirement) -> anyhow::Result<AnalysisResult> {
+    pub fn analyze_requirement(&self, requirement: &Requirement) -> SeizeResult<AnalysisResult> {
         let mut issues = Vec::new();
 
         // 明確性チェック
         if requirement.description.len() < 10 {
-            issues.push("要求の記述が短すぎます（明確性）".to_string());
+            issues.push(Finding::DescriptionTooShort);
         }
 
         // 検証可能性チェック
         if requirement.acceptance_criteria.is_empty() {
-            issues.push("受入基準が定義されていません（検証可能性）".to_string());
+            issues.push(Finding::MissingAcceptanceCriteria);
         }
 
         // 一貫性チェック（親要求との整合性）
@@ -306,21 +359,32 @@ impl RequirementsEngineer {
             // （実際の実装では親要求を取得して比較）
         }
 
-        // 憲章準拠チェック
-        if self.enforce_charter {
-            self.validate_against_charter(requirement, &mut issues)?;
+        // 憲章準拠チェック（要求の分析では処理を止めず問題として報告する）
+        let violations = if self.enforce_charter {
+            self.validate_against_charter(requirement)
+        } else {
+            Vec::new()
+        };
+        let mut recommendations = self.generate_recommendations(requirement);
+        for violation in &violations {
+            let finding = Finding::Charter(violation.clone());
+            match violation.severity {
+                Severity::Error | Severity::Warn => issues.push(finding),
+                Severity::Info => recommendations.push(finding),
+            }
         }
 
         Ok(AnalysisResult {
             requirement_id: requirement.id.clone(),
             is_valid: issues.is_empty(),
             issues,
-            recommendations: self.generate_recommendations(requirement),
+            recommendations,
+            violations,
         })
     }
 
     /// 要求を仕様化（Specification）
-    pub fn add_requirement(&mut self, requirement: Requirement) -> anyhow::Result<()> {
+    pub fn add_requirement(&mut self, requirement: Requirement) -> SeizeResult<()> {
         // 分析実行
         let analysis = self.analyze_requirement(&requirement)?;
 
@@ -339,7 +403,7 @@ impl RequirementsEngineer {
     /// ## 質問
     /// - 正しいシステムを作っているか？
     /// - ステークホルダーのニーズを満たしているか？
-    pub fn validate_requirements(&self) -> anyhow::Result<ValidationReport> {
+    pub fn validate_requirements(&self) -> SeizeResult<ValidationReport> {
         let mut report = ValidationReport {
             total_requirements: self.specification.requirements.len(),
             valid_requirements: 0,
@@ -351,13 +415,22 @@ impl RequirementsEngineer {
             if analysis.is_valid {
                 report.valid_requirements += 1;
             } else {
-                report.issues.extend(analysis.issues);
+                report.issues.extend(analysis.issues.iter().map(Finding::to_string));
             }
         }
 
         Ok(report)
     }
 
+    /// 仕様書内のすべての要求を分析
+    pub fn analyze_all(&self) -> SeizeResult<Vec<AnalysisResult>> {
+        self.specification
+            .requirements
+            .iter()
+            .map(|req| self.analyze_requirement(req))
+            .collect()
+    }
+
     /// 要求のトレーサビリティ分析
     pub fn analyze_traceability(&self) -> TraceabilityMatrix {
         let mut matrix = TraceabilityMatrix {
@@ -388,7 +461,7 @@ impl RequirementsEngineer {
         &self,
         text: &str,
         stakeholder: &str,
-    ) -> anyhow::Result<Vec<Requirement>> {
+    ) -> SeizeResult<Vec<Requirement>> {
         let mut requirements = Vec::new();
 
         // 簡易実装: "〜する必要がある"などのパターンで要求を抽出
@@ -415,66 +488,79 @@ impl RequirementsEngineer {
         Ok(requirements)
     }
 
-    fn validate_against_charter(
-        &self,
-        requirement: &Requirement,
-        issues: &mut Vec<String>,
-    ) -> anyhow::Result<()> {
-        // 組織憲章に基づく検証
-        let desc_lower = requirement.description.to_lowercase();
-
-        // AI自動判断の禁止チェック
-        if desc_lower.contains("自動判断") && !desc_lower.contains("人間") {
-            issues.push("憲章違反: 自動判断には人間の承認が必要です".to_string());
-        }
-
-        // データ最小化原則
-        if desc_lower.contains("すべてのデータ") || desc_lower.contains("全データ") {
-            issues.push("憲章推奨: 必要最小限のデータ収集を検討してください".to_string());
-        }
-
-        // 説明可能性
-        if requirement.req_type == RequirementType::NonFunctional {
-            if let RequirementType::NonFunctional { category } = &requirement.req_type {
-                if *category == NonFunctionalCategory::Security {
-                    if requirement.acceptance_criteria.is_empty() {
-                        issues.push("セキュリティ要求には明確な受入基準が必要です".to_string());
-                    }
-                }
-            }
-        }
-
-        Ok(())
+    fn validate_against_charter(&self, requirement: &Requirement) -> Vec<CharterViolation> {
+        let subject = CharterSubject::new(RuleScope::Requirement, &requirement.description)
+            .with_requirement(requirement);
+        self.charter.evaluate(&subject)
     }
 
-    fn generate_recommendations(&self, requirement: &Requirement) -> Vec<String> {
+    fn generate_recommendations(&self, requirement: &Requirement) -> Vec<Finding> {
         let mut recommendations = Vec::new();
 
         if requirement.acceptance_criteria.is_empty() {
-            recommendations.push("Given-When-Then形式の受入基準を追加してください".to_string());
+            recommendations.push(Finding::AddAcceptanceCriteria);
         }
 
         if requirement.traceability.parent_requirements.is_empty()
             && requirement.req_type != RequirementType::Business
         {
-            recommendations.push("上位要求とのトレーサビリティを確立してください".to_string());
+            recommendations.push(Finding::MissingTraceability);
         }
 
         if requirement.stakeholders.is_empty() {
-            recommendations.push("ステークホルダーを明記してください".to_string());
+            recommendations.push(Finding::MissingStakeholders);
         }
 
         recommendations
     }
 }
 
+/// 要求の分析で見つかった問題・推奨事項
+///
+/// 表示する文言は利用側で選べるよう、種類と該当した憲章のルールだけを持つ。
+/// `Display` は日本語の文言を返す
+#[derive(Debug, Clone, PartialEq)]
+pub enum Finding {
+    /// 要求の記述が短すぎる（明確性）
+    DescriptionTooShort,
+    /// 受入基準が定義されていない（検証可能性）
+    MissingAcceptanceCriteria,
+    /// Given-When-Then形式の受入基準を追加する
+    AddAcceptanceCriteria,
+    /// 上位要求とのトレーサビリティがない
+    MissingTraceability,
+    /// ステークホルダーが明記されていない
+    MissingStakeholders,
+    /// 憲章のルールに該当した
+    Charter(CharterViolation),
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DescriptionTooShort => write!(f, "要求の記述が短すぎます（明確性）"),
+            Self::MissingAcceptanceCriteria => write!(f, "受入基準が定義されていません（検証可能性）"),
+            Self::AddAcceptanceCriteria => write!(f, "Given-When-Then形式の受入基準を追加してください"),
+            Self::MissingTraceability => write!(f, "上位要求とのトレーサビリティを確立してください"),
+            Self::MissingStakeholders => write!(f, "ステークホルダーを明記してください"),
+            Self::Charter(violation) => match violation.severity {
+                Severity::Error => write!(f, "憲章違反 {}", violation),
+                Severity::Warn => write!(f, "憲章推奨 {}", violation),
+                Severity::Info => write!(f, "{}", violation),
+            },
+        }
+    }
+}
+
 /// 分析結果
 #[derive(Debug, Clone)]
 pub struct AnalysisResult {
     pub requirement_id: String,
     pub is_valid: bool,
-    pub issues: Vec<String>,
-    pub recommendations: Vec<String>,
+    pub issues: Vec<Finding>,
+    pub recommendations: Vec<Finding>,
+    /// 該当した憲章のルール
+    pub violations: Vec<CharterViolation>,
 }
 
 /// 検証レポート
@@ -486,12 +572,12 @@ pub struct ValidationReport {
 }
 
 /// トレーサビリティマトリクス
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TraceabilityMatrix {
     pub requirements: HashMap<String, TraceabilityEntry>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TraceabilityEntry {
     pub requirement_id: String,
     pub parent_ids: Vec<String>,
@@ -499,18 +585,6 @@ pub struct TraceabilityEntry {
     pub test_case_ids: Vec<String>,
 }
 
-impl Default for Traceability {
-    fn default() -> Self {
-        Self {
-            parent_requirements: Vec::new(),
-            child_requirements: Vec::new(),
-            related_requirements: Vec::new(),
-            design_elements: Vec::new(),
-            test_cases: Vec::new(),
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -556,4 +630,68 @@ mod tests {
         let result2 = engineer.analyze_requirement(&req).unwrap();
         assert!(result2.is_valid);
     }
+
+    #[test]
+    fn test_charter_violations_are_structured() {
+        let engineer = RequirementsEngineer::new("Test Project".to_string(), true);
+        let req = Requirement {
+            id: "NFR-001".to_string(),
+            req_type: RequirementType::NonFunctional {
+                category: NonFunctionalCategory::Security,
+            },
+            description: "ログイン失敗時はシステムが自動判断でアカウントを凍結する".to_string(),
+            priority: RequirementPriority::Must,
+            stakeholders: vec!["Security".to_string()],
+            acceptance_criteria: Vec::new(),
+            traceability: Traceability::default(),
+            verification_method: VerificationMethod::Test,
+            status: RequirementStatus::Proposed,
+            metadata: HashMap::new(),
+        };
+
+        let result = engineer.analyze_requirement(&req).unwrap();
+        let rules: Vec<(&str, Severity)> = result
+            .violations
+            .iter()
+            .map(|v| (v.rule_id.as_str(), v.severity))
+            .collect();
+        assert_eq!(rules, vec![("HUMAN-001", Severity::Error), ("TRANS-001", Severity::Warn)]);
+        assert!(result.issues.iter().any(|i| i.to_string() == "憲章違反 [HUMAN-001] 最終判断は人間が行う必要があります"));
+    }
+
+    #[test]
+    fn test_traceability_from_loaded_specification() {
+        let mut engineer = RequirementsEngineer::new("Test Project".to_string(), true);
+        let mut traceability = Traceability::default();
+        traceability.parent_requirements.push("BR-001".to_string());
+        traceability.test_cases.push("TC-001".to_string());
+        engineer
+            .add_requirement(Requirement {
+                id: "FR-001".to_string(),
+                req_type: RequirementType::Functional,
+                description: "ユーザー認証機能を提供する".to_string(),
+                priority: RequirementPriority::Must,
+                stakeholders: vec!["User".to_string()],
+                acceptance_criteria: Vec::new(),
+                traceability,
+                verification_method: VerificationMethod::Test,
+                status: RequirementStatus::Proposed,
+                metadata: HashMap::new(),
+            })
+            .unwrap();
+
+        let path = std::env::temp_dir().join(format!("seize-spec-{}.json", std::process::id()));
+        engineer.save(&path).unwrap();
+
+        let loaded = RequirementsEngineer::load(&path, true).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(loaded.export_specification().project_name, "Test Project");
+        assert_eq!(loaded.analyze_all().unwrap().len(), 1);
+
+        let matrix = loaded.analyze_traceability();
+        let entry = &matrix.requirements["FR-001"];
+        assert_eq!(entry.parent_ids, vec!["BR-001".to_string()]);
+        assert_eq!(entry.test_case_ids, vec!["TC-001".to_string()]);
+    }
 }
@@ -0,0 +1,355 @@
+//! # 知識の検索 (Knowledge Retrieval)
+//!
+//! 知識ベースの学習・意思決定から、問いに関係するものを探す。
+//!
+//! - 語彙: 英数字は単語、日本語などはそれ以外の文字の n-gram（bigram）に分けて BM25 で順位付けする
+//! - 埋め込み: モデルが埋め込みに対応していれば、BM25 の上位候補を埋め込みの類似度を加えて並べ直す
+
+use crate::model::LanguageModel;
+use crate::world::{Decision, KnowledgeBase, Learning};
+use std::collections::HashMap;
+
+/// 検索で見つかった知識
+#[derive(Debug, Clone, Copy)]
+pub enum Knowledge<'a> {
+    Learning(&'a Learning),
+    Decision(&'a Decision),
+}
+
+impl Knowledge<'_> {
+    /// 検索の対象にするテキスト
+    pub fn text(&self) -> String {
+        match self {
+            Knowledge::Learning(learning) => learning.content.clone(),
+            Knowledge::Decision(decision) => [
+                decision.purpose.as_str(),
+                decision.input.as_str(),
+                decision.rationale.as_str(),
+                &decision.options.join(" "),
+                decision.rejection_reason.as_deref().unwrap_or(""),
+            ]
+            .join(" "),
+        }
+    }
+
+    /// 文脈に含める1行の要約
+    pub fn summary(&self) -> String {
+        match self {
+            Knowledge::Learning(learning) => {
+                format!("学習: {} (信頼度: {:.2})", learning.content, learning.confidence)
+            }
+            Knowledge::Decision(decision) => format!(
+                "意思決定: {} ({:?}, {})",
+                decision.purpose,
+                decision.status,
+                decision.rejection_reason.as_deref().unwrap_or(&decision.rationale)
+            ),
+        }
+    }
+}
+
+/// 検索結果
+#[derive(Debug, Clone, Copy)]
+pub struct SearchHit<'a> {
+    pub knowledge: Knowledge<'a>,
+    /// 関連度（大きいほど関連が強い）
+    pub score: f64,
+}
+
+/// 埋め込みで並べ直す候補の数（返す件数に対する倍率と下限）
+const RERANK_FACTOR: usize = 4;
+const RERANK_MIN: usize = 20;
+
+impl KnowledgeBase {
+    /// 問いに関係する学習・意思決定を関連度の高い順に最大 `k` 件探す（語彙の一致だけを使う）
+    pub fn search(&self, query: &str, k: usize) -> Vec<SearchHit<'_>> {
+        let documents = self.documents();
+        let index = LexicalIndex::new(documents.iter().map(|d| d.text()));
+
+        index
+            .search(query)
+            .into_iter()
+            .take(k)
+            .map(|(i, score)| SearchHit {
+                knowledge: documents[i],
+                score,
+            })
+            .collect()
+    }
+
+    /// 問いに関係する学習・意思決定を探し、モデルが埋め込みに対応していれば並べ直す
+    ///
+    /// BM25 の上位候補について、正規化した BM25 の値と埋め込みのコサイン類似度の平均で順位を付ける。
+    /// 埋め込みに対応していないモデルや、埋め込みの取得に失敗した場合は `search` と同じ結果を返す
+    pub fn search_with(&self, query: &str, k: usize, model: &dyn LanguageModel) -> Vec<SearchHit<'_>> {
+        let candidates = self.search(query, (k * RERANK_FACTOR).max(RERANK_MIN));
+        if candidates.len() <= 1 || k == 0 {
+            return candidates.into_iter().take(k).collect();
+        }
+
+        let texts: Vec<String> = std::iter::once(query.to_string())
+            .chain(candidates.iter().map(|hit| hit.knowledge.text()))
+            .collect();
+        let embeddings = match model.embed(&texts) {
+            Ok(Some(embeddings)) if embeddings.len() == texts.len() => embeddings,
+            Ok(_) => return candidates.into_iter().take(k).collect(),
+            Err(e) => {
+                tracing::warn!("埋め込みを取得できないため語彙の一致だけで検索します: {}", e);
+                return candidates.into_iter().take(k).collect();
+            }
+        };
+
+        let best = candidates[0].score.max(f64::EPSILON);
+        let mut reranked: Vec<SearchHit> = candidates
+            .iter()
+            .zip(&embeddings[1..])
+            .map(|(hit, embedding)| SearchHit {
+                knowledge: hit.knowledge,
+                score: (hit.score / best + cosine(&embeddings[0], embedding)) / 2.0,
+            })
+            .collect();
+        reranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+        reranked.truncate(k);
+        reranked
+    }
+
+    /// 検索の対象（学習・意思決定の順）
+    fn documents(&self) -> Vec<Knowledge<'_>> {
+        self.learnings
+            .iter()
+            .map(Knowledge::Learning)
+            .chain(self.decisions.iter().map(Knowledge::Decision))
+            .collect()
+    }
+}
+
+/// BM25 による語彙の索引
+#[derive(Debug, Clone, Default)]
+pub struct LexicalIndex {
+    /// 文書ごとの語の出現回数
+    documents: Vec<HashMap<String, usize>>,
+    /// 文書ごとの語数
+    lengths: Vec<usize>,
+    /// 語を含む文書の数
+    document_frequency: HashMap<String, usize>,
+}
+
+impl LexicalIndex {
+    const K1: f64 = 1.2;
+    const B: f64 = 0.75;
+
+    /// 文書から索引を作成
+    pub fn new(documents: impl IntoIterator<Item = String>) -> Self {
+        let mut index = Self::default();
+        for document in documents {
+            let tokens = tokenize(&document);
+            let mut frequency: HashMap<String, usize> = HashMap::new();
+            for token in &tokens {
+                *frequency.entry(token.clone()).or_default() += 1;
+            }
+            for token in frequency.keys() {
+                *index.document_frequency.entry(token.clone()).or_default() += 1;
+            }
+            index.lengths.push(tokens.len());
+            index.documents.push(frequency);
+        }
+        index
+    }
+
+    /// 問いに対する BM25 の値が正の文書を、値の高い順に（文書のインデックス, 値）で返す
+    ///
+    /// 同じ値なら後に加えた（新しい）文書を先にする
+    pub fn search(&self, query: &str) -> Vec<(usize, f64)> {
+        let count = self.documents.len() as f64;
+        let average = self.lengths.iter().sum::<usize>() as f64 / count.max(1.0);
+        let mut terms = tokenize(query);
+        terms.sort();
+        terms.dedup();
+
+        let mut scores: Vec<(usize, f64)> = self
+            .documents
+            .iter()
+            .zip(&self.lengths)
+            .enumerate()
+            .map(|(i, (frequency, &length))| {
+                let score = terms
+                    .iter()
+                    .filter_map(|term| {
+                        let tf = *frequency.get(term)? as f64;
+                        let df = self.document_frequency[term] as f64;
+                        let idf = ((count - df + 0.5) / (df + 0.5) + 1.0).ln();
+                        let norm = Self::K1 * (1.0 - Self::B + Self::B * length as f64 / average.max(1.0));
+                        Some(idf * tf * (Self::K1 + 1.0) / (tf + norm))
+                    })
+                    .sum::<f64>();
+                (i, score)
+            })
+            .filter(|&(_, score)| score > 0.0)
+            .collect();
+        scores.sort_by(|a, b| b.1.total_cmp(&a.1).then(b.0.cmp(&a.0)));
+        scores
+    }
+}
+
+/// テキストを語に分ける
+///
+/// 英数字の並びは小文字の単語、それ以外の文字（日本語など）の並びは文字の bigram
+/// （1文字だけの並びはその文字）にする。空白・記号は区切りとして捨てる
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let mut run: Vec<char> = Vec::new();
+
+    let flush_run = |run: &mut Vec<char>, tokens: &mut Vec<String>| {
+        match run.len() {
+            0 => {}
+            1 => tokens.push(run[0].to_string()),
+            _ => tokens.extend(run.windows(2).map(|w| w.iter().collect())),
+        }
+        run.clear();
+    };
+
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            flush_run(&mut run, &mut tokens);
+            word.extend(c.to_lowercase());
+        } else {
+            if !word.is_empty() {
+                tokens.push(std::mem::take(&mut word));
+            }
+            if c.is_alphanumeric() {
+                run.push(c);
+            } else {
+                flush_run(&mut run, &mut tokens);
+            }
+        }
+    }
+    if !word.is_empty() {
+        tokens.push(word);
+    }
+    flush_run(&mut run, &mut tokens);
+
+    tokens
+}
+
+/// コサイン類似度
+fn cosine(a: &[f32], b: &[f32]) -> f64 {
+    let dot: f64 = a.iter().zip(b).map(|(x, y)| *x as f64 * *y as f64).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| (*x as f64).powi(2)).sum::<f64>().sqrt();
+    let denominator = norm(a) * norm(b);
+    if denominator == 0.0 {
+        0.0
+    } else {
+        dot / denominator
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{ModelRequest, ModelResponse};
+    use crate::world::DecisionStatus;
+
+    fn learning(content: &str) -> Learning {
+        Learning {
+            timestamp: chrono::Utc::now(),
+            content: content.to_string(),
+            source: "Test".to_string(),
+            confidence: 0.5,
+            support: 0.0,
+            refute: 0.0,
+        }
+    }
+
+    fn decision(purpose: &str, rationale: &str) -> Decision {
+        Decision {
+            timestamp: chrono::Utc::now(),
+            purpose: purpose.to_string(),
+            input: String::new(),
+            options: vec![],
+            rationale: rationale.to_string(),
+            approver: "test".to_string(),
+            impact_scope: String::new(),
+            alternatives: vec![],
+            revocation_conditions: vec![],
+            status: DecisionStatus::Approved,
+            rejection_reason: None,
+        }
+    }
+
+    fn knowledge() -> KnowledgeBase {
+        KnowledgeBase {
+            principles: vec![],
+            learnings: vec![
+                learning("Validation task succeeds: cargo test"),
+                learning("認証機能のテストはモックサーバーが必要"),
+                learning("README の構成を見直した"),
+            ],
+            decisions: vec![
+                decision("認証方式をOAuthに変更する", "既存のセッション管理が複雑なため"),
+                decision("Adopt PostgreSQL for the ledger", "transactions are required"),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_tokenize_mixes_words_and_bigrams() {
+        assert_eq!(tokenize("認証をOAuth 2へ"), vec!["認証", "証を", "oauth", "2", "へ"]);
+    }
+
+    #[test]
+    fn test_search_ranks_relevant_knowledge() {
+        let knowledge = knowledge();
+
+        let hits = knowledge.search("認証のテストを追加", 2);
+        assert_eq!(hits.len(), 2);
+        assert!(matches!(hits[0].knowledge, Knowledge::Learning(l) if l.content.starts_with("認証機能のテスト")));
+        assert!(matches!(hits[1].knowledge, Knowledge::Decision(d) if d.purpose.starts_with("認証方式")));
+        assert!(hits[0].score > hits[1].score);
+
+        let hits = knowledge.search("Which database for the ledger?", 5);
+        assert_eq!(hits.len(), 1);
+        assert!(hits[0].knowledge.summary().starts_with("意思決定: Adopt PostgreSQL"));
+
+        assert!(knowledge.search("デプロイ", 5).is_empty());
+    }
+
+    /// 「方式」を含むかどうかだけを表す埋め込みを返すモデル
+    struct KeywordEmbedder;
+
+    impl LanguageModel for KeywordEmbedder {
+        fn name(&self) -> &str {
+            "keyword-embedder"
+        }
+
+        fn complete(&self, _request: &ModelRequest) -> anyhow::Result<ModelResponse> {
+            anyhow::bail!("未対応です")
+        }
+
+        fn embed(&self, texts: &[String]) -> anyhow::Result<Option<Vec<Vec<f32>>>> {
+            Ok(Some(
+                texts
+                    .iter()
+                    .map(|t| if t.contains("方式") { vec![1.0, 0.0] } else { vec![0.0, 1.0] })
+                    .collect(),
+            ))
+        }
+    }
+
+    #[test]
+    fn test_search_with_embeddings_reranks_candidates() {
+        let knowledge = knowledge();
+        let query = "認証のテスト方式";
+
+        let lexical = knowledge.search(query, 1);
+        assert!(matches!(lexical[0].knowledge, Knowledge::Learning(_)));
+
+        let reranked = knowledge.search_with(query, 1, &KeywordEmbedder);
+        assert!(matches!(reranked[0].knowledge, Knowledge::Decision(d) if d.purpose.starts_with("認証方式")));
+        assert!(reranked[0].score <= 1.0);
+
+        // 埋め込みに対応していないモデルでは語彙の検索と同じ
+        let fallback = knowledge.search_with(query, 1, &crate::model::HeuristicModel);
+        assert_eq!(fallback[0].knowledge.text(), lexical[0].knowledge.text());
+    }
+}
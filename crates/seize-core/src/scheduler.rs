@@ -0,0 +1,916 @@
+//! # スケジューラ (Scheduler)
+//!
+//! タスクの依存関係から有向非巡回グラフ (DAG) を構築し、
+//! 実行戦略に従ってタスクを実行する。
+//!
+//! - `Sequential`: トポロジカル順に1つずつ実行
+//! - `Parallel`: 依存関係のないタスクを同じ段 (wave) にまとめて並行実行
+//! - `Adaptive`: `Parallel` と同様に実行し、失敗したタスクを再計画器が計画した別のタスクに置き換えて実行する
+//!
+//! 承認者が設定されている場合、破壊的なタスクは実行前に承認を求め、
+//! 承認されなかったタスクは失敗として扱う。
+//!
+//! タスクの予算に実行時間の上限があれば、上限を過ぎたタスクを待たずに失敗とする。
+//! 予算を超えたタスクの副作用（補償操作）はその場で取り消す。
+
+use crate::approval::{self, ApprovalDecision, ApprovalRequest, Approver};
+use crate::budget::ResourceUsage;
+use crate::command::{ExecutionStrategy, Task, TaskStatus};
+use crate::error::{SeizeError, SeizeResult};
+use crate::executor::{ExecutorRegistry, TaskContext, TaskOutcome};
+use crate::transaction::Transaction;
+use crate::world::TaskRecord;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// タスクの依存グラフ
+#[derive(Debug, Clone)]
+pub struct TaskGraph {
+    /// 依存関係のないタスクごとにまとめた段（タスクのインデックス）
+    layers: Vec<Vec<usize>>,
+
+    /// トポロジカル順（同順位なら元の並び順）
+    order: Vec<usize>,
+}
+
+impl TaskGraph {
+    /// タスクの依存関係からグラフを構築
+    ///
+    /// 重複したID・存在しない依存先は `InvalidPlan`、循環依存は `CyclicDependency` を返す
+    pub fn build(tasks: &[Task]) -> SeizeResult<Self> {
+        let mut index = HashMap::new();
+        for (i, task) in tasks.iter().enumerate() {
+            if index.insert(task.id.as_str(), i).is_some() {
+                return Err(SeizeError::InvalidPlan(format!("タスクIDが重複しています: {}", task.id)));
+            }
+        }
+
+        let mut in_degree = vec![0usize; tasks.len()];
+        let mut dependents = vec![Vec::new(); tasks.len()];
+        for (i, task) in tasks.iter().enumerate() {
+            for dependency in &task.dependencies {
+                let &d = index.get(dependency.as_str()).ok_or_else(|| {
+                    SeizeError::InvalidPlan(format!("タスク {} の依存先 {} が存在しません", task.id, dependency))
+                })?;
+                in_degree[i] += 1;
+                dependents[d].push(i);
+            }
+        }
+
+        // Kahn法: 段ごとに入次数0のタスクを取り出す
+        let mut layers = Vec::new();
+        let mut order = Vec::new();
+        let mut ready: BTreeSet<usize> = (0..tasks.len()).filter(|&i| in_degree[i] == 0).collect();
+        let mut current: Vec<usize> = ready.iter().copied().collect();
+
+        while !current.is_empty() {
+            let mut next = BTreeSet::new();
+            for &i in &current {
+                for &dependent in &dependents[i] {
+                    in_degree[dependent] -= 1;
+                    if in_degree[dependent] == 0 {
+                        next.insert(dependent);
+                    }
+                }
+            }
+            layers.push(current);
+            current = next.into_iter().collect();
+        }
+
+        // トポロジカル順: 実行可能なもののうち元の並び順が最小のものから
+        let mut in_degree: Vec<usize> = tasks.iter().map(|t| t.dependencies.len()).collect();
+        while let Some(i) = ready.pop_first() {
+            order.push(i);
+            for &dependent in &dependents[i] {
+                in_degree[dependent] -= 1;
+                if in_degree[dependent] == 0 {
+                    ready.insert(dependent);
+                }
+            }
+        }
+
+        if order.len() < tasks.len() {
+            let cyclic = (0..tasks.len())
+                .filter(|i| !order.contains(i))
+                .map(|i| tasks[i].id.clone())
+                .collect();
+            return Err(SeizeError::CyclicDependency(cyclic));
+        }
+
+        Ok(Self { layers, order })
+    }
+
+    /// 並行実行できる段
+    pub fn layers(&self) -> &[Vec<usize>] {
+        &self.layers
+    }
+
+    /// トポロジカル順
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+}
+
+/// スケジューラの実行結果
+#[derive(Debug, Clone, Default)]
+pub struct ScheduleReport {
+    /// 完了したタスクの結果（完了順）
+    pub completed: Vec<(String, TaskOutcome)>,
+
+    /// タスクごとの実行記録
+    pub records: Vec<TaskRecord>,
+
+    /// 承認を求めたタスクとその判断
+    pub approvals: Vec<(ApprovalRequest, ApprovalDecision)>,
+}
+
+/// `Adaptive` で失敗したタスクの代わりを計画する再計画器
+pub trait Replanner: Send + Sync {
+    /// 失敗したタスクの代わりに実行するタスクを返す（代わりがなければ `None`）
+    ///
+    /// 返したタスクのID・依存関係・予算は失敗したタスクのものに置き換えるため、
+    /// 依存元のタスクは代わりのタスクの後に実行される
+    fn replan(&self, task: &Task, error: &str) -> anyhow::Result<Option<Task>>;
+}
+
+/// タスクスケジューラ
+pub struct Scheduler<'a> {
+    executors: &'a ExecutorRegistry,
+
+    /// `Adaptive` で1つのタスクを再計画する回数
+    max_retries: u32,
+
+    /// `Adaptive` で失敗したタスクの代わりを計画する再計画器
+    replanner: Option<&'a dyn Replanner>,
+
+    /// 破壊的なタスクの承認者
+    approver: Option<&'a dyn Approver>,
+
+    /// 完了したタスクの補償操作を登録するトランザクション
+    transaction: Option<&'a Transaction>,
+
+    /// 書き込みを段階的に留める場合の、実行前の世界のファイルシステム
+    staged_files: Option<HashMap<String, String>>,
+}
+
+struct Job {
+    index: usize,
+    task: Task,
+    context: TaskContext,
+}
+
+/// 実行中のタスクの状態（上限を過ぎて見放したタスクが後から完了したら、実行したスレッドが副作用を取り消す）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JobState {
+    Running,
+    Finished,
+    Abandoned,
+}
+
+impl<'a> Scheduler<'a> {
+    /// 新しいスケジューラを作成
+    pub fn new(executors: &'a ExecutorRegistry) -> Self {
+        Self {
+            executors,
+            max_retries: 1,
+            replanner: None,
+            approver: None,
+            transaction: None,
+            staged_files: None,
+        }
+    }
+
+    /// `Adaptive` 実行時の再計画の回数を設定
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// `Adaptive` で失敗したタスクの代わりを計画する再計画器を設定
+    ///
+    /// 再計画器がなければ `Adaptive` でも失敗したタスクはそのまま失敗とする
+    pub fn with_replanner(mut self, replanner: &'a dyn Replanner) -> Self {
+        self.replanner = Some(replanner);
+        self
+    }
+
+    /// 破壊的なタスクの承認者を設定
+    pub fn with_approver(mut self, approver: &'a dyn Approver) -> Self {
+        self.approver = Some(approver);
+        self
+    }
+
+    /// 完了したタスクの補償操作を登録するトランザクションを設定
+    pub fn with_transaction(mut self, transaction: &'a Transaction) -> Self {
+        self.transaction = Some(transaction);
+        self
+    }
+
+    /// ファイル書き込みを作業ディレクトリに反映せず、世界のファイルシステムに留める
+    ///
+    /// `files` は実行前の世界のファイルシステム。完了したタスクが読み書きしたファイルは
+    /// 後の段のタスクから読める
+    pub fn with_staged_files(mut self, files: HashMap<String, String>) -> Self {
+        self.staged_files = Some(files);
+        self
+    }
+
+    /// タスクを実行し、各タスクの状態を更新する
+    pub fn run(
+        &self,
+        tasks: &mut [Task],
+        strategy: &ExecutionStrategy,
+        working_directory: &Path,
+        world_version: usize,
+    ) -> SeizeResult<ScheduleReport> {
+        let graph = TaskGraph::build(tasks)?;
+        let waves: VecDeque<Vec<usize>> = match strategy {
+            ExecutionStrategy::Sequential => graph.order().iter().map(|&i| vec![i]).collect(),
+            ExecutionStrategy::Parallel | ExecutionStrategy::Adaptive => graph.layers().to_vec().into(),
+        };
+        let adaptive = matches!(strategy, ExecutionStrategy::Adaptive);
+
+        block_on(self.run_waves(tasks, waves, adaptive, working_directory, world_version))?
+    }
+
+    async fn run_waves(
+        &self,
+        tasks: &mut [Task],
+        mut waves: VecDeque<Vec<usize>>,
+        adaptive: bool,
+        working_directory: &Path,
+        world_version: usize,
+    ) -> SeizeResult<ScheduleReport> {
+        let mut report = ScheduleReport::default();
+        let mut outputs: HashMap<String, String> = HashMap::new();
+        let mut failed: HashSet<String> = HashSet::new();
+        let mut attempts = vec![0u32; tasks.len()];
+        let mut usages = vec![ResourceUsage::default(); tasks.len()];
+        let mut approved: HashSet<usize> = HashSet::new();
+        let mut staged = self.staged_files.clone().map(Arc::new);
+
+        while let Some(wave) = waves.pop_front() {
+            let mut jobs = Vec::new();
+
+            for index in wave {
+                let task = &mut tasks[index];
+                if let Some(dependency) = task.dependencies.iter().find(|d| failed.contains(*d)) {
+                    let error = format!("依存タスク {} が失敗しました", dependency);
+                    task.status = TaskStatus::Failed;
+                    failed.insert(task.id.clone());
+                    report.records.push(record(task, world_version, 0, Duration::ZERO, &ResourceUsage::default(), Err(error)));
+                    continue;
+                }
+
+                if let Some(approver) = self.approver {
+                    if approval::requires_approval(task) && !approved.contains(&index) {
+                        let request = ApprovalRequest::for_task(task);
+                        let decision = approver
+                            .review(&request)
+                            .map_err(|e| SeizeError::Approval(format!("{:#}", e)))?;
+                        let refusal = match &decision {
+                            ApprovalDecision::Approved { .. } => None,
+                            ApprovalDecision::Rejected { approver, reason } => {
+                                Some(format!("{} により却下されました: {}", approver, approval::describe_reason(reason)))
+                            }
+                            ApprovalDecision::Pending => Some("承認待ちです".to_string()),
+                        };
+                        report.approvals.push((request, decision));
+
+                        if let Some(error) = refusal {
+                            tracing::warn!("タスク {} は承認されませんでした: {}", task.id, error);
+                            task.status = TaskStatus::Failed;
+                            failed.insert(task.id.clone());
+                            report.records.push(record(task, world_version, 0, Duration::ZERO, &ResourceUsage::default(), Err(error)));
+                            continue;
+                        }
+                        approved.insert(index);
+                    }
+                }
+
+                if let Some(error) = task.budget.refusal(&task.task_type) {
+                    tracing::warn!("タスク {} は予算がないため実行しません: {}", task.id, error);
+                    task.status = TaskStatus::Failed;
+                    failed.insert(task.id.clone());
+                    report.records.push(record(task, world_version, 0, Duration::ZERO, &usages[index], Err(error)));
+                    continue;
+                }
+
+                task.status = TaskStatus::InProgress;
+                tracing::debug!("タスク実行中: {} ({:?})", task.id, task.task_type);
+                jobs.push(Job {
+                    index,
+                    task: task.clone(),
+                    context: TaskContext {
+                        working_directory: PathBuf::from(working_directory),
+                        dependency_outputs: task
+                            .dependencies
+                            .iter()
+                            .filter_map(|d| outputs.get(d).map(|o| (d.clone(), o.clone())))
+                            .collect(),
+                        budget: task.budget.clone(),
+                        staged_files: staged.clone(),
+                    },
+                });
+            }
+
+            let mut retry = Vec::new();
+            for (index, result, elapsed) in self.run_jobs(jobs).await {
+                let task = &mut tasks[index];
+                attempts[index] += 1;
+                usages[index].wall_time_ms += elapsed.as_millis() as u64;
+
+                // 予算を超えたタスク（実行時間の上限で打ち切ったものを含む）は副作用を取り消して失敗とし、再試行しない
+                let mut over_budget = task.budget.wall_time().is_some_and(|limit| elapsed >= limit);
+                let result = result.and_then(|outcome| {
+                    usages[index].add(&outcome.usage);
+                    let exceeded = task.budget.exceeded(&usages[index]);
+                    over_budget = !exceeded.is_empty();
+                    if over_budget {
+                        let failures = compensate(&outcome);
+                        if !failures.is_empty() {
+                            anyhow::bail!(
+                                "予算を超えました: {}（取り消せなかった副作用: {}）",
+                                exceeded.join(", "),
+                                failures.join(", ")
+                            );
+                        }
+                        anyhow::bail!("予算を超えました: {}", exceeded.join(", "));
+                    }
+                    if let Some(transaction) = self.transaction {
+                        for compensation in &outcome.compensations {
+                            transaction.register(compensation.clone());
+                        }
+                    }
+                    Ok(outcome)
+                });
+
+                match result {
+                    Ok(outcome) => {
+                        task.status = TaskStatus::Completed;
+                        if let Some(files) = staged.as_mut() {
+                            Arc::make_mut(files).extend(outcome.files.clone());
+                        }
+                        outputs.insert(task.id.clone(), outcome.output.clone());
+                        report.records.push(record(
+                            task,
+                            world_version,
+                            attempts[index],
+                            elapsed,
+                            &usages[index],
+                            Ok(&outcome.output),
+                        ));
+                        report.completed.push((task.id.clone(), outcome));
+                    }
+                    Err(e) if adaptive && !over_budget && attempts[index] <= self.max_retries => {
+                        match self.replan(task, &e) {
+                            Some(replacement) => {
+                                tracing::warn!(
+                                    "タスク {} が失敗したため「{}」に置き換えます: {}",
+                                    task.id,
+                                    replacement.description,
+                                    e
+                                );
+                                // 代わりのタスクは元の計画のタスクと同じく、実行前に改めて承認を求める
+                                *task = replacement;
+                                approved.remove(&index);
+                                retry.push(index);
+                            }
+                            None => {
+                                tracing::warn!("タスク {} が失敗しました（代わりのタスクはありません）: {}", task.id, e);
+                                task.status = TaskStatus::Failed;
+                                failed.insert(task.id.clone());
+                                report.records.push(record(
+                                    task,
+                                    world_version,
+                                    attempts[index],
+                                    elapsed,
+                                    &usages[index],
+                                    Err(e.to_string()),
+                                ));
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!("タスク {} が失敗しました: {}", task.id, e);
+                        task.status = TaskStatus::Failed;
+                        failed.insert(task.id.clone());
+                        report.records.push(record(
+                            task,
+                            world_version,
+                            attempts[index],
+                            elapsed,
+                            &usages[index],
+                            Err(e.to_string()),
+                        ));
+                    }
+                }
+            }
+
+            // Adaptive: 代わりのタスクを次の段として実行し、依存タスクはその後に回す
+            if !retry.is_empty() {
+                waves.push_front(retry);
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// 失敗したタスクの代わりを再計画器に求める（失敗したタスクと同じものは代わりにしない）
+    fn replan(&self, task: &Task, error: &anyhow::Error) -> Option<Task> {
+        let replanner = self.replanner?;
+        let mut replacement = match replanner.replan(task, &format!("{:#}", error)) {
+            Ok(replacement) => replacement?,
+            Err(e) => {
+                tracing::warn!("タスク {} を再計画できません: {:#}", task.id, e);
+                return None;
+            }
+        };
+
+        let unchanged = replacement.task_type == task.task_type
+            && replacement.description == task.description
+            && replacement.parameters == task.parameters;
+        if unchanged {
+            return None;
+        }
+
+        replacement.id = task.id.clone();
+        replacement.dependencies = task.dependencies.clone();
+        replacement.budget = task.budget.clone();
+        replacement.recalled = task.recalled.clone();
+        replacement.status = TaskStatus::Pending;
+        replacement.subtasks.clear();
+        Some(replacement)
+    }
+
+    /// 1つの段のタスクを並行に実行
+    ///
+    /// 予算に実行時間の上限があるタスクは、上限を過ぎたら完了を待たずに失敗とする
+    async fn run_jobs(&self, jobs: Vec<Job>) -> Vec<(usize, anyhow::Result<TaskOutcome>, Duration)> {
+        let mut handles = Vec::new();
+
+        for job in jobs {
+            let executor = self.executors.get(&job.task.task_type);
+            let limit = job.context.budget.wall_time();
+            let state = Arc::new(Mutex::new(JobState::Running));
+            let job_state = state.clone();
+            let handle = tokio::task::spawn_blocking(move || {
+                let started = Instant::now();
+                let result = match executor {
+                    Some(executor) => executor.execute(&job.task, &job.context),
+                    None => Err(anyhow::anyhow!("{:?} の実行器が登録されていません", job.task.task_type)),
+                };
+                let mut state = job_state.lock().unwrap_or_else(|e| e.into_inner());
+                if *state == JobState::Abandoned {
+                    if let Ok(outcome) = &result {
+                        for failure in compensate(outcome) {
+                            tracing::warn!("打ち切ったタスク {} の副作用を取り消せません: {}", job.task.id, failure);
+                        }
+                    }
+                }
+                *state = JobState::Finished;
+                (result, started.elapsed())
+            });
+            let deadline = limit.map(|limit| (tokio::time::Instant::now() + limit, limit));
+            handles.push((job.index, handle, state, deadline));
+        }
+
+        let mut results = Vec::new();
+        for (index, mut handle, state, deadline) in handles {
+            let joined = match deadline {
+                Some((deadline, limit)) => match tokio::time::timeout_at(deadline, &mut handle).await {
+                    Ok(joined) => joined,
+                    Err(_) => {
+                        let finished = {
+                            let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+                            if *state == JobState::Running {
+                                *state = JobState::Abandoned;
+                            }
+                            *state == JobState::Finished
+                        };
+                        // 上限の直後に完了したタスクは結果を受け取り、予算の確認で副作用を取り消す
+                        if finished {
+                            handle.await
+                        } else {
+                            let error = anyhow::anyhow!("実行時間の上限 {}ms を過ぎたため打ち切りました", limit.as_millis());
+                            results.push((index, Err(error), limit));
+                            continue;
+                        }
+                    }
+                },
+                None => handle.await,
+            };
+            let (result, elapsed) = match joined {
+                Ok(finished) => finished,
+                Err(e) => (Err(anyhow::anyhow!("タスクの実行が中断されました: {}", e)), Duration::ZERO),
+            };
+            results.push((index, result, elapsed));
+        }
+
+        results
+    }
+}
+
+/// 1つのタスクの副作用を登録と逆の順に取り消し、失敗した補償操作の説明とエラーを返す
+fn compensate(outcome: &TaskOutcome) -> Vec<String> {
+    let transaction = Transaction::new();
+    for compensation in &outcome.compensations {
+        transaction.register(compensation.clone());
+    }
+    transaction.rollback()
+}
+
+fn record(
+    task: &Task,
+    world_version: usize,
+    attempts: u32,
+    elapsed: Duration,
+    usage: &ResourceUsage,
+    result: Result<&str, String>,
+) -> TaskRecord {
+    const SUMMARY_LIMIT: usize = 200;
+
+    let (summary, error) = match result {
+        Ok(output) => (output.chars().take(SUMMARY_LIMIT).collect(), None),
+        Err(error) => (String::new(), Some(error)),
+    };
+
+    TaskRecord {
+        world_version,
+        task_id: task.id.clone(),
+        description: task.description.clone(),
+        task_type: task.task_type.clone(),
+        status: task.status.clone(),
+        attempts,
+        duration_ms: elapsed.as_millis() as u64,
+        summary,
+        error,
+        budget: task.budget.clone(),
+        usage: usage.clone(),
+    }
+}
+
+/// 同期コンテキストから非同期処理を実行
+///
+/// 呼び出し元がマルチスレッドのtokioランタイム上ならそのランタイムを使い、
+/// それ以外では専用のランタイムを作成する
+fn block_on<F>(future: F) -> SeizeResult<F::Output>
+where
+    F: Future + Send,
+    F::Output: Send,
+{
+    use tokio::runtime::{Builder, Handle, RuntimeFlavor};
+
+    // 実行時間の上限で見放したタスクの完了は待たずにランタイムを閉じる（取り消しは実行中のスレッドが行う）
+    let run_in_new_runtime = |future: F| {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| SeizeError::ExecutionFailed(format!("tokioランタイムを作成できません: {}", e)))?;
+        let output = runtime.block_on(future);
+        runtime.shutdown_background();
+        Ok(output)
+    };
+
+    match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            Ok(tokio::task::block_in_place(|| handle.block_on(future)))
+        }
+        // current_thread ランタイム上ではブロックできないため別スレッドで実行する
+        Ok(_) => std::thread::scope(|scope| {
+            scope
+                .spawn(|| run_in_new_runtime(future))
+                .join()
+                .map_err(|_| SeizeError::ExecutionFailed("スケジューラのスレッドが異常終了しました".to_string()))?
+        }),
+        Err(_) => run_in_new_runtime(future),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::budget::ResourceBudget;
+    use crate::command::TaskType;
+    use crate::executor::TaskExecutor;
+    use crate::model;
+    use crate::transaction::Compensation;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_graph_layers_and_order() {
+        let tasks = vec![
+            Task::new("a", TaskType::Analysis),
+            Task::new("b", TaskType::Analysis).with_dependencies(&["a"]),
+            Task::new("c", TaskType::Analysis),
+            Task::new("d", TaskType::Analysis).with_dependencies(&["b", "c"]),
+        ];
+        let graph = TaskGraph::build(&tasks).unwrap();
+
+        assert_eq!(graph.layers(), &[vec![0, 2], vec![1], vec![3]]);
+        assert_eq!(graph.order(), &[0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_graph_rejects_cycles_and_missing_dependencies() {
+        let cyclic = vec![
+            Task::new("a", TaskType::Analysis).with_dependencies(&["b"]),
+            Task::new("b", TaskType::Analysis).with_dependencies(&["a"]),
+            Task::new("c", TaskType::Analysis),
+        ];
+        let error = TaskGraph::build(&cyclic).unwrap_err().to_string();
+        assert!(error.contains("循環"));
+        assert!(error.contains("a, b"));
+
+        let missing = vec![Task::new("a", TaskType::Analysis).with_dependencies(&["zzz"])];
+        assert!(TaskGraph::build(&missing).unwrap_err().to_string().contains("zzz"));
+    }
+
+    /// 実行中のタスク数の最大値を記録する実行器
+    struct ConcurrencyProbe {
+        running: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    impl TaskExecutor for ConcurrencyProbe {
+        fn execute(&self, task: &Task, _context: &TaskContext) -> anyhow::Result<TaskOutcome> {
+            let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(running, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(50));
+            self.running.fetch_sub(1, Ordering::SeqCst);
+            Ok(TaskOutcome::output(task.id.clone()))
+        }
+    }
+
+    #[test]
+    fn test_parallel_runs_independent_tasks_concurrently() {
+        let probe = Arc::new(ConcurrencyProbe {
+            running: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+        });
+        let mut registry = ExecutorRegistry::new(model::default_model());
+        registry.register(TaskType::Analysis, probe.clone());
+
+        let mut tasks = vec![
+            Task::new("a", TaskType::Analysis),
+            Task::new("b", TaskType::Analysis),
+            Task::new("c", TaskType::Analysis),
+            Task::new("d", TaskType::Analysis).with_dependencies(&["a", "b", "c"]),
+        ];
+        let report = Scheduler::new(&registry)
+            .run(&mut tasks, &ExecutionStrategy::Parallel, Path::new("."), 0)
+            .unwrap();
+
+        assert_eq!(probe.peak.load(Ordering::SeqCst), 3);
+        assert_eq!(report.completed.last().unwrap().0, "d");
+        assert!(tasks.iter().all(|t| t.status == TaskStatus::Completed));
+    }
+
+    /// `mode` パラメータが `broken` のタスクを失敗させる実行器
+    struct ModeExecutor;
+
+    impl TaskExecutor for ModeExecutor {
+        fn execute(&self, task: &Task, _context: &TaskContext) -> anyhow::Result<TaskOutcome> {
+            if task.parameters.get("mode").map(String::as_str) == Some("broken") {
+                anyhow::bail!("壊れた手順です");
+            }
+            Ok(TaskOutcome::output(task.description.clone()))
+        }
+    }
+
+    /// 失敗したタスクを別の手順に置き換える再計画器
+    struct FixingReplanner {
+        errors: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl Replanner for FixingReplanner {
+        fn replan(&self, task: &Task, error: &str) -> anyhow::Result<Option<Task>> {
+            self.errors.lock().unwrap().push(error.to_string());
+            Ok(Some(Task {
+                id: "ignored".to_string(),
+                description: format!("{}（別の手順）", task.description),
+                parameters: HashMap::from([("mode".to_string(), "fixed".to_string())]),
+                ..task.clone()
+            }))
+        }
+    }
+
+    /// 失敗したタスクをシェルコマンドを実行する検証に置き換える再計画器
+    struct ShellReplanner;
+
+    impl Replanner for ShellReplanner {
+        fn replan(&self, task: &Task, _error: &str) -> anyhow::Result<Option<Task>> {
+            Ok(Some(Task {
+                description: "コマンドで直す".to_string(),
+                task_type: TaskType::Validation,
+                parameters: HashMap::from([("command".to_string(), "touch replanned.txt".to_string())]),
+                ..task.clone()
+            }))
+        }
+    }
+
+    #[test]
+    fn test_replanned_commands_need_approval() {
+        use crate::approval::{ApprovalPolicy, PolicyApprover};
+
+        let runs = Arc::new(TokenSpender {
+            calls: AtomicUsize::new(0),
+            tokens: 0,
+            undone: Default::default(),
+        });
+        let mut registry = ExecutorRegistry::new(model::default_model());
+        registry.register(TaskType::Analysis, Arc::new(ModeExecutor));
+        registry.register(TaskType::Validation, runs.clone());
+        let approver = PolicyApprover::new(ApprovalPolicy::AutoDeny);
+
+        let mut tasks = vec![Task::new("a", TaskType::Analysis)];
+        tasks[0].parameters.insert("mode".to_string(), "broken".to_string());
+        let report = Scheduler::new(&registry)
+            .with_replanner(&ShellReplanner)
+            .with_approver(&approver)
+            .run(&mut tasks, &ExecutionStrategy::Adaptive, Path::new("."), 0)
+            .unwrap();
+
+        // 再計画で現れたコマンドも承認者に諮られ、却下されれば実行されない
+        assert_eq!(runs.calls.load(Ordering::SeqCst), 0);
+        assert_eq!(tasks[0].status, TaskStatus::Failed);
+        assert_eq!(report.approvals.len(), 1);
+        assert!(report.approvals[0].0.details.starts_with("$ touch replanned.txt"));
+        assert!(report.records[0].error.as_deref().unwrap().contains("却下"));
+    }
+
+    #[test]
+    fn test_adaptive_replaces_failed_tasks() {
+        let mut registry = ExecutorRegistry::new(model::default_model());
+        registry.register(TaskType::Analysis, Arc::new(ModeExecutor));
+        let replanner = FixingReplanner {
+            errors: std::sync::Mutex::new(Vec::new()),
+        };
+        let plan = || {
+            let mut tasks = vec![
+                Task::new("a", TaskType::Analysis),
+                Task::new("b", TaskType::Analysis).with_dependencies(&["a"]),
+            ];
+            tasks[0].parameters.insert("mode".to_string(), "broken".to_string());
+            tasks
+        };
+
+        // 再計画しない戦略・再計画器がない場合は、同じタスクを再実行せず失敗とする
+        for (strategy, with_replanner) in [(ExecutionStrategy::Sequential, true), (ExecutionStrategy::Adaptive, false)] {
+            let mut tasks = plan();
+            let mut scheduler = Scheduler::new(&registry);
+            if with_replanner {
+                scheduler = scheduler.with_replanner(&replanner);
+            }
+            let report = scheduler.run(&mut tasks, &strategy, Path::new("."), 0).unwrap();
+            assert_eq!(tasks[0].status, TaskStatus::Failed);
+            assert_eq!(tasks[1].status, TaskStatus::Failed);
+            assert_eq!(report.records.len(), 2);
+        }
+        assert!(replanner.errors.lock().unwrap().is_empty());
+
+        let mut tasks = plan();
+        let report = Scheduler::new(&registry)
+            .with_replanner(&replanner)
+            .run(&mut tasks, &ExecutionStrategy::Adaptive, Path::new("."), 0)
+            .unwrap();
+
+        assert_eq!(replanner.errors.lock().unwrap().as_slice(), ["壊れた手順です"]);
+        assert_eq!(tasks[0].id, "a");
+        assert_eq!(tasks[0].description, "a（別の手順）");
+        assert_eq!(tasks[0].parameters["mode"], "fixed");
+        assert!(tasks.iter().all(|t| t.status == TaskStatus::Completed));
+        assert_eq!(report.records[0].attempts, 2);
+        assert_eq!(report.records[0].description, "a（別の手順）");
+        assert_eq!(report.completed[1].1.output, "b");
+    }
+
+    /// 呼び出し回数を数え、トークンを使ったと報告する実行器（取り消されたタスクを記録する補償操作を返す）
+    struct TokenSpender {
+        calls: AtomicUsize,
+        tokens: u64,
+        undone: Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    impl TaskExecutor for TokenSpender {
+        fn execute(&self, task: &Task, _context: &TaskContext) -> anyhow::Result<TaskOutcome> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let undone = self.undone.clone();
+            let id = task.id.clone();
+            Ok(TaskOutcome {
+                compensations: vec![Compensation::custom(format!("{} を取り消す", id), move || {
+                    undone.lock().unwrap().push(id.clone());
+                    Ok(())
+                })],
+                usage: ResourceUsage {
+                    tokens: self.tokens,
+                    ..ResourceUsage::default()
+                },
+                ..TaskOutcome::output(task.id.clone())
+            })
+        }
+    }
+
+    #[test]
+    fn test_task_budgets_are_enforced() {
+        let spender = Arc::new(TokenSpender {
+            calls: AtomicUsize::new(0),
+            tokens: 100,
+            undone: Default::default(),
+        });
+        let mut registry = ExecutorRegistry::new(model::default_model());
+        registry.register(TaskType::Analysis, spender.clone());
+
+        let mut tasks = vec![
+            Task::new("refused", TaskType::Analysis),
+            Task::new("over", TaskType::Analysis),
+            Task::new("within", TaskType::Analysis),
+        ];
+        tasks[0].budget = ResourceBudget::unlimited().with_tokens(0);
+        tasks[1].budget = ResourceBudget::unlimited().with_tokens(50);
+        tasks[2].budget = ResourceBudget::unlimited().with_tokens(100);
+        let transaction = Transaction::new();
+        let report = Scheduler::new(&registry)
+            .with_transaction(&transaction)
+            .run(&mut tasks, &ExecutionStrategy::Adaptive, Path::new("."), 0)
+            .unwrap();
+
+        // 予算を超えたタスクの副作用はその場で取り消し、予算内のタスクの補償だけを登録する
+        assert_eq!(*spender.undone.lock().unwrap(), vec!["over".to_string()]);
+        assert_eq!(transaction.len(), 1);
+
+        // 予算のないタスクは実行せず、予算を超えたタスクは再試行しない
+        assert_eq!(spender.calls.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            tasks.iter().map(|t| t.status.clone()).collect::<Vec<_>>(),
+            vec![TaskStatus::Failed, TaskStatus::Failed, TaskStatus::Completed]
+        );
+
+        let record = |id: &str| report.records.iter().find(|r| r.task_id == id).unwrap();
+        assert_eq!(record("refused").attempts, 0);
+        assert!(record("over").error.as_deref().unwrap().contains("トークン 100 > 50"));
+        assert_eq!(record("over").usage.tokens, 100);
+        assert_eq!(record("within").budget.tokens, Some(100));
+    }
+
+    /// `delay_ms` パラメータの時間だけ待ってからファイルを書き込み、書き込みを取り消す補償操作を返す実行器
+    struct SlowWriter;
+
+    impl TaskExecutor for SlowWriter {
+        fn execute(&self, task: &Task, context: &TaskContext) -> anyhow::Result<TaskOutcome> {
+            let delay = task.parameters.get("delay_ms").map_or(Ok(0), |ms| ms.parse())?;
+            std::thread::sleep(Duration::from_millis(delay));
+            let path = context.working_directory.join(format!("{}.txt", task.id));
+            std::fs::write(&path, &task.id)?;
+            Ok(TaskOutcome {
+                compensations: vec![Compensation::RestoreFile {
+                    path,
+                    previous: None,
+                    created_dirs: vec![],
+                }],
+                ..TaskOutcome::output(task.id.clone())
+            })
+        }
+    }
+
+    #[test]
+    fn test_wall_time_limit_is_enforced_for_every_task() {
+        let dir = std::env::temp_dir().join(format!("seize-scheduler-wall-time-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut registry = ExecutorRegistry::new(model::default_model());
+        registry.register(TaskType::Analysis, Arc::new(SlowWriter));
+
+        let mut tasks = vec![
+            Task::new("slow", TaskType::Analysis),
+            Task::new("unlimited", TaskType::Analysis),
+        ];
+        tasks[0].parameters.insert("delay_ms".to_string(), "500".to_string());
+        tasks[0].budget = ResourceBudget::unlimited().with_wall_time(Duration::from_millis(50));
+        let transaction = Transaction::new();
+        let started = Instant::now();
+        let report = Scheduler::new(&registry)
+            .with_transaction(&transaction)
+            .run(&mut tasks, &ExecutionStrategy::Adaptive, &dir, 0)
+            .unwrap();
+
+        // 上限を過ぎたタスクは完了を待たずに失敗とし（呼び出し元も待たせない）、再試行しない
+        assert!(started.elapsed() < Duration::from_millis(400), "{:?}", started.elapsed());
+        let record = |id: &str| report.records.iter().find(|r| r.task_id == id).unwrap();
+        assert!(record("slow").error.as_deref().unwrap().contains("50ms"));
+        assert_eq!(record("slow").attempts, 1);
+        assert_eq!(tasks[0].status, TaskStatus::Failed);
+        assert_eq!(tasks[1].status, TaskStatus::Completed);
+        assert_eq!(transaction.len(), 1);
+        assert!(dir.join("unlimited.txt").exists());
+
+        // 打ち切った後に完了したタスクの書き込みは実行したスレッドが取り消す
+        std::thread::sleep(Duration::from_millis(500).saturating_sub(started.elapsed()) + Duration::from_millis(200));
+        assert!(!dir.join("slow.txt").exists());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
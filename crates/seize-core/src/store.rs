@@ -0,0 +1,194 @@
+//! # 世界ストア (World Store)
+//!
+//! 瞬くたびに変わる世界を、バージョンごとのスナップショットとして保存する。
+//!
+//! ```text
+//! .seize/
+//! ├── HEAD              # 現在のバージョン番号
+//! └── worlds/
+//!     ├── 000000.json   # World v0
+//!     └── 000003.json   # World v3
+//! ```
+
+use crate::world::World;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 世界ストア
+pub struct WorldStore {
+    root: PathBuf,
+}
+
+/// ストアのログの1行
+#[derive(Debug, Clone)]
+pub struct WorldLogEntry {
+    pub version: usize,
+    /// 派生元のバージョン
+    pub parent: Option<usize>,
+    pub learnings: usize,
+    pub decisions: usize,
+    pub history: usize,
+    /// HEAD が指しているか
+    pub is_head: bool,
+}
+
+impl WorldStore {
+    /// 既定のストアディレクトリ
+    pub const DEFAULT_DIR: &'static str = ".seize";
+
+    /// 派生元バージョンを記録するメタデータのキー
+    pub const PARENT_KEY: &'static str = "parent_version";
+
+    /// ストアを開く（存在しなければ作成する）
+    pub fn open(root: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(root.join("worlds"))
+            .map_err(|e| anyhow::anyhow!("世界ストアを作成できません ({}): {}", root.display(), e))?;
+        Ok(Self { root })
+    }
+
+    /// ストアのディレクトリ
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn snapshot_path(&self, version: usize) -> PathBuf {
+        self.root.join("worlds").join(format!("{:06}.json", version))
+    }
+
+    fn head_path(&self) -> PathBuf {
+        self.root.join("HEAD")
+    }
+
+    /// 保存されているバージョン（昇順）
+    pub fn versions(&self) -> anyhow::Result<Vec<usize>> {
+        let mut versions = Vec::new();
+        for entry in fs::read_dir(self.root.join("worlds"))? {
+            let name = entry?.file_name();
+            let name = name.to_string_lossy();
+            if let Some(version) = name.strip_suffix(".json").and_then(|v| v.parse().ok()) {
+                versions.push(version);
+            }
+        }
+        versions.sort_unstable();
+        Ok(versions)
+    }
+
+    /// HEAD が指すバージョン
+    pub fn head(&self) -> anyhow::Result<Option<usize>> {
+        match fs::read_to_string(self.head_path()) {
+            Ok(head) => Ok(Some(head.trim().parse().map_err(|e| {
+                anyhow::anyhow!("HEAD の内容が不正です ({:?}): {}", head.trim(), e)
+            })?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// 指定したバージョンの世界を読み込む
+    pub fn load(&self, version: usize) -> anyhow::Result<World> {
+        let path = self.snapshot_path(version);
+        let json = fs::read_to_string(&path)
+            .map_err(|e| anyhow::anyhow!("World v{} を読み込めません ({}): {}", version, path.display(), e))?;
+        serde_json::from_str(&json)
+            .map_err(|e| anyhow::anyhow!("World v{} の形式が不正です: {}", version, e))
+    }
+
+    /// HEAD の世界を読み込む（空のストアなら `None`）
+    pub fn load_head(&self) -> anyhow::Result<Option<World>> {
+        self.head()?.map(|version| self.load(version)).transpose()
+    }
+
+    /// 世界を保存し、HEAD を進める
+    ///
+    /// 過去のバージョンをチェックアウトして進めた世界が既存のバージョンと衝突する場合は、
+    /// 最新バージョンの次の番号に採番し直す。派生元は `metadata["parent_version"]` に記録する。
+    /// 保存したバージョンを返す。
+    pub fn save(&self, world: &mut World) -> anyhow::Result<usize> {
+        if let Some(head) = self.head()? {
+            world.metadata.insert(Self::PARENT_KEY.to_string(), head.to_string());
+        }
+
+        if let Some(&latest) = self.versions()?.last() {
+            if world.version <= latest && self.snapshot_path(world.version).exists() {
+                world.version = latest + 1;
+            }
+        }
+
+        let path = self.snapshot_path(world.version);
+        write_atomic(&path, &serde_json::to_string_pretty(world)?)?;
+        write_atomic(&self.head_path(), &world.version.to_string())?;
+
+        Ok(world.version)
+    }
+
+    /// 指定したバージョンに HEAD を移動し、その世界を返す
+    pub fn checkout(&self, version: usize) -> anyhow::Result<World> {
+        let world = self.load(version)?;
+        write_atomic(&self.head_path(), &version.to_string())?;
+        Ok(world)
+    }
+
+    /// 保存されている世界の一覧（新しい順）
+    pub fn log(&self) -> anyhow::Result<Vec<WorldLogEntry>> {
+        let head = self.head()?;
+        let mut entries = Vec::new();
+
+        for version in self.versions()?.into_iter().rev() {
+            let world = self.load(version)?;
+            entries.push(WorldLogEntry {
+                version,
+                parent: world.metadata.get(Self::PARENT_KEY).and_then(|p| p.parse().ok()),
+                learnings: world.knowledge.learnings.len(),
+                decisions: world.knowledge.decisions.len(),
+                history: world.context.history.len(),
+                is_head: head == Some(version),
+            });
+        }
+
+        Ok(entries)
+    }
+}
+
+/// 一時ファイルに書いてからリネームする
+fn write_atomic(path: &Path, content: &str) -> anyhow::Result<()> {
+    let temp = path.with_extension("tmp");
+    fs::write(&temp, content)?;
+    fs::rename(&temp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_log_and_checkout() {
+        let dir = std::env::temp_dir().join(format!("seize-store-{}", std::process::id()));
+        fs::remove_dir_all(&dir).ok();
+        let store = WorldStore::open(&dir).unwrap();
+        assert!(store.load_head().unwrap().is_none());
+
+        let mut world = World::new();
+        assert_eq!(store.save(&mut world).unwrap(), 0);
+
+        world.advance();
+        world.context.history.push("Executed: task_0".to_string());
+        assert_eq!(store.save(&mut world).unwrap(), 1);
+        assert_eq!(store.head().unwrap(), Some(1));
+
+        // 過去のバージョンから進めた世界は新しい番号で保存される
+        let mut branched = store.checkout(0).unwrap();
+        assert_eq!(store.head().unwrap(), Some(0));
+        branched.advance();
+        assert_eq!(store.save(&mut branched).unwrap(), 2);
+
+        let log = store.log().unwrap();
+        fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(log.iter().map(|e| e.version).collect::<Vec<_>>(), vec![2, 1, 0]);
+        assert!(log[0].is_head);
+        assert_eq!(log[0].parent, Some(0));
+        assert_eq!(log[1].history, 1);
+    }
+}
@@ -0,0 +1,179 @@
+//! # トランザクション (Transaction)
+//!
+//! Θ の適用を1つのトランザクションとして扱う。
+//! 実行器やフェーズは外部への副作用（ファイルの書き込みなど）を取り消す補償操作を登録し、
+//! いずれかのフェーズが失敗したときは登録と逆の順に補償操作を実行して副作用を取り消す。
+
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// 任意の取り消し処理
+pub type Undo = Arc<dyn Fn() -> anyhow::Result<()> + Send + Sync>;
+
+/// 補償操作
+#[derive(Clone)]
+pub enum Compensation {
+    /// ファイルを書き込む前の内容に戻す（書き込む前になかったファイルは削除する）
+    ///
+    /// `created_dirs` は書き込みのために新しく作ったディレクトリ（深い順）で、空になっていれば削除する
+    RestoreFile {
+        path: PathBuf,
+        previous: Option<Vec<u8>>,
+        created_dirs: Vec<PathBuf>,
+    },
+    /// 任意の取り消し処理
+    Custom { description: String, undo: Undo },
+}
+
+impl Compensation {
+    /// 任意の取り消し処理から補償操作を作成
+    pub fn custom(
+        description: impl Into<String>,
+        undo: impl Fn() -> anyhow::Result<()> + Send + Sync + 'static,
+    ) -> Self {
+        Compensation::Custom {
+            description: description.into(),
+            undo: Arc::new(undo),
+        }
+    }
+
+    /// 補償操作の説明
+    pub fn description(&self) -> String {
+        match self {
+            Compensation::RestoreFile { path, previous: Some(_), .. } => format!("{} を元の内容に戻す", path.display()),
+            Compensation::RestoreFile { path, previous: None, .. } => format!("{} を削除する", path.display()),
+            Compensation::Custom { description, .. } => description.clone(),
+        }
+    }
+
+    /// 補償操作を実行
+    pub fn run(&self) -> anyhow::Result<()> {
+        match self {
+            Compensation::RestoreFile { path, previous: Some(content), .. } => fs::write(path, content)
+                .map_err(|e| anyhow::anyhow!("ファイルを元に戻せません ({}): {}", path.display(), e)),
+            Compensation::RestoreFile { path, previous: None, created_dirs } => {
+                match fs::remove_file(path) {
+                    Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                        anyhow::bail!("ファイルを削除できません ({}): {}", path.display(), e)
+                    }
+                    _ => {}
+                }
+                // 他のファイルが残っているディレクトリは消さない
+                for dir in created_dirs {
+                    if fs::remove_dir(dir).is_err() {
+                        break;
+                    }
+                }
+                Ok(())
+            }
+            Compensation::Custom { undo, .. } => undo(),
+        }
+    }
+}
+
+impl fmt::Debug for Compensation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Compensation({})", self.description())
+    }
+}
+
+/// 補償操作を登録順に保持するトランザクション
+#[derive(Debug, Default)]
+pub struct Transaction {
+    compensations: Mutex<Vec<Compensation>>,
+}
+
+impl Transaction {
+    /// 新しいトランザクションを開始
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 補償操作を登録
+    pub fn register(&self, compensation: Compensation) {
+        self.lock().push(compensation);
+    }
+
+    /// 登録されている補償操作の数
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// 補償操作が登録されていないか
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 登録と逆の順に補償操作を実行し、失敗した補償操作の説明とエラーを返す
+    ///
+    /// 1つが失敗しても残りの補償操作は実行する
+    pub fn rollback(self) -> Vec<String> {
+        let compensations = self.compensations.into_inner().unwrap_or_else(|e| e.into_inner());
+        compensations
+            .iter()
+            .rev()
+            .filter_map(|compensation| {
+                tracing::info!("↩ {}", compensation.description());
+                compensation
+                    .run()
+                    .err()
+                    .map(|e| format!("{}: {}", compensation.description(), e))
+            })
+            .collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Compensation>> {
+        self.compensations.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_rollback_runs_in_reverse_order() {
+        let dir = std::env::temp_dir().join(format!("seize-transaction-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let existing = dir.join("existing.txt");
+        let created = dir.join("new/deep/created.txt");
+        fs::write(&existing, "before").unwrap();
+
+        let transaction = Transaction::new();
+        transaction.register(Compensation::RestoreFile {
+            path: existing.clone(),
+            previous: Some(b"before".to_vec()),
+            created_dirs: vec![],
+        });
+        fs::write(&existing, "after").unwrap();
+        transaction.register(Compensation::RestoreFile {
+            path: created.clone(),
+            previous: None,
+            created_dirs: vec![dir.join("new/deep"), dir.join("new")],
+        });
+        fs::create_dir_all(created.parent().unwrap()).unwrap();
+        fs::write(&created, "new").unwrap();
+
+        let order = Arc::new(AtomicUsize::new(0));
+        let seen = order.clone();
+        transaction.register(Compensation::custom("最初に取り消す", move || {
+            seen.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }));
+        transaction.register(Compensation::custom("失敗する", || anyhow::bail!("取り消せません")));
+        assert_eq!(transaction.len(), 4);
+
+        let failures = transaction.rollback();
+        let restored = fs::read_to_string(&existing).unwrap();
+        let removed = !dir.join("new").exists();
+        fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(failures, vec!["失敗する: 取り消せません".to_string()]);
+        assert_eq!(order.load(Ordering::SeqCst), 1);
+        assert_eq!(restored, "before");
+        assert!(removed);
+    }
+}
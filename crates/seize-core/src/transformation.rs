@@ -3,12 +3,28 @@
 //! 6つの変換フェーズによる世界状態の更新
 //! Θ = θ₆ ◦ θ₅ ◦ θ₄ ◦ θ₃ ◦ θ₂ ◦ θ₁
 
-use crate::command::ExecutionPlan;
-use crate::world::{World, Decision, Learning};
+use crate::approval::{
+    self, ApprovalDecision, ApprovalKind, ApprovalPolicy, ApprovalRequest, Approver, PolicyApprover,
+};
+use crate::budget::ResourceUsage;
+use crate::charter::{CharterEngine, CharterSubject, RuleScope};
+use crate::clock::{Clock, SystemClock};
+use crate::command::{ExecutionPlan, ModelReplanner, TaskStatus, TaskType};
+use crate::error::{SeizeError, SeizeResult};
+use crate::executor::{ExecutorRegistry, TaskExecutor};
+use crate::learning::{LearningModel, Observation};
+use crate::lexicon::Lexicon;
+use crate::model::{self, LanguageModel};
+use crate::phase::{PhaseContext, PhasePipeline};
+use crate::scheduler::Scheduler;
+use crate::transaction::Transaction;
+use crate::world::{World, Decision, DecisionStatus, Learning, TaskRecord};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
 
 /// 変換フェーズ
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TransformationPhase {
     /// θ₁: Understand - 理解
     Understand,
@@ -22,10 +38,27 @@ pub enum TransformationPhase {
     Integrate,
     /// θ₆: Learn - 学習
     Learn,
+    /// 利用側が加えた独自のフェーズ
+    Custom(String),
+}
+
+impl TransformationPhase {
+    /// ログに表示する名前
+    pub fn label(&self) -> String {
+        match self {
+            TransformationPhase::Understand => "θ₁ (Understand)".to_string(),
+            TransformationPhase::Generate => "θ₂ (Generate)".to_string(),
+            TransformationPhase::Allocate => "θ₃ (Allocate)".to_string(),
+            TransformationPhase::Execute => "θ₄ (Execute)".to_string(),
+            TransformationPhase::Integrate => "θ₅ (Integrate)".to_string(),
+            TransformationPhase::Learn => "θ₆ (Learn)".to_string(),
+            TransformationPhase::Custom(name) => name.clone(),
+        }
+    }
 }
 
 /// 変換結果
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransformationResult {
     pub phase: TransformationPhase,
     pub success: bool,
@@ -33,16 +66,122 @@ pub struct TransformationResult {
     pub artifacts: Vec<String>,
 }
 
+/// Θ の適用に失敗し、世界を適用前に戻した結果
+#[derive(Debug)]
+pub struct RolledBack {
+    /// 失敗したフェーズ
+    pub phase: TransformationPhase,
+
+    /// フェーズが返したエラー
+    pub error: SeizeError,
+
+    /// 適用前の世界（失敗を学習として記録したもの）
+    pub world: World,
+
+    /// 取り消すまでに実行したタスクの記録（コストの集計に使う）
+    pub executed: Vec<TaskRecord>,
+}
+
 /// 世界変換器
 pub struct WorldTransformer {
     /// 憲章に基づく検証
     enforce_charter: bool,
+
+    /// 憲章に基づく検証器
+    charter: Arc<CharterEngine>,
+
+    /// タスクタイプごとの実行器
+    executors: ExecutorRegistry,
+
+    /// 意思決定・破壊的なタスクの承認者
+    approver: Arc<dyn Approver>,
+
+    /// 学習・意思決定の記録に付ける時刻の取得元
+    clock: Arc<dyn Clock>,
+
+    /// 変換フェーズの並び
+    pipeline: PhasePipeline,
+
+    /// θ₆ で学習を更新する信頼度モデル
+    learning: LearningModel,
+
+    /// θ₁ で知識を検索するときに埋め込みを求める言語モデル
+    model: Arc<dyn LanguageModel>,
+
+    /// θ₁ でタスクに添える関連知識の件数（0 なら添えない）
+    recall: usize,
 }
 
 impl WorldTransformer {
     /// 新しい世界変換器を作成
     pub fn new(enforce_charter: bool) -> Self {
-        Self { enforce_charter }
+        Self {
+            enforce_charter,
+            charter: Arc::new(CharterEngine::default()),
+            executors: ExecutorRegistry::new(model::default_model()),
+            approver: Arc::new(PolicyApprover::new(ApprovalPolicy::AutoDeny)),
+            clock: Arc::new(SystemClock),
+            pipeline: PhasePipeline::standard(),
+            learning: LearningModel::default(),
+            model: model::default_model(),
+            recall: 5,
+        }
+    }
+
+    /// 言語モデルを設定（モデルを使う実行器を置き換える）
+    pub fn with_model(mut self, model: Arc<dyn LanguageModel>) -> Self {
+        self.executors.register_model(model.clone());
+        self.model = model;
+        self
+    }
+
+    /// タスクタイプの実行器を設定
+    pub fn with_executor(mut self, task_type: TaskType, executor: Arc<dyn TaskExecutor>) -> Self {
+        self.executors.register(task_type, executor);
+        self
+    }
+
+    /// 承認者を設定（既定はすべて自動却下。自動承認は `ApprovalPolicy::AutoApprove` で明示する）
+    pub fn with_approver(mut self, approver: Arc<dyn Approver>) -> Self {
+        self.approver = approver;
+        self
+    }
+
+    /// 検証に使う憲章を設定
+    pub fn with_charter(mut self, charter: Arc<CharterEngine>) -> Self {
+        self.charter = charter;
+        self
+    }
+
+    /// 時計を設定
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// 変換フェーズの並びを設定（既定は θ₁〜θ₆）
+    pub fn with_pipeline(mut self, pipeline: PhasePipeline) -> Self {
+        self.pipeline = pipeline;
+        self
+    }
+
+    /// θ₁ でタスクに添える関連知識の件数を設定（既定は5件、0 なら添えない）
+    pub fn with_recall(mut self, recall: usize) -> Self {
+        self.recall = recall;
+        self
+    }
+
+    /// θ₆ で使う学習の信頼度モデルを設定
+    pub fn with_learning_model(mut self, learning: LearningModel) -> Self {
+        self.learning = learning;
+        self
+    }
+
+    /// 承認者に審査を求める
+    fn review(&self, request: &ApprovalRequest) -> SeizeResult<ApprovalDecision> {
+        self.approver
+            .review(request)
+            .map_err(|e| SeizeError::Approval(format!("{:#}", e)))
     }
 
     /// 実行計画を適用して世界を変換
@@ -54,55 +193,197 @@ impl WorldTransformer {
     /// 4. θ₄: Execute - タスクを実行
     /// 5. θ₅: Integrate - 結果を統合
     /// 6. θ₆: Learn - 学習し次に活かす
-    pub fn apply(&self, plan: ExecutionPlan, mut world: World) -> anyhow::Result<World> {
-        // θ₁: Understand
-        let understand_result = self.theta1_understand(&plan, &world)?;
-        tracing::info!("θ₁ (Understand): {}", understand_result.message);
+    ///
+    /// フェーズの並びは `with_pipeline` で組み替えられる
+    pub fn apply(&self, plan: ExecutionPlan, world: World) -> SeizeResult<World> {
+        self.apply_observed(plan, world, &mut |_| {})
+    }
 
-        // θ₂: Generate
-        let generate_result = self.theta2_generate(&plan, &world)?;
-        tracing::info!("θ₂ (Generate): {}", generate_result.message);
+    /// 実行計画を適用して世界を変換し、各フェーズの結果を `on_phase` に渡す
+    ///
+    /// フェーズが失敗した場合は補償操作で外部への副作用を取り消したうえで、そのエラーを返す
+    pub fn apply_observed(
+        &self,
+        plan: ExecutionPlan,
+        world: World,
+        on_phase: &mut dyn FnMut(&TransformationResult),
+    ) -> SeizeResult<World> {
+        self.apply_transaction(plan, world, on_phase)
+            .map_err(|rolled_back| rolled_back.error)
+    }
 
-        // θ₃: Allocate
-        let allocate_result = self.theta3_allocate(&plan, &world)?;
-        tracing::info!("θ₃ (Allocate): {}", allocate_result.message);
+    /// 実行計画を1つのトランザクションとして適用する
+    ///
+    /// 各フェーズは世界の作業用の複製を変更し、実行器・フェーズは外部への副作用を
+    /// 取り消す補償操作を登録する。いずれかのフェーズが失敗した場合は、
+    /// 補償操作を登録と逆の順に実行し、適用前の世界に失敗を学習として記録して返す
+    pub fn apply_transaction(
+        &self,
+        mut plan: ExecutionPlan,
+        world: World,
+        on_phase: &mut dyn FnMut(&TransformationResult),
+    ) -> Result<World, Box<RolledBack>> {
+        let transaction = Transaction::new();
+        let mut staged = world.clone();
+        let mut results: Vec<TransformationResult> = Vec::new();
 
-        // θ₄: Execute
-        let execute_result = self.theta4_execute(&plan, &mut world)?;
-        tracing::info!("θ₄ (Execute): {}", execute_result.message);
+        for stage in self.pipeline.stages() {
+            if stage.skips(&plan) {
+                tracing::info!("{}: この計画では実行しません", stage.phase.label());
+                continue;
+            }
 
-        // θ₅: Integrate
-        let integrate_result = self.theta5_integrate(&plan, &mut world, &execute_result)?;
-        tracing::info!("θ₅ (Integrate): {}", integrate_result.message);
+            let outcome = match &stage.implementation {
+                Some(phase) => phase.run(PhaseContext {
+                    plan: &mut plan,
+                    world: &mut staged,
+                    results: &results,
+                    transaction: &transaction,
+                }),
+                None => self.run_builtin(&stage.phase, &mut plan, &mut staged, &results, &transaction),
+            };
+            let result = match outcome {
+                Ok(result) => result,
+                Err(error) => {
+                    let executed = staged.context.task_records.split_off(
+                        world.context.task_records.len().min(staged.context.task_records.len()),
+                    );
+                    let mut rolled_back = self.roll_back(world, &plan, stage.phase.clone(), error, transaction);
+                    rolled_back.executed = executed;
+                    return Err(Box::new(rolled_back));
+                }
+            };
 
-        // θ₆: Learn
-        let learn_result = self.theta6_learn(&plan, &mut world, &integrate_result)?;
-        tracing::info!("θ₆ (Learn): {}", learn_result.message);
+            tracing::info!("{}: {}", stage.phase.label(), result.message);
+            on_phase(&result);
+            results.push(result);
+        }
 
         // 世界を進める（瞬く）
-        world.advance();
+        staged.advance();
+
+        Ok(staged)
+    }
+
+    /// 補償操作を実行し、適用前の世界に失敗を記録する
+    fn roll_back(
+        &self,
+        mut world: World,
+        plan: &ExecutionPlan,
+        phase: TransformationPhase,
+        error: SeizeError,
+        transaction: Transaction,
+    ) -> RolledBack {
+        tracing::warn!("{} が失敗したため World v{} に戻します: {}", phase.label(), world.version, error);
+
+        let failures = transaction.rollback();
+        for failure in &failures {
+            tracing::warn!("補償操作に失敗しました: {}", failure);
+        }
+
+        let mut content = format!(
+            "Goal: {} -> Rolled back: {} failed ({})",
+            plan.goal.description,
+            phase.label(),
+            error
+        );
+        if !failures.is_empty() {
+            content.push_str(&format!("; 取り消せなかった操作: {}", failures.join(", ")));
+        }
+        world.context.history.push(format!("RolledBack: {} ({})", phase.label(), error));
+        world.record_learning(Learning {
+            timestamp: self.clock.now(),
+            content,
+            source: "Rollback".to_string(),
+            confidence: 1.0,
+            support: 0.0,
+            refute: 0.0,
+        });
 
-        Ok(world)
+        RolledBack {
+            phase,
+            error,
+            world,
+            executed: Vec::new(),
+        }
+    }
+
+    /// 組み込みのフェーズを実行
+    fn run_builtin(
+        &self,
+        phase: &TransformationPhase,
+        plan: &mut ExecutionPlan,
+        world: &mut World,
+        results: &[TransformationResult],
+        transaction: &Transaction,
+    ) -> SeizeResult<TransformationResult> {
+        match phase {
+            TransformationPhase::Understand => self.theta1_understand(plan, world),
+            TransformationPhase::Generate => self.theta2_generate(plan, world),
+            TransformationPhase::Allocate => self.theta3_allocate(plan, world),
+            TransformationPhase::Execute => self.theta4_execute(plan, world, transaction),
+            TransformationPhase::Integrate => self.theta5_integrate(plan, world, results),
+            TransformationPhase::Learn => self.theta6_learn(plan, world, results),
+            TransformationPhase::Custom(name) => Err(SeizeError::InvalidPipeline(format!(
+                "独自のフェーズ {} に実装がありません",
+                name
+            ))),
+        }
     }
 
     /// θ₁: Understand - 現在の世界状態を理解
+    ///
+    /// 憲章に基づく検証が有効なら、世界の原則を憲章に揃え、
+    /// 目標とタスクを憲章のルールで検証する
+    ///
+    /// 目標に関係する過去の学習・意思決定を知識ベースから探し、
+    /// 言語モデルを使うタスクに想起した知見として持たせる（プロンプト自体は変えない）
     fn theta1_understand(
         &self,
-        plan: &ExecutionPlan,
-        world: &World,
-    ) -> anyhow::Result<TransformationResult> {
+        plan: &mut ExecutionPlan,
+        world: &mut World,
+    ) -> SeizeResult<TransformationResult> {
+        if self.enforce_charter {
+            world.knowledge.principles = self.charter.charter().principles.clone();
+
+            let texts = std::iter::once(&plan.goal.description).chain(plan.tasks.iter().map(|t| &t.description));
+            for text in texts {
+                let subject = CharterSubject::new(RuleScope::Plan, text)
+                    .with_goal_category(&plan.goal.category)
+                    .with_world(world);
+                self.charter.enforce(&subject)?;
+            }
+        }
+
+        let query = format!("{} {}", plan.goal.description, plan.goal.essential_question);
+        let related: Vec<String> = world
+            .knowledge
+            .search_with(&query, self.recall, self.model.as_ref())
+            .iter()
+            .map(|hit| hit.knowledge.summary())
+            .collect();
+        if !related.is_empty() {
+            let uses_model = |t: &TaskType| matches!(t, TaskType::Analysis | TaskType::CodeGeneration | TaskType::Decision);
+            for task in plan.tasks.iter_mut().filter(|t| uses_model(&t.task_type)) {
+                task.recalled = related.clone();
+            }
+        }
+
         let context = format!(
-            "World Version: {}, Tasks: {}, Knowledge: {} principles",
+            "World Version: {}, Tasks: {}, Knowledge: {} principles, {} related",
             world.version,
             plan.tasks.len(),
-            world.knowledge.principles.len()
+            world.knowledge.principles.len(),
+            related.len()
         );
 
+        let mut artifacts = vec![context.clone()];
+        artifacts.extend(related);
         Ok(TransformationResult {
             phase: TransformationPhase::Understand,
             success: true,
             message: format!("世界状態を理解しました: {}", context),
-            artifacts: vec![context],
+            artifacts,
         })
     }
 
@@ -111,11 +392,11 @@ impl WorldTransformer {
         &self,
         plan: &ExecutionPlan,
         _world: &World,
-    ) -> anyhow::Result<TransformationResult> {
+    ) -> SeizeResult<TransformationResult> {
         let mut artifacts = Vec::new();
 
         for task in &plan.tasks {
-            artifacts.push(format!("Task {}: {}", task.id, task.prompt));
+            artifacts.push(format!("Task {}: {}", task.id, task.model_prompt()));
         }
 
         Ok(TransformationResult {
@@ -127,63 +408,162 @@ impl WorldTransformer {
     }
 
     /// θ₃: Allocate - リソースを配分
+    ///
+    /// 実行戦略を決め、計画全体の予算をタスクごとに配分する
     fn theta3_allocate(
         &self,
-        plan: &ExecutionPlan,
+        plan: &mut ExecutionPlan,
         _world: &World,
-    ) -> anyhow::Result<TransformationResult> {
+    ) -> SeizeResult<TransformationResult> {
         let strategy_desc = match plan.strategy {
             crate::command::ExecutionStrategy::Sequential => "逐次実行",
             crate::command::ExecutionStrategy::Parallel => "並列実行",
             crate::command::ExecutionStrategy::Adaptive => "適応的実行",
         };
 
+        let mut artifacts = vec![strategy_desc.to_string()];
+        if !plan.budget.is_unlimited() {
+            let budgets = plan.budget.allocate(&plan.tasks);
+            for (task, budget) in plan.tasks.iter_mut().zip(budgets) {
+                task.budget = budget;
+                artifacts.push(format!("{}: {}", task.id, task.budget.describe()));
+            }
+        }
+
         Ok(TransformationResult {
             phase: TransformationPhase::Allocate,
             success: true,
-            message: format!("実行戦略を決定: {}", strategy_desc),
-            artifacts: vec![strategy_desc.to_string()],
+            message: format!("実行戦略を決定: {}（予算: {}）", strategy_desc, plan.budget.describe()),
+            artifacts,
         })
     }
 
     /// θ₄: Execute - タスクを実行
+    ///
+    /// 憲章に基づく検証が有効なら、まず計画の実行を意思決定として承認者に諮る。
+    /// 承認されれば依存関係と実行戦略に従ってタスクを実行器に渡し、
+    /// 状態を InProgress → Completed / Failed と更新する。
+    /// 承認の判断（却下・判断待ちを含む）はすべて意思決定として記録する。
+    /// ファイル書き込みは作業ディレクトリに触れず世界のファイルシステムにだけ反映し、
+    /// 検証は書き込みを反映した作業ディレクトリの写しで実行する
+    /// （作業ディレクトリへは実行後に `WorldDiff::apply` でまとめて書き戻す）。
+    /// 完了したタスクの補償操作は `transaction` に登録する。
+    /// タスクの使用量の合計が計画全体の予算を超えたら `BudgetExceeded` を返す
     fn theta4_execute(
         &self,
-        plan: &ExecutionPlan,
+        plan: &mut ExecutionPlan,
         world: &mut World,
-    ) -> anyhow::Result<TransformationResult> {
-        let mut executed_tasks = Vec::new();
-
-        for task in &plan.tasks {
-            // 実際のタスク実行はここで行われる
-            // （簡易実装: タスクの説明を履歴に記録）
-            world.context.history.push(format!(
-                "Executed: {} - {}",
-                task.id, task.description
-            ));
-            executed_tasks.push(task.id.clone());
-        }
-
-        // 憲章に基づく意思決定の記録
+        transaction: &Transaction,
+    ) -> SeizeResult<TransformationResult> {
+        let messages = &Lexicon::builtin(plan.goal.language).decisions;
         if self.enforce_charter {
-            let decision = Decision {
-                timestamp: chrono::Utc::now().to_rfc3339(),
+            let options: Vec<String> = plan.tasks.iter().map(|t| t.description.clone()).collect();
+            let request = ApprovalRequest::new(ApprovalKind::Plan, plan.goal.description.clone(), options.join("\n"));
+            let (approver, status, rejection_reason) = approval_fields(self.review(&request)?);
+            let refusal = rejection_reason.clone();
+
+            world.record_decision(Decision {
+                timestamp: self.clock.now(),
                 purpose: plan.goal.description.clone(),
                 input: plan.goal.essential_question.clone(),
-                options: plan.tasks.iter().map(|t| t.description.clone()).collect(),
-                rationale: "統一エージェント方程式に基づく自動実行".to_string(),
-                approver: "System".to_string(),
+                options,
+                rationale: messages.plan_rationale.clone(),
                 impact_scope: format!("{} tasks", plan.tasks.len()),
                 alternatives: vec![],
-                revocation_conditions: vec!["エラー発生時".to_string()],
-            };
-            world.record_decision(decision);
+                revocation_conditions: vec![messages.plan_revocation.clone()],
+                approver,
+                status,
+                rejection_reason,
+            });
+
+            if let Some(reason) = refusal {
+                world.context.history.push(format!("Rejected: {} ({})", plan.goal.description, reason));
+                let message = format!("計画は承認されませんでした: {}", approval::describe_reason(&reason));
+
+                return Ok(TransformationResult {
+                    phase: TransformationPhase::Execute,
+                    success: false,
+                    message,
+                    artifacts: vec![],
+                });
+            }
+        }
+
+        let working_directory = PathBuf::from(&world.context.working_directory);
+        let replanner = ModelReplanner::new(self.model.clone(), plan.goal.clone());
+        let report = Scheduler::new(&self.executors)
+            .with_replanner(&replanner)
+            .with_approver(self.approver.as_ref())
+            .with_transaction(transaction)
+            .with_staged_files(world.filesystem.clone())
+            .run(&mut plan.tasks, &plan.strategy, &working_directory, world.version)?;
+
+        for (request, decision) in report.approvals {
+            let (approver, status, rejection_reason) = approval_fields(decision);
+            world.record_decision(Decision {
+                timestamp: self.clock.now(),
+                purpose: request.summary,
+                input: plan.goal.essential_question.clone(),
+                options: vec![request.details],
+                rationale: messages.task_rationale.clone(),
+                impact_scope: "1 task".to_string(),
+                alternatives: vec![],
+                revocation_conditions: vec![],
+                approver,
+                status,
+                rejection_reason,
+            });
+        }
+
+        let mut executed_tasks = Vec::new();
+        for (task_id, outcome) in report.completed {
+            world.filesystem.extend(outcome.files);
+            executed_tasks.push(format!("{}: {}", task_id, outcome.output));
         }
 
+        let mut failed_tasks = Vec::new();
+        for record in &report.records {
+            match &record.error {
+                None => world.context.history.push(format!(
+                    "Executed: {} - {}",
+                    record.task_id, record.description
+                )),
+                Some(error) => {
+                    world.context.history.push(format!(
+                        "Failed: {} - {} ({})",
+                        record.task_id, record.description, error
+                    ));
+                    failed_tasks.push(record.task_id.clone());
+                }
+            }
+        }
+        let mut usage = ResourceUsage::default();
+        for record in &report.records {
+            usage.add(&record.usage);
+        }
+        world.context.task_records.extend(report.records);
+
+        // 計画全体の予算を超えたら Θ を失敗させ、トランザクションを取り消す
+        let exceeded = plan.budget.exceeded(&usage);
+        if !exceeded.is_empty() {
+            return Err(SeizeError::BudgetExceeded(exceeded.join(", ")));
+        }
+
+        let message = if failed_tasks.is_empty() {
+            format!("{}個のタスクを実行しました", executed_tasks.len())
+        } else {
+            format!(
+                "{}個のタスクを実行しました（{}個失敗: {}）",
+                executed_tasks.len(),
+                failed_tasks.len(),
+                failed_tasks.join(", ")
+            )
+        };
+
         Ok(TransformationResult {
             phase: TransformationPhase::Execute,
-            success: true,
-            message: format!("{}個のタスクを実行しました", executed_tasks.len()),
+            success: failed_tasks.is_empty(),
+            message,
             artifacts: executed_tasks,
         })
     }
@@ -193,12 +573,17 @@ impl WorldTransformer {
         &self,
         _plan: &ExecutionPlan,
         world: &mut World,
-        execute_result: &TransformationResult,
-    ) -> anyhow::Result<TransformationResult> {
-        // 実行結果を世界に統合
+        results: &[TransformationResult],
+    ) -> SeizeResult<TransformationResult> {
+        // 実行結果（θ₄ を飛ばした場合はなし）を世界に統合
+        let artifacts = results
+            .iter()
+            .rev()
+            .find(|r| r.phase == TransformationPhase::Execute)
+            .map_or(0, |r| r.artifacts.len());
         let integration_summary = format!(
             "Integrated {} artifacts into World v{}",
-            execute_result.artifacts.len(),
+            artifacts,
             world.version
         );
 
@@ -216,54 +601,460 @@ impl WorldTransformer {
     }
 
     /// θ₆: Learn - 学習し次に活かす
+    ///
+    /// この反復で実行したタスクの成否と計画全体の成否を観測として、学習の信頼度を更新する。
+    /// 依存タスクの失敗・却下・予算切れで実行されなかったタスクは観測しない
     fn theta6_learn(
         &self,
         plan: &ExecutionPlan,
         world: &mut World,
-        integrate_result: &TransformationResult,
-    ) -> anyhow::Result<TransformationResult> {
-        // 学習内容を記録
-        let learning = Learning {
-            timestamp: chrono::Utc::now().to_rfc3339(),
-            content: format!(
-                "Goal: {} -> Result: {}",
-                plan.goal.description, integrate_result.message
-            ),
-            source: "UnifiedAgentFormula".to_string(),
-            confidence: 0.85,
-        };
+        results: &[TransformationResult],
+    ) -> SeizeResult<TransformationResult> {
+        let mut observations: Vec<Observation> = world
+            .context
+            .task_records
+            .iter()
+            .filter(|r| r.world_version == world.version && r.attempts > 0)
+            .map(|r| Observation {
+                statement: format!("{:?} task succeeds: {}", r.task_type, r.description),
+                source: "TaskOutcome".to_string(),
+                confirmed: r.status == TaskStatus::Completed,
+            })
+            .collect();
 
-        world.record_learning(learning);
+        // 計画全体の成否（θ₄ を飛ばした場合は観測しない）
+        if let Some(execute) = results.iter().rev().find(|r| r.phase == TransformationPhase::Execute) {
+            observations.push(Observation {
+                statement: format!("Goal: {} -> Plan succeeds", plan.goal.description),
+                source: "UnifiedAgentFormula".to_string(),
+                confirmed: execute.success,
+            });
+        }
+
+        let update = self
+            .learning
+            .update(&mut world.knowledge.learnings, &observations, self.clock.now());
 
         Ok(TransformationResult {
             phase: TransformationPhase::Learn,
             success: true,
-            message: format!("学習を記録しました (Total: {} learnings)", world.knowledge.learnings.len()),
-            artifacts: vec![],
+            message: format!(
+                "学習を更新しました (追加 {}, 確認 {}, 否定 {}, 忘却 {} / Total: {} learnings)",
+                update.added,
+                update.confirmed,
+                update.contradicted,
+                update.forgotten,
+                world.knowledge.learnings.len()
+            ),
+            artifacts: observations.iter().map(|o| o.statement.clone()).collect(),
         })
     }
 }
 
+/// 承認者の判断を意思決定の記録の（承認者, 状態, 理由）に変換
+fn approval_fields(decision: ApprovalDecision) -> (String, DecisionStatus, Option<String>) {
+    match decision {
+        ApprovalDecision::Approved { approver } => (approver, DecisionStatus::Approved, None),
+        ApprovalDecision::Rejected { approver, reason } => (approver, DecisionStatus::Rejected, Some(reason)),
+        ApprovalDecision::Pending => (
+            "(判断待ち)".to_string(),
+            DecisionStatus::Pending,
+            Some("承認者の判断待ち".to_string()),
+        ),
+    }
+}
+
 impl Default for WorldTransformer {
     fn default() -> Self {
         Self::new(true)
     }
 }
 
-// chrono依存を追加する必要があるため、簡易実装として標準ライブラリのみ使用
-// 実際の実装では chrono クレートを使用することを推奨
-mod chrono {
-    pub struct Utc;
-    impl Utc {
-        pub fn now() -> DateTime {
-            DateTime
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::budget::ResourceBudget;
+    use crate::command::{CommandStack, ExecutionStrategy, Task};
+    use crate::intent::{Goal, GoalCategory};
+
+    /// 承認をすべて自動で通す承認者
+    fn auto_approve() -> Arc<dyn Approver> {
+        Arc::new(PolicyApprover::new(ApprovalPolicy::AutoApprove))
+    }
+
+    fn plan_with(tasks: Vec<Task>) -> ExecutionPlan {
+        let goal = Goal {
+            essential_question: "何を残したいのか?".to_string(),
+            ..Goal::for_test("メモを書く", GoalCategory::CodeGeneration)
+        };
+        let mut plan = CommandStack::new().decompose(goal).unwrap();
+        plan.tasks = tasks;
+        plan
+    }
+
+    #[test]
+    fn test_execute_updates_filesystem_and_skips_failed_dependents() {
+        let dir = std::env::temp_dir().join(format!("seize-transform-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut world = World::new();
+        world.context.working_directory = dir.to_string_lossy().into_owned();
+
+        let plan = plan_with(vec![
+            Task::new("write", TaskType::FileWrite)
+                .with_parameters(&[("path", "memo.txt"), ("content", "hello")]),
+            Task::new("check", TaskType::Validation)
+                .with_dependencies(&["write"])
+                .with_parameters(&[("command", "grep -q hello memo.txt")]),
+            Task::new("broken", TaskType::Validation).with_parameters(&[("command", "exit 1")]),
+            Task::new("after", TaskType::Analysis).with_dependencies(&["broken"]),
+        ]);
+
+        let now = chrono::Utc::now();
+        let world = WorldTransformer::default()
+            .with_approver(auto_approve())
+            .with_clock(Arc::new(crate::clock::FixedClock::new(now)))
+            .apply(plan, world)
+            .unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(world.filesystem["memo.txt"], "hello");
+        assert!(world.knowledge.decisions.iter().all(|d| d.timestamp == now));
+        assert_eq!(world.knowledge.learnings[0].timestamp, now);
+        let history = &world.context.history;
+        assert!(history[0].starts_with("Executed: write"));
+        assert!(history[1].starts_with("Executed: check"));
+        assert!(history[2].starts_with("Failed: broken"));
+        assert!(history[3].starts_with("Failed: after"));
+        assert_eq!(world.context.task_records.len(), 4);
+        assert_eq!(world.context.task_records[3].attempts, 0);
+
+        // 実行したタスクと計画全体の成否を学習する（実行されなかった after は除く）
+        let learnings: Vec<(&str, f64)> = world
+            .knowledge
+            .learnings
+            .iter()
+            .map(|l| (l.content.as_str(), l.confidence))
+            .collect();
+        assert_eq!(learnings.len(), 4);
+        assert_eq!(learnings[2], ("Validation task succeeds: broken", 1.0 / 3.0));
+        assert_eq!(learnings[3], ("Goal: メモを書く -> Plan succeeds", 1.0 / 3.0));
+    }
+
+    #[test]
+    fn test_understand_adds_related_knowledge_to_prompts() {
+        let mut world = World::new();
+        for content in ["メモを書くときは日付を入れる", "Cargo のビルドが遅い"] {
+            world.knowledge.learnings.push(Learning {
+                timestamp: chrono::Utc::now(),
+                content: content.to_string(),
+                source: "Test".to_string(),
+                confidence: 0.9,
+                support: 0.0,
+                refute: 0.0,
+            });
+        }
+        let plan = plan_with(vec![
+            Task::new("look", TaskType::Analysis),
+            Task::new("list", TaskType::FileRead),
+        ]);
+
+        let mut results = Vec::new();
+        WorldTransformer::new(false)
+            .apply_observed(plan, world, &mut |result| results.push(result.clone()))
+            .unwrap();
+
+        let understand = &results[0];
+        assert!(understand.message.ends_with("1 related"));
+        assert_eq!(understand.artifacts[1], "学習: メモを書くときは日付を入れる (信頼度: 0.90)");
+
+        let prompts = &results[1].artifacts;
+        assert!(prompts[0].contains("## 関連する過去の知見\n- 学習: メモを書くときは日付を入れる"));
+        assert!(!prompts[1].contains("関連する過去の知見"));
+    }
+
+    #[test]
+    fn test_file_queue_approval_survives_recalled_knowledge() {
+        use crate::approval::FileQueueApprover;
+
+        let dir = std::env::temp_dir().join(format!("seize-transform-queue-{}", std::process::id()));
+        std::fs::remove_dir_all(&dir).ok();
+        let queue = Arc::new(FileQueueApprover::open(&dir).unwrap());
+        let transformer = WorldTransformer::new(false).with_approver(queue.clone());
+        let plan = plan_with(vec![
+            Task::new("decide", TaskType::Decision).with_parameters(&[("options", "A, B")]),
+        ]);
+
+        // 1回目の実行: 判断待ちになる
+        let mut world = transformer.apply(plan.clone(), World::new()).unwrap();
+        assert!(world.context.history[0].starts_with("Failed: decide"));
+        let pending = queue.pending().unwrap();
+        assert_eq!(pending.len(), 1);
+        queue
+            .respond(
+                &pending[0].id,
+                &ApprovalDecision::Approved {
+                    approver: "reviewer".to_string(),
+                },
+            )
+            .unwrap();
+
+        // 2回目の実行: 想起した知見でプロンプトが変わっても、記録した判断が見つかる
+        world.knowledge.learnings.push(Learning {
+            timestamp: chrono::Utc::now(),
+            content: "メモを書くときは日付を入れる".to_string(),
+            source: "Test".to_string(),
+            confidence: 0.9,
+            support: 0.0,
+            refute: 0.0,
+        });
+        let mut results = Vec::new();
+        let world = transformer
+            .apply_observed(plan, world, &mut |result| results.push(result.clone()))
+            .unwrap();
+        let pending = queue.pending().unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert!(results[1].artifacts[0].contains("関連する過去の知見"));
+        assert!(world.context.history.last().unwrap().starts_with("Executed: decide"));
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn test_learnings_are_confirmed_across_iterations() {
+        let plan = plan_with(vec![Task::new("look", TaskType::Analysis)]);
+        let transformer = WorldTransformer::new(false);
+
+        let mut world = World::new();
+        for _ in 0..3 {
+            world = transformer.apply(plan.clone(), world).unwrap();
+        }
+
+        let learnings = &world.knowledge.learnings;
+        assert_eq!(learnings.len(), 2);
+        assert!(learnings.iter().all(|l| l.support > 2.99 && l.refute == 0.0));
+        assert!(learnings.iter().all(|l| (l.confidence - 0.8).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_execute_rejects_cyclic_plan() {
+        let mut plan = plan_with(vec![
+            Task::new("a", TaskType::Analysis).with_dependencies(&["b"]),
+            Task::new("b", TaskType::Analysis).with_dependencies(&["a"]),
+        ]);
+        plan.strategy = ExecutionStrategy::Parallel;
+
+        assert!(matches!(
+            WorldTransformer::default().with_approver(auto_approve()).apply(plan, World::new()),
+            Err(SeizeError::CyclicDependency(_))
+        ));
+    }
+
+    #[test]
+    fn test_rejected_file_write_is_not_executed() {
+        use crate::approval::{ApprovalPolicy, PolicyApprover};
+
+        let dir = std::env::temp_dir().join(format!("seize-transform-deny-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut world = World::new();
+        world.context.working_directory = dir.to_string_lossy().into_owned();
+
+        let plan = plan_with(vec![
+            Task::new("write", TaskType::FileWrite)
+                .with_parameters(&[("path", "memo.txt"), ("content", "hello")]),
+            Task::new("read", TaskType::FileRead),
+        ]);
+
+        let world = WorldTransformer::new(false)
+            .with_approver(Arc::new(PolicyApprover::new(ApprovalPolicy::AutoDeny)))
+            .apply(plan, world)
+            .unwrap();
+        let written = dir.join("memo.txt").exists();
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert!(!written);
+        assert!(world.context.history[0].starts_with("Failed: write"));
+        assert!(world.context.history[1].starts_with("Executed: read"));
+        let decisions = &world.knowledge.decisions;
+        assert_eq!(decisions.len(), 1);
+        assert_eq!(decisions[0].status, DecisionStatus::Rejected);
+        assert!(decisions[0].purpose.contains("write"));
+    }
+
+    struct Review;
+
+    impl crate::phase::Phase for Review {
+        fn phase(&self) -> TransformationPhase {
+            TransformationPhase::Custom("Review".to_string())
+        }
+
+        fn run(&self, context: PhaseContext<'_>) -> SeizeResult<TransformationResult> {
+            let executed = context.results.iter().any(|r| r.phase == TransformationPhase::Execute);
+            context.world.context.history.push("Reviewed".to_string());
+            Ok(TransformationResult {
+                phase: self.phase(),
+                success: executed,
+                message: "レビューしました".to_string(),
+                artifacts: vec![],
+            })
         }
     }
-    pub struct DateTime;
-    impl DateTime {
-        pub fn to_rfc3339(&self) -> String {
-            use std::time::SystemTime;
-            format!("{:?}", SystemTime::now())
+
+    struct FixedAllocator;
+
+    impl crate::phase::Phase for FixedAllocator {
+        fn phase(&self) -> TransformationPhase {
+            TransformationPhase::Allocate
         }
+
+        fn run(&self, context: PhaseContext<'_>) -> SeizeResult<TransformationResult> {
+            context.plan.strategy = ExecutionStrategy::Parallel;
+            Ok(TransformationResult {
+                phase: self.phase(),
+                success: true,
+                message: "並列実行に固定しました".to_string(),
+                artifacts: vec![],
+            })
+        }
+    }
+
+    #[test]
+    fn test_custom_phase_pipeline() {
+        let pipeline = PhasePipeline::standard()
+            .replace(TransformationPhase::Allocate, Arc::new(FixedAllocator))
+            .unwrap()
+            .insert_after(TransformationPhase::Execute, Arc::new(Review))
+            .unwrap()
+            .skip_when(TransformationPhase::Generate, |plan| {
+                plan.tasks.iter().all(|t| t.task_type == TaskType::Analysis)
+            })
+            .unwrap();
+        assert!(pipeline.clone().insert_after(TransformationPhase::Learn, Arc::new(Review)).is_err());
+        assert!(PhasePipeline::standard()
+            .skip(TransformationPhase::Custom("Deploy".to_string()))
+            .is_err());
+
+        let plan = plan_with(vec![Task::new("look", TaskType::Analysis)]);
+        let mut phases = Vec::new();
+        let world = WorldTransformer::new(false)
+            .with_pipeline(pipeline)
+            .apply_observed(plan, World::new(), &mut |result| phases.push(result.phase.clone()))
+            .unwrap();
+
+        assert_eq!(
+            phases,
+            vec![
+                TransformationPhase::Understand,
+                TransformationPhase::Allocate,
+                TransformationPhase::Execute,
+                TransformationPhase::Custom("Review".to_string()),
+                TransformationPhase::Integrate,
+                TransformationPhase::Learn,
+            ]
+        );
+        assert_eq!(world.context.history.last().unwrap(), "Reviewed");
+        assert_eq!(world.knowledge.learnings[0].content, "Analysis task succeeds: look");
+        assert!(world.knowledge.learnings[1].content.ends_with("-> Plan succeeds"));
+    }
+
+    struct Explode;
+
+    impl crate::phase::Phase for Explode {
+        fn phase(&self) -> TransformationPhase {
+            TransformationPhase::Custom("Explode".to_string())
+        }
+
+        fn run(&self, context: PhaseContext<'_>) -> SeizeResult<TransformationResult> {
+            context.world.context.history.push("Exploded".to_string());
+            Err(SeizeError::ExecutionFailed("レビューに通りませんでした".to_string()))
+        }
+    }
+
+    #[test]
+    fn test_failed_phase_rolls_back_world_and_files() {
+        let dir = std::env::temp_dir().join(format!("seize-transform-rollback-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("kept.txt"), "original").unwrap();
+        let mut world = World::new();
+        world.context.working_directory = dir.to_string_lossy().into_owned();
+
+        let plan = plan_with(vec![
+            Task::new("overwrite", TaskType::FileWrite)
+                .with_parameters(&[("path", "kept.txt"), ("content", "changed")]),
+            Task::new("create", TaskType::FileWrite)
+                .with_dependencies(&["overwrite"])
+                .with_parameters(&[("path", "new/created.txt"), ("content", "new")]),
+        ]);
+        let pipeline = PhasePipeline::standard()
+            .insert_after(TransformationPhase::Execute, Arc::new(Explode))
+            .unwrap();
+
+        let rolled_back = WorldTransformer::new(false)
+            .with_approver(auto_approve())
+            .with_pipeline(pipeline)
+            .apply_transaction(plan, world, &mut |_| {})
+            .unwrap_err();
+        let kept = std::fs::read_to_string(dir.join("kept.txt")).unwrap();
+        let created = dir.join("new/created.txt").exists();
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(kept, "original");
+        assert!(!created);
+        assert_eq!(rolled_back.phase, TransformationPhase::Custom("Explode".to_string()));
+        assert!(matches!(rolled_back.error, SeizeError::ExecutionFailed(_)));
+
+        let world = rolled_back.world;
+        assert_eq!(world.version, 0);
+        assert!(world.filesystem.is_empty());
+        assert!(world.context.task_records.is_empty());
+        assert_eq!(world.context.history.len(), 1);
+        assert!(world.context.history[0].starts_with("RolledBack: Explode"));
+        assert_eq!(world.knowledge.learnings.len(), 1);
+        assert!(world.knowledge.learnings[0].content.contains("Rolled back: Explode failed"));
+    }
+
+    #[test]
+    fn test_plan_budget_is_allocated_and_enforced() {
+        let dir = std::env::temp_dir().join(format!("seize-transform-budget-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut world = World::new();
+        world.context.working_directory = dir.to_string_lossy().into_owned();
+
+        let mut plan = plan_with(vec![
+            Task::new("first", TaskType::FileWrite)
+                .with_parameters(&[("path", "first.txt"), ("content", "1")]),
+            Task::new("second", TaskType::FileWrite)
+                .with_parameters(&[("path", "second.txt"), ("content", "2")]),
+        ]);
+        plan.budget = ResourceBudget::unlimited().with_file_writes(1);
+
+        // θ₃ が書き込みを1回だけ配分し、2つ目の書き込みは実行しない
+        let allocated = WorldTransformer::new(false)
+            .with_approver(auto_approve())
+            .apply(plan.clone(), world.clone())
+            .unwrap();
+        let second_written = dir.join("second.txt").exists();
+        std::fs::remove_file(dir.join("first.txt")).ok();
+
+        assert!(!second_written);
+        assert_eq!(allocated.context.task_records[0].budget.file_writes, Some(1));
+        assert_eq!(allocated.context.task_records[1].budget.file_writes, Some(0));
+        assert_eq!(allocated.context.task_records[1].attempts, 0);
+
+        // 配分しなくても θ₄ が計画全体の予算を超えたことを検出し、書き込みを取り消す
+        let pipeline = PhasePipeline::standard().skip(TransformationPhase::Allocate).unwrap();
+        let rolled_back = WorldTransformer::new(false)
+            .with_approver(auto_approve())
+            .with_pipeline(pipeline)
+            .apply_transaction(plan, world, &mut |_| {})
+            .unwrap_err();
+        let written = dir.join("first.txt").exists() || dir.join("second.txt").exists();
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert!(!written);
+        assert_eq!(rolled_back.phase, TransformationPhase::Execute);
+        assert!(matches!(rolled_back.error, SeizeError::BudgetExceeded(_)));
+        assert!(rolled_back.world.context.task_records.is_empty());
+        assert_eq!(rolled_back.executed.len(), 2);
+        assert!(rolled_back.executed.iter().all(|r| r.usage.file_writes == 1));
     }
 }
@@ -6,6 +6,9 @@
 //! 世界は離散的な「景色」として瞬間的に捉えられ、
 //! 各認識サイクルで「瞬き」のように更新される。
 
+use crate::budget::{ResourceBudget, ResourceUsage};
+use crate::clock::Timestamp;
+use crate::command::{TaskStatus, TaskType};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -45,6 +48,36 @@ pub struct ExecutionContext {
 
     /// 実行履歴
     pub history: Vec<String>,
+
+    /// タスクごとの実行記録
+    #[serde(default)]
+    pub task_records: Vec<TaskRecord>,
+}
+
+/// タスクの実行記録
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskRecord {
+    /// 実行時の世界のバージョン
+    pub world_version: usize,
+    pub task_id: String,
+    pub description: String,
+    pub task_type: TaskType,
+    /// 最終的な状態
+    pub status: TaskStatus,
+    /// 試行回数（依存タスクの失敗で実行されなかった場合は0）
+    pub attempts: u32,
+    /// 最後の試行の所要時間（ミリ秒）
+    pub duration_ms: u64,
+    /// 出力の要約
+    pub summary: String,
+    /// 失敗理由
+    pub error: Option<String>,
+    /// 配分された予算
+    #[serde(default)]
+    pub budget: ResourceBudget,
+    /// 資源の使用量（すべての試行の合計）
+    #[serde(default)]
+    pub usage: ResourceUsage,
 }
 
 /// 知識ベース
@@ -79,16 +112,27 @@ pub enum PrincipleCategory {
 /// 学習した知見
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Learning {
-    pub timestamp: String,
+    /// 記録した時刻（確認・否定されるたびに更新する）
+    #[serde(deserialize_with = "crate::clock::deserialize_timestamp")]
+    pub timestamp: Timestamp,
     pub content: String,
     pub source: String,
     pub confidence: f64,
+
+    /// 確認された重み（`timestamp` の時点、時間とともに減衰する）
+    #[serde(default)]
+    pub support: f64,
+
+    /// 否定された重み（`timestamp` の時点、時間とともに減衰する）
+    #[serde(default)]
+    pub refute: f64,
 }
 
 /// 意思決定の記録
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Decision {
-    pub timestamp: String,
+    #[serde(deserialize_with = "crate::clock::deserialize_timestamp")]
+    pub timestamp: Timestamp,
     pub purpose: String,
     pub input: String,
     pub options: Vec<String>,
@@ -97,6 +141,23 @@ pub struct Decision {
     pub impact_scope: String,
     pub alternatives: Vec<String>,
     pub revocation_conditions: Vec<String>,
+
+    /// 承認状態（旧形式のスナップショットは承認済みとみなす）
+    #[serde(default)]
+    pub status: DecisionStatus,
+
+    /// 却下・判断待ちの理由
+    #[serde(default)]
+    pub rejection_reason: Option<String>,
+}
+
+/// 意思決定の承認状態
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DecisionStatus {
+    #[default]
+    Approved,
+    Rejected,
+    Pending,
 }
 
 impl World {
@@ -139,6 +200,7 @@ impl Default for ExecutionContext {
             working_directory: String::from("."),
             environment: HashMap::new(),
             history: Vec::new(),
+            task_records: Vec::new(),
         }
     }
 }
@@ -146,37 +208,9 @@ impl Default for ExecutionContext {
 impl Default for KnowledgeBase {
     fn default() -> Self {
         Self {
-            principles: Self::load_charter_principles(),
+            principles: crate::charter::Charter::builtin().principles,
             learnings: Vec::new(),
             decisions: Vec::new(),
         }
     }
 }
-
-impl KnowledgeBase {
-    /// 憲章から原則を読み込む
-    fn load_charter_principles() -> Vec<Principle> {
-        vec![
-            Principle {
-                name: "人間は判断する".to_string(),
-                description: "AIは情報を示す。最終的な選択と責任は人間にある".to_string(),
-                category: PrincipleCategory::Human,
-            },
-            Principle {
-                name: "意図を持って問いを立てる".to_string(),
-                description: "正しい答えよりも、正しい問いを生み出す力を尊ぶ".to_string(),
-                category: PrincipleCategory::Human,
-            },
-            Principle {
-                name: "公正と透明性".to_string(),
-                description: "意思決定の記録を保存し、説明可能性を維持する".to_string(),
-                category: PrincipleCategory::Organization,
-            },
-            Principle {
-                name: "目的の明確化".to_string(),
-                description: "利用前に「何を・なぜ・どのように」使うかを定義する".to_string(),
-                category: PrincipleCategory::AIUtilization,
-            },
-        ]
-    }
-}